@@ -3,6 +3,7 @@
 
 #include "Color.h"
 
+#include <algorithm>
 #include <format>
 
 #include "cxx-rust-cssparser-impl/src/ffi.rs.h"
@@ -107,30 +108,18 @@ std::string SetOperationData::toString() const
     return std::format("SetOperationData(r: {}, g: {}, b: {}, a: {})", m_r.value_or(-1), m_g.value_or(-1), m_b.value_or(-1), m_a.value_or(-1));
 }
 
-SetOperationData SetOperationData::fromRust(const rust::SetColorOperationValues &rustData)
+// A negative channel means the channel should not be set, see SetColorOperationValues.
+inline std::optional<uint8_t> decodeSetChannel(int16_t channel)
 {
-    auto result = SetOperationData{};
-    if (rustData.r < 0) {
-        result.m_r = std::nullopt;
-    } else {
-        result.m_r = uint8_t(rustData.r);
-    }
-    if (rustData.g < 0) {
-        result.m_g = std::nullopt;
-    } else {
-        result.m_g = uint8_t(rustData.g);
-    }
-    if (rustData.b < 0) {
-        result.m_b = std::nullopt;
-    } else {
-        result.m_b = uint8_t(rustData.b);
+    if (channel < 0) {
+        return std::nullopt;
     }
-    if (rustData.a < 0) {
-        result.m_a = std::nullopt;
-    } else {
-        result.m_a = uint8_t(rustData.a);
-    }
-    return result;
+    return uint8_t(std::min(channel, int16_t(255)));
+}
+
+SetOperationData SetOperationData::fromRust(const rust::SetColorOperationValues &rustData)
+{
+    return SetOperationData{decodeSetChannel(rustData.r), decodeSetChannel(rustData.g), decodeSetChannel(rustData.b), decodeSetChannel(rustData.a)};
 }
 
 ModifiedColorData::ModifiedColorData()
@@ -275,6 +264,13 @@ Color Color::fromRust(const ::rust::cxxbridge1::Box<rust::Color> &color)
         break;
     }
 
+    try {
+        result.m_resolved = RgbaData{color->red(), color->green(), color->blue(), color->alpha()};
+        result.m_luminance = color->luminance();
+    } catch (const std::exception &) {
+        // Custom colors can only be resolved by the application.
+    }
+
     return result;
 }
 
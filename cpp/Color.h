@@ -151,6 +151,9 @@ private:
  * \class cssparser::Color::SetOperationData
  *
  * \brief The data required for a "set" color operation.
+ *
+ * Each channel, including alpha, is in the range 0-255. A channel without
+ * value is left unchanged.
  */
 class CSSPARSER_EXPORT SetOperationData
 {
@@ -281,6 +284,24 @@ public:
     {
         return std::get<T>(m_data);
     }
+    /*!
+     * Returns this color resolved to plain RGBA values, with any modifications applied.
+     *
+     * Returns an empty optional if the color is empty or depends on a custom color.
+     */
+    inline std::optional<RgbaData> resolved() const
+    {
+        return m_resolved;
+    }
+    /*!
+     * Returns the relative luminance of this color, between 0.0 and 1.0.
+     *
+     * Returns an empty optional if the color cannot be resolved.
+     */
+    inline std::optional<float> luminance() const
+    {
+        return m_luminance;
+    }
     /*!
      * Returns a string representation of this Color.
      */
@@ -292,6 +313,8 @@ public:
 private:
     Type m_type = Type::Empty;
     std::variant<std::nullopt_t, RgbaData, CustomColorData, ModifiedColorData> m_data = std::nullopt;
+    std::optional<RgbaData> m_resolved;
+    std::optional<float> m_luminance;
 };
 
 }
@@ -12,9 +12,10 @@ using namespace std::string_literals;
 
 using namespace cssparser;
 
-Property::Property(const std::string &name, const std::vector<Value> &values)
+Property::Property(const std::string &name, const std::vector<Value> &values, bool important)
     : m_name(name)
     , m_values(values)
+    , m_important(important)
 {
 }
 
@@ -25,7 +26,113 @@ Property Property::fromRust(const rust::Property &rustData)
         values.push_back(Value::fromRust(rustValue));
     }
 
-    return Property{std::string(rustData.name()), values};
+    auto property = Property{std::string(rustData.name()), values, rustData.important()};
+    property.m_flag = std::string(rustData.flag());
+    return property;
+}
+
+std::vector<std::string> cssparser::acceptedPropertyTypes(const std::string &name)
+{
+    std::vector<std::string> result;
+    for (const auto &entry : rust::property_accepted_types(name)) {
+        result.push_back(std::string(entry));
+    }
+    return result;
+}
+
+std::string cssparser::propertySyntax(const std::string &name)
+{
+    return std::string(rust::property_syntax(name));
+}
+
+std::string cssparser::propertyFunctionSyntax(const std::string &name)
+{
+    return std::string(rust::property_function_syntax(name));
+}
+
+std::vector<std::string> cssparser::registeredPropertyNames()
+{
+    std::vector<std::string> result;
+    for (const auto &entry : rust::registered_property_names()) {
+        result.push_back(std::string(entry));
+    }
+    return result;
+}
+
+PropertyDefinition PropertyDefinition::fromRust(const rust::PropertyDefinition &rustData)
+{
+    auto result = PropertyDefinition{};
+    result.m_name = std::string(rustData.name());
+    result.m_syntax = std::string(rustData.syntax_string());
+    result.m_inherits = rustData.inherits();
+    result.m_css = std::string(rustData.to_css());
+
+    for (const auto &rustValue : rustData.initial_values()) {
+        result.m_initialValues.push_back(Value::fromRust(rustValue));
+    }
+
+    return result;
+}
+
+std::optional<PropertyDefinition> cssparser::propertyDefinition(const std::string &name)
+{
+    try {
+        auto definition = rust::property_definition(name);
+        return PropertyDefinition::fromRust(*definition);
+    } catch (const std::exception &) {
+        return std::nullopt;
+    }
+}
+
+std::vector<Value> cssparser::parsePropertyValue(const std::string &name, const std::string &value, Error *error)
+{
+    std::vector<Value> result;
+    try {
+        for (const auto &rustValue : rust::parse_property_value(name, value)) {
+            result.push_back(Value::fromRust(rustValue));
+        }
+    } catch (const std::exception &e) {
+        if (error) {
+            *error = Error{
+                .message = e.what(),
+            };
+        }
+    }
+    return result;
+}
+
+std::vector<BackgroundLayer> cssparser::parseBackground(const std::string &value, Error *error)
+{
+    std::vector<BackgroundLayer> result;
+    try {
+        for (const auto &rustLayer : rust::parse_background(value)) {
+            result.push_back(BackgroundLayer::fromRust(rustLayer));
+        }
+    } catch (const std::exception &e) {
+        if (error) {
+            *error = Error{
+                .message = e.what(),
+            };
+        }
+    }
+    return result;
+}
+
+std::vector<GridTrack> cssparser::parseTrackList(const std::string &value, Error *error)
+{
+    std::vector<GridTrack> result;
+    try {
+        for (const auto &rustTrack : rust::parse_track_list(value)) {
+            result.push_back(GridTrack::fromRust(rustTrack));
+        }
+    } catch (const std::exception &e) {
+        if (error) {
+            *error = Error{
+                .message = e.what(),
+            };
+        }
+    }
+    return result;
 }
 
 Rule::Rule()
@@ -47,9 +154,168 @@ Rule Rule::fromRust(const rust::StyleRule &rule)
         result.m_properties.push_back(Property::fromRust(property));
     }
 
+    result.m_media = std::shared_ptr<rust::MediaQuery>(rule.media().into_raw(), [](rust::MediaQuery *media) {
+        ::rust::Box<rust::MediaQuery>::from_raw(media);
+    });
+
+    result.m_layer = std::string(rule.layer());
+
+    return result;
+}
+
+bool Rule::matchesMedia(const MediaContext &context) const
+{
+    if (!m_media) {
+        return true;
+    }
+
+    return m_media->evaluate(rust::MediaContext{
+        .media_type = context.mediaType,
+        .width = context.width,
+        .height = context.height,
+        .device_pixel_ratio = context.devicePixelRatio,
+        .color_scheme = context.colorScheme,
+    });
+}
+
+Keyframe Keyframe::fromRust(const rust::Keyframe &rustData)
+{
+    auto result = Keyframe{};
+
+    for (auto offset : rustData.offsets()) {
+        result.m_offsets.push_back(offset);
+    }
+
+    for (const auto &property : rustData.properties()) {
+        result.m_properties.push_back(Property::fromRust(property));
+    }
+
+    return result;
+}
+
+Keyframes Keyframes::fromRust(const rust::Keyframes &rustData)
+{
+    auto result = Keyframes{};
+    result.m_name = std::string(rustData.name());
+
+    for (const auto &stop : rustData.stops()) {
+        result.m_stops.push_back(Keyframe::fromRust(stop));
+    }
+
+    return result;
+}
+
+FontFace FontFace::fromRust(const rust::FontFace &rustData)
+{
+    auto result = FontFace{};
+    result.m_family = std::string(rustData.family());
+
+    for (const auto &source : rustData.sources()) {
+        result.m_sources.push_back(Source{std::string(source.url), std::string(source.format)});
+    }
+
+    for (const auto &range : rustData.unicode_ranges()) {
+        result.m_unicodeRanges.push_back(UnicodeRange{range.start, range.end});
+    }
+
+    return result;
+}
+
+BackgroundLayer BackgroundLayer::fromRust(const rust::BackgroundLayer &rustData)
+{
+    auto result = BackgroundLayer{};
+    result.m_image = std::string(rustData.image());
+
+    for (const auto &repeat : rustData.repeat()) {
+        result.m_repeat.push_back(std::string(repeat));
+    }
+
+    for (const auto &value : rustData.position()) {
+        result.m_position.push_back(Value::fromRust(value));
+    }
+
+    for (const auto &value : rustData.size()) {
+        result.m_size.push_back(Value::fromRust(value));
+    }
+
+    result.m_attachment = std::string(rustData.attachment());
+    result.m_origin = std::string(rustData.origin());
+    result.m_clip = std::string(rustData.clip());
+    result.m_color = Color::Color::fromRust(rustData.color());
+
+    return result;
+}
+
+GridTrack GridTrack::fromRust(const rust::GridTrack &rustData)
+{
+    auto result = GridTrack{};
+
+    switch (rustData.track_type()) {
+    case rust::GridTrackType::Breadth:
+        result.m_type = Type::Breadth;
+        result.m_breadth = Dimension::fromRust(rustData.breadth());
+        break;
+    case rust::GridTrackType::Keyword:
+        result.m_type = Type::Keyword;
+        result.m_keyword = std::string(rustData.keyword());
+        break;
+    case rust::GridTrackType::MinMax:
+        result.m_type = Type::MinMax;
+        result.m_minimum = std::make_shared<const GridTrack>(GridTrack::fromRust(*rustData.minimum()));
+        result.m_maximum = std::make_shared<const GridTrack>(GridTrack::fromRust(*rustData.maximum()));
+        break;
+    case rust::GridTrackType::FitContent:
+        result.m_type = Type::FitContent;
+        result.m_breadth = Dimension::fromRust(rustData.breadth());
+        break;
+    }
+
     return result;
 }
 
+inline Error::Kind convertErrorKind(cssparser::rust::ParseErrorKind kind)
+{
+    switch (kind) {
+    case cssparser::rust::ParseErrorKind::Unspecified:
+        return Error::Kind::Unspecified;
+    case cssparser::rust::ParseErrorKind::Unimplemented:
+        return Error::Kind::Unimplemented;
+    case cssparser::rust::ParseErrorKind::UnexpectedEndOfInput:
+        return Error::Kind::UnexpectedEndOfInput;
+    case cssparser::rust::ParseErrorKind::Unknown:
+        return Error::Kind::Unknown;
+    case cssparser::rust::ParseErrorKind::UnknownProperty:
+        return Error::Kind::UnknownProperty;
+    case cssparser::rust::ParseErrorKind::UnexpectedToken:
+        return Error::Kind::UnexpectedToken;
+    case cssparser::rust::ParseErrorKind::InvalidSelectors:
+        return Error::Kind::InvalidSelectors;
+    case cssparser::rust::ParseErrorKind::InvalidPropertySyntax:
+        return Error::Kind::InvalidPropertySyntax;
+    case cssparser::rust::ParseErrorKind::InvalidPropertyValue:
+        return Error::Kind::InvalidPropertyValue;
+    case cssparser::rust::ParseErrorKind::UnknownFunction:
+        return Error::Kind::UnknownFunction;
+    case cssparser::rust::ParseErrorKind::InvalidPropertyDefinition:
+        return Error::Kind::InvalidPropertyDefinition;
+    case cssparser::rust::ParseErrorKind::PropertyValueDoesNotMatchSyntax:
+        return Error::Kind::PropertyValueDoesNotMatchSyntax;
+    case cssparser::rust::ParseErrorKind::UnsupportedAtRule:
+        return Error::Kind::UnsupportedAtRule;
+    case cssparser::rust::ParseErrorKind::InvalidAtRule:
+        return Error::Kind::InvalidAtRule;
+    case cssparser::rust::ParseErrorKind::InvalidQualifiedRule:
+        return Error::Kind::InvalidQualifiedRule;
+    case cssparser::rust::ParseErrorKind::FileError:
+        return Error::Kind::FileError;
+    case cssparser::rust::ParseErrorKind::StyleSheetParseError:
+        return Error::Kind::StyleSheetParseError;
+    }
+
+    assert(false && "Mismatch between error kinds in C++ and Rust, update C++ code!");
+    return Error::Kind::Unspecified;
+}
+
 struct StyleSheet::Private
 {
     void update();
@@ -60,6 +326,12 @@ struct StyleSheet::Private
     std::vector<Rule> rules;
     std::vector<Error> errors;
     std::vector<std::filesystem::path> paths;
+    std::vector<std::string> layers;
+    std::vector<Keyframes> keyframes;
+    std::vector<FontFace> fontFaces;
+    std::vector<Property> variables;
+    std::vector<Error> variableErrors;
+    std::vector<ParseStats> parseStats;
 };
 
 StyleSheet::StyleSheet(const std::filesystem::path &path)
@@ -83,11 +355,51 @@ std::span<const Error> StyleSheet::errors() const
     return std::span<const Error>(d->errors.cbegin(), d->errors.cend());
 }
 
+std::string StyleSheet::errorsJson() const
+{
+    return std::string(d->stylesheet->errors_json());
+}
+
 std::span<const std::filesystem::path> StyleSheet::paths() const
 {
     return std::span<const std::filesystem::path>(d->paths.cbegin(), d->paths.cend());
 }
 
+std::span<const std::string> StyleSheet::layers() const
+{
+    return std::span<const std::string>(d->layers.cbegin(), d->layers.cend());
+}
+
+std::span<const Keyframes> StyleSheet::keyframes() const
+{
+    return std::span<const Keyframes>(d->keyframes.cbegin(), d->keyframes.cend());
+}
+
+std::span<const FontFace> StyleSheet::fontFaces() const
+{
+    return std::span<const FontFace>(d->fontFaces.cbegin(), d->fontFaces.cend());
+}
+
+std::span<const Property> StyleSheet::variables() const
+{
+    return std::span<const Property>(d->variables.cbegin(), d->variables.cend());
+}
+
+std::span<const Error> StyleSheet::variableErrors() const
+{
+    return std::span<const Error>(d->variableErrors.cbegin(), d->variableErrors.cend());
+}
+
+std::span<const ParseStats> StyleSheet::parseStats() const
+{
+    return std::span<const ParseStats>(d->parseStats.cbegin(), d->parseStats.cend());
+}
+
+void StyleSheet::setRecordStats(bool enabled)
+{
+    d->stylesheet->set_record_stats(enabled);
+}
+
 void StyleSheet::parse()
 {
     try {
@@ -98,6 +410,7 @@ void StyleSheet::parse()
             .line = 0,
             .column = 0,
             .message = e.what(),
+            .kind = Error::Kind::FileError,
         });
 
         return;
@@ -112,6 +425,35 @@ void StyleSheet::parseString(const std::string &source)
     d->update();
 }
 
+void StyleSheet::parseBytes(std::span<const char> data)
+{
+    try {
+        d->stylesheet->parse_bytes(::rust::Slice<const uint8_t>(reinterpret_cast<const uint8_t *>(data.data()), data.size()));
+    } catch (const std::exception &e) {
+        d->errors.push_back(Error{
+            .file = d->path,
+            .line = 0,
+            .column = 0,
+            .message = e.what(),
+            .kind = Error::Kind::FileError,
+        });
+
+        return;
+    }
+
+    d->update();
+}
+
+std::vector<Property> StyleSheet::parseInline(const std::string &declarations)
+{
+    std::vector<Property> result;
+    for (const auto &property : d->stylesheet->parse_inline(declarations)) {
+        result.push_back(Property::fromRust(property));
+    }
+    d->update();
+    return result;
+}
+
 void cssparser::StyleSheet::import(const std::filesystem::path &path)
 {
     d->stylesheet->import_file(path.string());
@@ -132,6 +474,7 @@ void StyleSheet::Private::update()
             .line = entry.line,
             .column = entry.column,
             .message = std::string(entry.message),
+            .kind = convertErrorKind(entry.kind),
         });
     }
 
@@ -139,4 +482,46 @@ void StyleSheet::Private::update()
     for (const auto &entry : stylesheet->paths()) {
         paths.push_back(std::filesystem::path(std::string(entry)));
     }
+
+    layers.clear();
+    for (const auto &entry : stylesheet->layers()) {
+        layers.push_back(std::string(entry));
+    }
+
+    keyframes.clear();
+    for (const auto &entry : stylesheet->keyframes()) {
+        keyframes.push_back(Keyframes::fromRust(entry));
+    }
+
+    fontFaces.clear();
+    for (const auto &entry : stylesheet->font_faces()) {
+        fontFaces.push_back(FontFace::fromRust(entry));
+    }
+
+    variables.clear();
+    for (const auto &entry : stylesheet->variables()) {
+        variables.push_back(Property::fromRust(entry));
+    }
+
+    variableErrors.clear();
+    for (const auto &entry : stylesheet->variable_errors()) {
+        variableErrors.push_back(Error{
+            .file = std::string(entry.file),
+            .line = entry.line,
+            .column = entry.column,
+            .message = std::string(entry.message),
+            .kind = convertErrorKind(entry.kind),
+        });
+    }
+
+    parseStats.clear();
+    for (const auto &entry : stylesheet->parse_stats()) {
+        parseStats.push_back(ParseStats{
+            .path = std::filesystem::path(std::string(entry.path)),
+            .rules = entry.rules,
+            .errors = entry.errors,
+            .bytes = entry.bytes,
+            .duration = std::chrono::microseconds(entry.duration_microseconds),
+        });
+    }
 }
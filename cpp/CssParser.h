@@ -3,7 +3,10 @@
 
 #pragma once
 
+#include <chrono>
 #include <filesystem>
+#include <memory>
+#include <optional>
 
 #include "Selector.h"
 
@@ -14,9 +17,29 @@ namespace cssparser
 namespace rust
 {
 struct Property;
+struct PropertyDefinition;
 struct StyleRule;
+struct MediaQuery;
+struct Keyframe;
+struct Keyframes;
+struct FontFace;
+struct BackgroundLayer;
+struct GridTrack;
 }
 
+/*!
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief A struct describing the output device that media queries are evaluated against.
+ */
+struct CSSPARSER_EXPORT MediaContext {
+    std::string mediaType = "screen";
+    float width = 0.0;
+    float height = 0.0;
+    float devicePixelRatio = 1.0;
+    std::string colorScheme = "light";
+};
+
 /*!
  * \class cssparser::Property
  * \inmodule cxx-rust-cssparser
@@ -30,8 +53,9 @@ public:
      * Constructor.
      *
      * Constructs a new Property with  \a name as name and \a values as values.
+     * \a important indicates whether the property was declared with `!important`.
      */
-    Property(const std::string &name, const std::vector<Value> &values);
+    Property(const std::string &name, const std::vector<Value> &values, bool important = false);
     /*!
      * Returns the name of this Property.
      */
@@ -61,6 +85,24 @@ public:
     {
         return m_values.at(index);
     }
+    /*!
+     * Returns whether this Property was declared with `!important`.
+     */
+    inline bool important() const
+    {
+        return m_important;
+    }
+    /*!
+     * Returns the identifier of the `!` flag this Property was declared with.
+     *
+     * This is \c important for `!important`, or another identifier like
+     * \c default if unknown flags are allowed. Returns an empty string if
+     * the Property has no flag.
+     */
+    inline std::string flag() const
+    {
+        return m_flag;
+    }
 
     // Internal. Convert from a rust Property to a C++ Property.
     static Property fromRust(const rust::Property &rustData);
@@ -68,8 +110,103 @@ public:
 private:
     std::string m_name;
     std::vector<Value> m_values;
+    bool m_important = false;
+    std::string m_flag;
+};
+
+/*!
+ * Returns the data types and keywords accepted by the property named \a name.
+ *
+ * Data types are returned in syntax form, for example \c{<length>}. Returns an
+ * empty list if no property with \a name has been defined.
+ */
+CSSPARSER_EXPORT std::vector<std::string> acceptedPropertyTypes(const std::string &name);
+
+/*!
+ * Returns the syntax of the property named \a name, like \c{<length> | auto}.
+ *
+ * Returns an empty string if no property with \a name has been defined.
+ */
+CSSPARSER_EXPORT std::string propertySyntax(const std::string &name);
+
+/*!
+ * Returns the syntax of the arguments of the function named \a name, like \c{<color>, <color>, <number>}.
+ *
+ * Returns an empty string if there is no function with \a name or it does not
+ * describe its arguments with a syntax.
+ */
+CSSPARSER_EXPORT std::string propertyFunctionSyntax(const std::string &name);
+
+/*!
+ * Returns the names of all properties that have been defined, sorted by name.
+ *
+ * This includes built-in properties and properties defined using \c{@property}.
+ */
+CSSPARSER_EXPORT std::vector<std::string> registeredPropertyNames();
+
+/*!
+ * \class cssparser::PropertyDefinition
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief The definition of a property, either built in or added using \c{@property}.
+ */
+class CSSPARSER_EXPORT PropertyDefinition
+{
+public:
+    /*!
+     * Returns the name of the defined property.
+     */
+    inline std::string name() const
+    {
+        return m_name;
+    }
+    /*!
+     * Returns the syntax of the property, like \c{<length> | auto}.
+     */
+    inline std::string syntax() const
+    {
+        return m_syntax;
+    }
+    /*!
+     * Returns whether the property inherits its value by default.
+     */
+    inline bool inherits() const
+    {
+        return m_inherits;
+    }
+    /*!
+     * Returns a view on the initial values of the property.
+     */
+    inline std::span<const Value> initialValues() const
+    {
+        return std::span<const Value>(m_initialValues.cbegin(), m_initialValues.cend());
+    }
+    /*!
+     * Returns this definition as an \c{@property} rule.
+     */
+    inline std::string toCss() const
+    {
+        return m_css;
+    }
+
+    // Internal. Convert from a rust PropertyDefinition to a C++ PropertyDefinition.
+    static PropertyDefinition fromRust(const rust::PropertyDefinition &rustData);
+
+private:
+    std::string m_name;
+    std::string m_syntax;
+    bool m_inherits = false;
+    std::vector<Value> m_initialValues;
+    std::string m_css;
 };
 
+/*!
+ * Returns the definition of the property named \a name.
+ *
+ * Returns an empty optional if no property with \a name has been defined.
+ */
+CSSPARSER_EXPORT std::optional<PropertyDefinition> propertyDefinition(const std::string &name);
+
 /*!
  * \class cssparser::Rule
  * \inmodule cxx-rust-cssparser
@@ -103,6 +240,21 @@ public:
     {
         return std::span<const Property>(m_properties.cbegin(), m_properties.cend());
     }
+    /*!
+     * Returns whether the media query of this Rule matches \a context.
+     *
+     * Rules that are not contained in an \c{@media} rule always match.
+     */
+    bool matchesMedia(const MediaContext &context) const;
+    /*!
+     * Returns the name of the cascade layer containing this Rule.
+     *
+     * Returns an empty string if this Rule is not part of a layer.
+     */
+    inline std::string layer() const
+    {
+        return m_layer;
+    }
 
     // Internal. Convert from a rust StyleRule to a C++ Rule.
     static Rule fromRust(const rust::StyleRule &rustData);
@@ -110,6 +262,297 @@ public:
 private:
     Selector m_selector;
     std::vector<Property> m_properties;
+    std::shared_ptr<rust::MediaQuery> m_media;
+    std::string m_layer;
+};
+
+/*!
+ * \class cssparser::Keyframe
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief A single stop of a \c{@keyframes} rule.
+ */
+class CSSPARSER_EXPORT Keyframe
+{
+public:
+    /*!
+     * Returns the offsets this stop applies to, between 0.0 and 1.0.
+     *
+     * \c{from} is 0.0 and \c{to} is 1.0.
+     */
+    inline std::span<const float> offsets() const
+    {
+        return std::span<const float>(m_offsets.cbegin(), m_offsets.cend());
+    }
+    /*!
+     * Returns the properties that apply at the offsets of this stop.
+     */
+    inline std::span<const Property> properties() const
+    {
+        return std::span<const Property>(m_properties.cbegin(), m_properties.cend());
+    }
+
+    // Internal. Convert from a rust Keyframe to a C++ Keyframe.
+    static Keyframe fromRust(const rust::Keyframe &rustData);
+
+private:
+    std::vector<float> m_offsets;
+    std::vector<Property> m_properties;
+};
+
+/*!
+ * \class cssparser::Keyframes
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief An animation defined by a \c{@keyframes} rule.
+ */
+class CSSPARSER_EXPORT Keyframes
+{
+public:
+    /*!
+     * Returns the name of this animation.
+     */
+    inline std::string name() const
+    {
+        return m_name;
+    }
+    /*!
+     * Returns the stops of this animation, in source order.
+     */
+    inline std::span<const Keyframe> stops() const
+    {
+        return std::span<const Keyframe>(m_stops.cbegin(), m_stops.cend());
+    }
+
+    // Internal. Convert from a rust Keyframes to a C++ Keyframes.
+    static Keyframes fromRust(const rust::Keyframes &rustData);
+
+private:
+    std::string m_name;
+    std::vector<Keyframe> m_stops;
+};
+
+/*!
+ * \class cssparser::FontFace
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief A font defined by a \c{@font-face} rule.
+ */
+class CSSPARSER_EXPORT FontFace
+{
+public:
+    /*!
+     * \class cssparser::FontFace::Source
+     * \inmodule cxx-rust-cssparser
+     *
+     * \brief A single entry of the \c{src} descriptor.
+     */
+    struct Source {
+        /*!
+         * The URL of the font file, relative to the style sheet it was defined in.
+         */
+        std::string url;
+        /*!
+         * The hint given by \c{format()}, or an empty string if there was none.
+         */
+        std::string format;
+    };
+
+    /*!
+     * Returns the name of the font family.
+     */
+    inline std::string family() const
+    {
+        return m_family;
+    }
+    /*!
+     * Returns the sources of this font, in order of preference.
+     */
+    inline std::span<const Source> sources() const
+    {
+        return std::span<const Source>(m_sources.cbegin(), m_sources.cend());
+    }
+    /*!
+     * Returns the ranges of code points supported by this font.
+     *
+     * Empty if the rule has no \c{unicode-range} descriptor.
+     */
+    inline std::span<const UnicodeRange> unicodeRanges() const
+    {
+        return std::span<const UnicodeRange>(m_unicodeRanges.cbegin(), m_unicodeRanges.cend());
+    }
+
+    // Internal. Convert from a rust FontFace to a C++ FontFace.
+    static FontFace fromRust(const rust::FontFace &rustData);
+
+private:
+    std::string m_family;
+    std::vector<Source> m_sources;
+    std::vector<UnicodeRange> m_unicodeRanges;
+};
+
+/*!
+ * \class cssparser::BackgroundLayer
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief A single comma-separated layer of the \c{background} shorthand.
+ */
+class CSSPARSER_EXPORT BackgroundLayer
+{
+public:
+    /*!
+     * Returns the URL of the image, or an empty string if the layer has no image.
+     */
+    inline std::string image() const
+    {
+        return m_image;
+    }
+    /*!
+     * Returns the repeat keywords of this layer.
+     */
+    inline std::span<const std::string> repeat() const
+    {
+        return std::span<const std::string>(m_repeat.cbegin(), m_repeat.cend());
+    }
+    /*!
+     * Returns the values of the position of this layer.
+     */
+    inline std::span<const Value> position() const
+    {
+        return std::span<const Value>(m_position.cbegin(), m_position.cend());
+    }
+    /*!
+     * Returns the values following the \c{/} after the position.
+     */
+    inline std::span<const Value> size() const
+    {
+        return std::span<const Value>(m_size.cbegin(), m_size.cend());
+    }
+    /*!
+     * Returns the attachment of this layer, one of \c{fixed}, \c{scroll} or
+     * \c{local}, or an empty string if the layer does not specify one.
+     */
+    inline std::string attachment() const
+    {
+        return m_attachment;
+    }
+    /*!
+     * Returns the origin box of this layer, or an empty string if the layer
+     * does not specify one.
+     */
+    inline std::string origin() const
+    {
+        return m_origin;
+    }
+    /*!
+     * Returns the clip box of this layer, or an empty string if the layer
+     * does not specify one.
+     *
+     * If only a single box was specified, it is used for both origin and clip.
+     */
+    inline std::string clip() const
+    {
+        return m_clip;
+    }
+    /*!
+     * Returns the background color, or an empty color if the layer has none.
+     *
+     * Only the final layer can have a color.
+     */
+    inline Color::Color color() const
+    {
+        return m_color;
+    }
+
+    // Internal. Convert from a rust BackgroundLayer to a C++ BackgroundLayer.
+    static BackgroundLayer fromRust(const rust::BackgroundLayer &rustData);
+
+private:
+    std::string m_image;
+    std::vector<std::string> m_repeat;
+    std::vector<Value> m_position;
+    std::vector<Value> m_size;
+    std::string m_attachment;
+    std::string m_origin;
+    std::string m_clip;
+    Color::Color m_color;
+};
+
+/*!
+ * \class cssparser::GridTrack
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief The size of a single track of a grid track list.
+ */
+class CSSPARSER_EXPORT GridTrack
+{
+public:
+    /*!
+     * \enum cssparser::GridTrack::Type
+     *
+     * The kind of track size.
+     *
+     * \value Breadth
+     *      A length, a percentage or a flexible length in Fr, see breadth().
+     * \value Keyword
+     *      One of \c{auto}, \c{min-content} or \c{max-content}, see keyword().
+     * \value MinMax
+     *      A \c{minmax()} function, see minimum() and maximum().
+     * \value FitContent
+     *      A \c{fit-content()} function, with its limit as breadth().
+     */
+    enum class Type {
+        Breadth,
+        Keyword,
+        MinMax,
+        FitContent,
+    };
+
+    /*!
+     * Returns the kind of this track size.
+     */
+    inline Type type() const
+    {
+        return m_type;
+    }
+    /*!
+     * Returns the breadth of a Breadth track or the limit of a FitContent track.
+     */
+    inline Dimension breadth() const
+    {
+        return m_breadth;
+    }
+    /*!
+     * Returns the keyword of a Keyword track.
+     */
+    inline std::string keyword() const
+    {
+        return m_keyword;
+    }
+    /*!
+     * Returns the minimum of a MinMax track.
+     */
+    inline std::shared_ptr<const GridTrack> minimum() const
+    {
+        return m_minimum;
+    }
+    /*!
+     * Returns the maximum of a MinMax track.
+     */
+    inline std::shared_ptr<const GridTrack> maximum() const
+    {
+        return m_maximum;
+    }
+
+    // Internal. Convert from a rust GridTrack to a C++ GridTrack.
+    static GridTrack fromRust(const rust::GridTrack &rustData);
+
+private:
+    Type m_type = Type::Breadth;
+    Dimension m_breadth;
+    std::string m_keyword;
+    std::shared_ptr<const GridTrack> m_minimum;
+    std::shared_ptr<const GridTrack> m_maximum;
 };
 
 /*!
@@ -118,12 +561,99 @@ private:
  * \brief A struct describing an error that happend during parsing.
  */
 struct CSSPARSER_EXPORT Error {
+    /*!
+     * \enum cssparser::Error::Kind
+     *
+     * The category of error.
+     *
+     * \value Unspecified
+     * \value Unimplemented
+     * \value UnexpectedEndOfInput
+     * \value Unknown
+     * \value UnknownProperty
+     * \value UnexpectedToken
+     * \value InvalidSelectors
+     * \value InvalidPropertySyntax
+     * \value InvalidPropertyValue
+     * \value UnknownFunction
+     * \value InvalidPropertyDefinition
+     * \value PropertyValueDoesNotMatchSyntax
+     * \value UnsupportedAtRule
+     * \value InvalidAtRule
+     * \value InvalidQualifiedRule
+     * \value FileError
+     * \value StyleSheetParseError
+     */
+    enum class Kind {
+        Unspecified,
+        Unimplemented,
+        UnexpectedEndOfInput,
+        Unknown,
+        UnknownProperty,
+        UnexpectedToken,
+        InvalidSelectors,
+        InvalidPropertySyntax,
+        InvalidPropertyValue,
+        UnknownFunction,
+        InvalidPropertyDefinition,
+        PropertyValueDoesNotMatchSyntax,
+        UnsupportedAtRule,
+        InvalidAtRule,
+        InvalidQualifiedRule,
+        FileError,
+        StyleSheetParseError,
+    };
+
     std::string file;
     uint32_t line = 0;
     uint32_t column = 0;
     std::string message;
+    Kind kind = Kind::Unspecified;
 };
 
+/*!
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief Statistics about parsing a single file, see StyleSheet::setRecordStats().
+ */
+struct CSSPARSER_EXPORT ParseStats {
+    std::filesystem::path path;
+    std::size_t rules = 0;
+    std::size_t errors = 0;
+    std::size_t bytes = 0;
+    /*!
+     * The time spent parsing, including the time spent parsing imports.
+     */
+    std::chrono::microseconds duration;
+};
+
+/*!
+ * Parse \a value as the value of the property named \a name.
+ *
+ * Returns the parsed values, or an empty list if \a value does not match the
+ * syntax of the property. In that case, if \a error is not null, it is set to
+ * the error that occurred.
+ */
+CSSPARSER_EXPORT std::vector<Value> parsePropertyValue(const std::string &name, const std::string &value, Error *error = nullptr);
+
+/*!
+ * Parse \a value as the value of the \c{background} shorthand.
+ *
+ * Returns the layers of the background, or an empty list if \a value is not a
+ * valid background. In that case, if \a error is not null, it is set to the
+ * error that occurred.
+ */
+CSSPARSER_EXPORT std::vector<BackgroundLayer> parseBackground(const std::string &value, Error *error = nullptr);
+
+/*!
+ * Parse \a value as a grid track list, like \c{1fr 2fr minmax(100px, 1fr)}.
+ *
+ * Returns the tracks of the list, or an empty list if \a value is not a valid
+ * track list. In that case, if \a error is not null, it is set to the error
+ * that occurred. \c{repeat()} is not supported yet.
+ */
+CSSPARSER_EXPORT std::vector<GridTrack> parseTrackList(const std::string &value, Error *error = nullptr);
+
 /*!
  * \inmodule cxx-rust-cssparser
  *
@@ -147,12 +677,58 @@ public:
      * A view of the list of errors generated when parsing this StyleSheet.
      */
     std::span<const Error> errors() const;
+    /*!
+     * The errors generated when parsing this StyleSheet, as a JSON array.
+     *
+     * Each error is an object with the fields \c kind, \c message, \c file,
+     * \c line and \c column.
+     */
+    std::string errorsJson() const;
     /*!
      * A view of the list of files that were parsed by this StyleSheet.
      *
      * This includes files that were imported using \c{@import} in CSS.
      */
     std::span<const std::filesystem::path> paths() const;
+    /*!
+     * A view of the names of all cascade layers, from lowest to highest priority.
+     *
+     * Rules that are not part of any layer have a higher priority than all layers.
+     */
+    std::span<const std::string> layers() const;
+    /*!
+     * A view of the animations defined using \c{@keyframes}.
+     */
+    std::span<const Keyframes> keyframes() const;
+    /*!
+     * A view of the fonts defined using \c{@font-face}.
+     */
+    std::span<const FontFace> fontFaces() const;
+    /*!
+     * A view of the custom properties declared in this style sheet and imported sheets.
+     *
+     * References to other custom properties using \c{var()} are already
+     * resolved. Custom properties that could not be resolved, like those with
+     * cyclic references, are not included, see variableErrors().
+     */
+    std::span<const Property> variables() const;
+    /*!
+     * A view of the errors that occurred while resolving the custom properties
+     * returned by variables().
+     */
+    std::span<const Error> variableErrors() const;
+    /*!
+     * A view of the statistics recorded for each parsed file, including imports.
+     *
+     * This is empty unless recording statistics was enabled before parsing.
+     */
+    std::span<const ParseStats> parseStats() const;
+    /*!
+     * Set whether statistics are recorded when parsing to \a enabled.
+     *
+     * Recording is disabled by default.
+     */
+    void setRecordStats(bool enabled);
     /*!
      * Parse a CSS file and add all rules to this StyleSheet.
      *
@@ -177,6 +753,23 @@ public:
      * errors.
      */
     void parseString(const std::string &data);
+    /*!
+     * Parse UTF-8 encoded CSS in \a data and add all rules to this StyleSheet.
+     *
+     * This is useful for data that does not come from a file, like resource
+     * data. If \a data is not valid UTF-8, a FileError is added to errors().
+     *
+     * \note Multiple calls will append to the internal list of rules and
+     * errors.
+     */
+    void parseBytes(std::span<const char> data);
+    /*!
+     * Parse a string containing an inline style, like \c{color: red; width: 10px}.
+     *
+     * Returns the properties declared in \a declarations. Any errors encountered
+     * while parsing will be added to errors().
+     */
+    std::vector<Property> parseInline(const std::string &declarations);
 
     void import(const std::filesystem::path &path);
 
@@ -51,6 +51,12 @@ std::string kindToString(SelectorPart::Kind kind)
         return "PseudoClass"s;
     case SelectorPart::Kind::DocumentRoot:
         return "DocumentRoot"s;
+    case SelectorPart::Kind::Namespace:
+        return "Namespace"s;
+    case SelectorPart::Kind::Negation:
+        return "Negation"s;
+    case SelectorPart::Kind::PseudoElement:
+        return "PseudoElement"s;
     case SelectorPart::Kind::DescendantCombinator:
         return "DescendantCombinator"s;
     case SelectorPart::Kind::ChildCombinator:
@@ -72,6 +78,24 @@ std::string SelectorPart::toString() const
     case SelectorPart::Kind::DescendantCombinator:
     case SelectorPart::Kind::ChildCombinator:
         return std::format("SelectorPart(type: {})", kind);
+    case SelectorPart::Kind::Namespace:
+        return std::format("SelectorPart(type: {}, prefix: {}, url: {})", kind, m_namespacePrefix, m_namespaceUrl);
+    case SelectorPart::Kind::PseudoClass:
+    case SelectorPart::Kind::PseudoElement:
+        if (!m_argument.empty()) {
+            return std::format("SelectorPart(type: {}, value: {}, argument: {})", kind, m_value.toString(), m_argument);
+        }
+        return std::format("SelectorPart(type: {}, value: {})", kind, m_value.toString());
+    case SelectorPart::Kind::Negation: {
+        std::string selectors;
+        for (const auto &selector : m_negatedSelectors) {
+            if (!selectors.empty()) {
+                selectors += ", "s;
+            }
+            selectors += selector.toString();
+        }
+        return std::format("SelectorPart(type: {}, selectors: {})", kind, selectors);
+    }
     default:
         return std::format("SelectorPart(type: {}, value: {})", kind, m_value.toString());
     }
@@ -98,6 +122,12 @@ inline SelectorPart::Kind convertKind(rust::SelectorKind rustKind)
         return SelectorPart::Kind::RelativeParent;
     case rust::SelectorKind::DocumentRoot:
         return SelectorPart::Kind::DocumentRoot;
+    case rust::SelectorKind::Namespace:
+        return SelectorPart::Kind::Namespace;
+    case rust::SelectorKind::Negation:
+        return SelectorPart::Kind::Negation;
+    case rust::SelectorKind::PseudoElement:
+        return SelectorPart::Kind::PseudoElement;
     case rust::SelectorKind::DescendantCombinator:
         return SelectorPart::Kind::DescendantCombinator;
     case rust::SelectorKind::ChildCombinator:
@@ -138,7 +168,9 @@ SelectorPart SelectorPart::fromRust(const rust::SelectorPart &rustData)
     auto result = SelectorPart{};
 
     result.m_kind = convertKind(rustData.kind());
-    result.m_value = Value::fromRust(rustData.value());
+    if (!rustData.value().is_empty()) {
+        result.m_value = Value::fromRust(rustData.value());
+    }
 
     if (rustData.attribute_operator() != rust::AttributeOperator::None) {
         result.m_attributeMatch = AttributeMatch{std::string(rustData.attribute_name()),
@@ -146,9 +178,29 @@ SelectorPart SelectorPart::fromRust(const rust::SelectorPart &rustData)
                                                  Value::fromRust(rustData.attribute_value())};
     }
 
+    if (result.m_kind == Kind::Namespace) {
+        result.m_namespacePrefix = std::string(rustData.namespace_prefix());
+        result.m_namespaceUrl = std::string(rustData.namespace_url());
+    }
+
+    if (result.m_kind == Kind::PseudoClass || result.m_kind == Kind::PseudoElement) {
+        result.m_argument = std::string(rustData.argument());
+    }
+
+    if (result.m_kind == Kind::Negation) {
+        std::ranges::transform(rustData.negated_selectors(), std::back_inserter(result.m_negatedSelectors), [](const auto &selector) {
+            return Selector::fromRust(selector);
+        });
+    }
+
     return result;
 }
 
+std::span<const Selector> SelectorPart::negatedSelectors() const
+{
+    return std::span<const Selector>(m_negatedSelectors.cbegin(), m_negatedSelectors.cend());
+}
+
 Selector::Selector()
 {
 }
@@ -158,6 +210,24 @@ Selector::Selector(const std::vector<SelectorPart> &parts)
 {
 }
 
+bool Selector::hasRelativeParent() const
+{
+    return std::ranges::any_of(m_parts, [](const auto &part) {
+        return part.kind() == SelectorPart::Kind::RelativeParent;
+    });
+}
+
+std::size_t Selector::complexity() const
+{
+    if (m_parts.empty()) {
+        return 0;
+    }
+
+    return std::ranges::count_if(m_parts, [](const auto &part) {
+        return part.kind() == SelectorPart::Kind::DescendantCombinator || part.kind() == SelectorPart::Kind::ChildCombinator;
+    }) + 1;
+}
+
 std::string Selector::toString() const
 {
     std::string parts;
@@ -18,6 +18,8 @@ struct SelectorPart;
 struct Selector;
 }
 
+class Selector;
+
 /*!
  * \class cssparser::AttributeMatch
  *
@@ -78,6 +80,9 @@ public:
         Attribute,
         RelativeParent,
         DocumentRoot,
+        Namespace,
+        Negation,
+        PseudoElement,
 
         // Special value to mark the start of combinator selectors
         CombinatorStart,
@@ -109,6 +114,45 @@ public:
         return m_attributeMatch;
     }
 
+    /*!
+     * Returns the namespace prefix of a Namespace part.
+     *
+     * This is "*" for any namespace and empty for the default namespace or no
+     * namespace.
+     */
+    inline std::string namespacePrefix() const
+    {
+        return m_namespacePrefix;
+    }
+
+    /*!
+     * Returns the namespace URL of a Namespace part.
+     *
+     * This is empty if the part matches elements without namespace.
+     */
+    inline std::string namespaceUrl() const
+    {
+        return m_namespaceUrl;
+    }
+
+    /*!
+     * Returns the argument of a functional PseudoClass or PseudoElement part,
+     * like \c{en} for \c{:lang(en)} or \c{track} for \c{::part(track)}.
+     *
+     * This is empty for parts without argument.
+     */
+    inline std::string argument() const
+    {
+        return m_argument;
+    }
+
+    /*!
+     * Returns the selectors of a Negation part, like \c{.a} and \c{.b} for \c{:not(.a, .b)}.
+     *
+     * This is empty for other parts.
+     */
+    std::span<const Selector> negatedSelectors() const;
+
     std::string toString() const;
 
     static SelectorPart fromRust(const rust::SelectorPart &rustData);
@@ -117,6 +161,10 @@ private:
     Kind m_kind = Kind::Unknown;
     Value m_value;
     std::optional<AttributeMatch> m_attributeMatch;
+    std::string m_namespacePrefix;
+    std::string m_namespaceUrl;
+    std::string m_argument;
+    std::vector<Selector> m_negatedSelectors;
 };
 
 /*!
@@ -135,6 +183,18 @@ public:
         return std::span<const SelectorPart>(m_parts.cbegin(), m_parts.cend());
     }
 
+    /*!
+     * Whether this selector contains a RelativeParent part, which refers to
+     * the selector of the enclosing rule.
+     */
+    bool hasRelativeParent() const;
+
+    /*!
+     * The number of compound selectors in this selector, which are separated
+     * by combinators.
+     */
+    std::size_t complexity() const;
+
     std::string toString() const;
 
     static Selector fromRust(const rust::Selector &rustData);
@@ -40,7 +40,7 @@ std::string Dimension::toString() const
     case Unit::Pt:
         return std::format("{} pt", m_value);
     case Unit::Percent:
-        return std::format("{} %", m_value);
+        return std::format("{} %", m_value * 100.0f);
     case Unit::Degrees:
         return std::format("{}°", m_value);
     case Unit::Radians:
@@ -49,6 +49,16 @@ std::string Dimension::toString() const
         return std::format("{} s", m_value);
     case Unit::Milliseconds:
         return std::format("{} ms", m_value);
+    case Unit::Dppx:
+        return std::format("{} dppx", m_value);
+    case Unit::Dpi:
+        return std::format("{} dpi", m_value);
+    case Unit::Dpcm:
+        return std::format("{} dpcm", m_value);
+    case Unit::Fr:
+        return std::format("{} fr", m_value);
+    case Unit::Other:
+        return std::format("{} {}", m_value, m_unitName);
     }
 
     return std::format("{} (Unknown unit)", m_value);
@@ -81,15 +91,94 @@ inline Dimension::Unit convertUnit(rust::Unit unit)
         return Dimension::Unit::Seconds;
     case rust::Unit::Milliseconds:
         return Dimension::Unit::Milliseconds;
+    case rust::Unit::Dppx:
+        return Dimension::Unit::Dppx;
+    case rust::Unit::Dpi:
+        return Dimension::Unit::Dpi;
+    case rust::Unit::Dpcm:
+        return Dimension::Unit::Dpcm;
+    case rust::Unit::Fr:
+        return Dimension::Unit::Fr;
+    case rust::Unit::Other:
+        return Dimension::Unit::Other;
     }
 
     assert(false && "Mismatch between unit types in C++ and Rust, update C++ code!");
     return Dimension::Unit::Unknown;
 }
 
-Dimension Dimension::fromRust(rust::Dimension rustData)
+inline rust::Unit convertUnit(Dimension::Unit unit)
 {
-    return Dimension{convertUnit(rustData.unit), rustData.value};
+    switch (unit) {
+    case Dimension::Unit::Unknown:
+        return rust::Unit::Unknown;
+    case Dimension::Unit::Unsupported:
+        return rust::Unit::Unsupported;
+    case Dimension::Unit::Number:
+        return rust::Unit::Number;
+    case Dimension::Unit::Px:
+        return rust::Unit::Px;
+    case Dimension::Unit::Em:
+        return rust::Unit::Em;
+    case Dimension::Unit::Rem:
+        return rust::Unit::Rem;
+    case Dimension::Unit::Pt:
+        return rust::Unit::Pt;
+    case Dimension::Unit::Percent:
+        return rust::Unit::Percent;
+    case Dimension::Unit::Degrees:
+        return rust::Unit::Degrees;
+    case Dimension::Unit::Radians:
+        return rust::Unit::Radians;
+    case Dimension::Unit::Seconds:
+        return rust::Unit::Seconds;
+    case Dimension::Unit::Milliseconds:
+        return rust::Unit::Milliseconds;
+    case Dimension::Unit::Dppx:
+        return rust::Unit::Dppx;
+    case Dimension::Unit::Dpi:
+        return rust::Unit::Dpi;
+    case Dimension::Unit::Dpcm:
+        return rust::Unit::Dpcm;
+    case Dimension::Unit::Fr:
+        return rust::Unit::Fr;
+    case Dimension::Unit::Other:
+        return rust::Unit::Other;
+    }
+
+    return rust::Unit::Unknown;
+}
+
+Dimension::UnitClass Dimension::unitClass() const
+{
+    switch (rust::unit_class(convertUnit(m_unit))) {
+    case rust::DimensionClass::Unknown:
+        return UnitClass::Unknown;
+    case rust::DimensionClass::Number:
+        return UnitClass::Number;
+    case rust::DimensionClass::Length:
+        return UnitClass::Length;
+    case rust::DimensionClass::Percentage:
+        return UnitClass::Percentage;
+    case rust::DimensionClass::Angle:
+        return UnitClass::Angle;
+    case rust::DimensionClass::Time:
+        return UnitClass::Time;
+    case rust::DimensionClass::Resolution:
+        return UnitClass::Resolution;
+    case rust::DimensionClass::Flex:
+        return UnitClass::Flex;
+    }
+
+    assert(false && "Mismatch between unit classes in C++ and Rust, update C++ code!");
+    return UnitClass::Unknown;
+}
+
+Dimension Dimension::fromRust(rust::Dimension rustData, const std::string &unitName)
+{
+    auto result = Dimension{convertUnit(rustData.unit), rustData.value};
+    result.m_unitName = unitName;
+    return result;
 }
 
 Value::Value()
@@ -105,6 +194,8 @@ inline Value::Type convertType(rust::ValueType rustType)
         return Value::Type::Dimension;
     case rust::ValueType::String:
         return Value::Type::String;
+    case rust::ValueType::Keyword:
+        return Value::Type::Keyword;
     case rust::ValueType::Color:
         return Value::Type::Color;
     case rust::ValueType::Image:
@@ -113,6 +204,12 @@ inline Value::Type convertType(rust::ValueType rustType)
         return Value::Type::Url;
     case rust::ValueType::Integer:
         return Value::Type::Integer;
+    case rust::ValueType::Slash:
+        return Value::Type::Slash;
+    case rust::ValueType::CssWideKeyword:
+        return Value::Type::CssWideKeyword;
+    case rust::ValueType::UnicodeRange:
+        return Value::Type::UnicodeRange;
     }
 
     assert(false && "Mismatch between value types in C++ and Rust, update C++ code!");
@@ -128,6 +225,8 @@ inline std::string valueTypeToString(Value::Type type)
         return "Dimension"s;
     case Value::Type::String:
         return "String"s;
+    case Value::Type::Keyword:
+        return "Keyword"s;
     case Value::Type::Color:
         return "Color"s;
     case Value::Type::Image:
@@ -136,11 +235,70 @@ inline std::string valueTypeToString(Value::Type type)
         return "Url"s;
     case Value::Type::Integer:
         return "Integer"s;
+    case Value::Type::Slash:
+        return "Slash"s;
+    case Value::Type::CssWideKeyword:
+        return "CssWideKeyword"s;
+    case Value::Type::UnicodeRange:
+        return "UnicodeRange"s;
     }
 
     return "Unknown"s;
 }
 
+inline CssWideKeyword convertCssWideKeyword(rust::CssWideKeyword keyword)
+{
+    switch (keyword) {
+    case rust::CssWideKeyword::Initial:
+        return CssWideKeyword::Initial;
+    case rust::CssWideKeyword::Inherit:
+        return CssWideKeyword::Inherit;
+    case rust::CssWideKeyword::Unset:
+        return CssWideKeyword::Unset;
+    case rust::CssWideKeyword::Revert:
+        return CssWideKeyword::Revert;
+    case rust::CssWideKeyword::RevertLayer:
+        return CssWideKeyword::RevertLayer;
+    }
+
+    assert(false && "Mismatch between CSS-wide keywords in C++ and Rust, update C++ code!");
+    return CssWideKeyword::Unset;
+}
+
+inline std::string cssWideKeywordToString(CssWideKeyword keyword)
+{
+    switch (keyword) {
+    case CssWideKeyword::Initial:
+        return "initial"s;
+    case CssWideKeyword::Inherit:
+        return "inherit"s;
+    case CssWideKeyword::Unset:
+        return "unset"s;
+    case CssWideKeyword::Revert:
+        return "revert"s;
+    case CssWideKeyword::RevertLayer:
+        return "revert-layer"s;
+    }
+
+    return "Unknown"s;
+}
+
+std::optional<float> Value::toNumber() const
+{
+    if (m_type == Type::Integer) {
+        return float(std::get<int>(m_data));
+    }
+
+    if (m_type == Type::Dimension) {
+        auto dimension = std::get<Dimension>(m_data);
+        if (dimension.unit() == Dimension::Unit::Number) {
+            return dimension.value();
+        }
+    }
+
+    return std::nullopt;
+}
+
 std::string Value::toString() const
 {
     std::string data;
@@ -152,6 +310,7 @@ std::string Value::toString() const
         data = std::get<Dimension>(m_data).toString();
         break;
     case Value::Type::String:
+    case Value::Type::Keyword:
     case Value::Type::Image:
     case Value::Type::Url:
         data = std::get<std::string>(m_data);
@@ -161,6 +320,18 @@ std::string Value::toString() const
         break;
     case Value::Type::Integer:
         data = std::to_string(std::get<int>(m_data));
+        break;
+    case Value::Type::Slash:
+        data = "/";
+        break;
+    case Value::Type::CssWideKeyword:
+        data = cssWideKeywordToString(std::get<CssWideKeyword>(m_data));
+        break;
+    case Value::Type::UnicodeRange: {
+        auto range = std::get<UnicodeRange>(m_data);
+        data = std::format("U+{:X}-{:X}", range.start, range.end);
+        break;
+    }
     }
 
     return std::format("Value(type: {}, data: {})", valueTypeToString(m_type), data);
@@ -175,9 +346,10 @@ Value Value::fromRust(const rust::Value &rustData)
     case rust::ValueType::Empty:
         break;
     case rust::ValueType::Dimension:
-        result.m_data = Dimension::fromRust(rustData.to_dimension());
+        result.m_data = Dimension::fromRust(rustData.to_dimension(), std::string(rustData.unit_name()));
         break;
     case rust::ValueType::String:
+    case rust::ValueType::Keyword:
         result.m_data = std::string(rustData.to_string());
         break;
     case rust::ValueType::Image:
@@ -192,6 +364,16 @@ Value Value::fromRust(const rust::Value &rustData)
     case rust::ValueType::Integer:
         result.m_data = rustData.to_integer();
         break;
+    case rust::ValueType::Slash:
+        break;
+    case rust::ValueType::CssWideKeyword:
+        result.m_data = convertCssWideKeyword(rustData.to_css_wide_keyword());
+        break;
+    case rust::ValueType::UnicodeRange: {
+        auto range = rustData.to_unicode_range();
+        result.m_data = UnicodeRange{range.start, range.end};
+        break;
+    }
     }
 
     return result;
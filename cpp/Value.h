@@ -4,6 +4,7 @@
 #pragma once
 
 #include <format>
+#include <optional>
 #include <sstream>
 
 #include "Color.h"
@@ -53,7 +54,7 @@ public:
      *      A length in Points.
      *      Points are a font measurement unit equal to 1/72".
      * \value Percent
-     *      A percentage.
+     *      A percentage. The value is stored as a fraction, so 50% has a value of 0.5.
      * \value Degrees
      *      An angle in Degrees.
      * \value Radians
@@ -62,6 +63,17 @@ public:
      *      A length of time measured in seconds.
      * \value Milliseconds
      *      A length of time measured in milliseconds.
+     * \value Dppx
+     *      A resolution in dots per pixel.
+     * \value Dpi
+     *      A resolution in dots per inch.
+     * \value Dpcm
+     *      A resolution in dots per centimeter.
+     * \value Fr
+     *      A fraction of the free space in a grid container.
+     * \value Other
+     *      A unit that is not known to the parser, see unitName().
+     *      This is only used when parsing with strict units disabled.
      */
     enum class Unit {
         Unknown,
@@ -76,6 +88,44 @@ public:
         Radians,
         Seconds,
         Milliseconds,
+        Dppx,
+        Dpi,
+        Dpcm,
+        Fr,
+        Other,
+    };
+
+    /*!
+     * \enum cssparser::Dimension::UnitClass
+     *
+     * The kind of quantity a dimension measures, based on its unit.
+     *
+     * \value Unknown
+     *      The unit is unknown, unsupported or not known to the parser.
+     * \value Number
+     *      A unitless number.
+     * \value Length
+     *      A length, like Px or Em.
+     * \value Percentage
+     *      A percentage.
+     * \value Angle
+     *      An angle, like Degrees or Radians.
+     * \value Time
+     *      A length of time, like Seconds or Milliseconds.
+     * \value Resolution
+     *      A resolution, like Dppx or Dpi.
+     * \value Flex
+     *      A flexible length in a grid, in Fr.
+     */
+    enum class UnitClass {
+        Unknown,
+        Number,
+        Length,
+        Percentage,
+        Angle,
+        Time,
+        Resolution,
+        Flex,
     };
 
     /*!
@@ -102,6 +152,10 @@ public:
     {
         return m_unit;
     }
+    /*!
+     * Returns the kind of quantity the unit of this Dimension measures.
+     */
+    UnitClass unitClass() const;
     /*!
      * Returns the value of this Dimension.
      */
@@ -109,17 +163,58 @@ public:
     {
         return m_value;
     }
+    /*!
+     * Returns the unit as it was written if unit() is \c Other, an empty string otherwise.
+     */
+    inline std::string unitName() const
+    {
+        return m_unitName;
+    }
     /*!
      * Returns a string representation of this dimension.
      */
     std::string toString() const;
 
     // Internal: Convert from a rust Dimension to C++ Dimension.
-    static Dimension fromRust(rust::Dimension rustData);
+    static Dimension fromRust(rust::Dimension rustData, const std::string &unitName = std::string{});
 
 private:
     Unit m_unit = Unit::Unknown;
     float m_value = 0.0;
+    std::string m_unitName;
+};
+
+/*!
+ * \enum cssparser::CssWideKeyword
+ * \inmodule cxx-rust-cssparser
+ *
+ * A keyword that is valid for every property.
+ *
+ * \value Initial
+ * \value Inherit
+ * \value Unset
+ * \value Revert
+ * \value RevertLayer
+ *      Roll back to the value from the previous cascade layer.
+ */
+enum class CssWideKeyword {
+    Initial,
+    Inherit,
+    Unset,
+    Revert,
+    RevertLayer,
+};
+
+/*!
+ * \inmodule cxx-rust-cssparser
+ *
+ * \brief A range of Unicode code points, like \c{U+0000-00FF}.
+ *
+ * Both \c start and \c end are inclusive.
+ */
+struct CSSPARSER_EXPORT UnicodeRange {
+    uint32_t start = 0;
+    uint32_t end = 0;
 };
 
 /*!
@@ -141,7 +236,9 @@ public:
      * \value Dimension
      *      A Dimension.
      * \value String
-     *      A string.
+     *      A quoted string.
+     * \value Keyword
+     *      An identifier like \c none or \c auto, represented as a string.
      * \value Color
      *      A Color.
      * \value Image
@@ -150,15 +247,25 @@ public:
      *      A URL, represented as a string.
      * \value Integer
      *      An integer.
+     * \value Slash
+     *      A slash separating components of a value.
+     * \value CssWideKeyword
+     *      A CssWideKeyword.
+     * \value UnicodeRange
+     *      A UnicodeRange.
      */
     enum class Type {
         Empty,
         Dimension,
         String,
+        Keyword,
         Color,
         Image,
         Url,
         Integer,
+        Slash,
+        CssWideKeyword,
+        UnicodeRange,
     };
 
     /*!
@@ -201,6 +308,20 @@ public:
     {
         return m_type;
     }
+    /*!
+     * Returns whether this value is empty, which is the case for a default constructed value.
+     */
+    inline bool isEmpty() const
+    {
+        return m_type == Type::Empty;
+    }
+    /*!
+     * Returns the number stored in this value.
+     *
+     * This works for both integers and dimensions without a unit. Returns
+     * std::nullopt for any other kind of value.
+     */
+    std::optional<float> toNumber() const;
     /*!
      * Returns a string representation of this value.
      */
@@ -211,7 +332,7 @@ public:
 
 private:
     Type m_type = Type::Empty;
-    std::variant<std::nullopt_t, Dimension, std::string, Color::Color, int> m_data = std::nullopt;
+    std::variant<std::nullopt_t, Dimension, std::string, Color::Color, int, cssparser::CssWideKeyword, cssparser::UnicodeRange> m_data = std::nullopt;
 };
 
 }
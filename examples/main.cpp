@@ -33,6 +33,12 @@ std::string kind_to_string(SelectorPart::Kind kind)
         return "PseudoClass"s;
     case SelectorPart::Kind::DocumentRoot:
         return "DocumentRoot"s;
+    case SelectorPart::Kind::Namespace:
+        return "Namespace"s;
+    case SelectorPart::Kind::Negation:
+        return "Negation"s;
+    case SelectorPart::Kind::PseudoElement:
+        return "PseudoElement"s;
     case SelectorPart::Kind::DescendantCombinator:
         return "DescendantCombinator";
     case SelectorPart::Kind::ChildCombinator:
@@ -50,6 +56,7 @@ std::string value_to_string(const Value &value)
     case Value::Type::Dimension:
         return value.get<Dimension>().toString();
     case Value::Type::String:
+    case Value::Type::Keyword:
     case Value::Type::Image:
     case Value::Type::Url:
         return value.get<std::string>();
@@ -57,6 +64,14 @@ std::string value_to_string(const Value &value)
         return value.get<Color::Color>().toString();
     case Value::Type::Integer:
         return std::to_string(value.get<int>());
+    case Value::Type::Slash:
+        return "/"s;
+    case Value::Type::CssWideKeyword:
+        return "CSS-wide keyword"s;
+    case Value::Type::UnicodeRange: {
+        auto range = value.get<UnicodeRange>();
+        return std::format("U+{:X}-{:X}", range.start, range.end);
+    }
     }
 
     return std::string{};
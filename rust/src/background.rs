@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+use crate::{
+    details::{parse_error, parse_error_from_cssparser_error},
+    details::property::syntax::ParsedPropertySyntax,
+    details::property::value::{parse_values, parse_values_with_options},
+    details::rulesparser::ParserOptions,
+    parseerror::{ParseError, ParseErrorKind},
+    value::{Color, Value, ValueData},
+};
+
+const REPEAT_KEYWORDS: &[&str] = &["repeat", "no-repeat", "repeat-x", "repeat-y", "space", "round"];
+const POSITION_KEYWORDS: &[&str] = &["left", "right", "top", "bottom", "center"];
+const SIZE_KEYWORDS: &[&str] = &["auto", "cover", "contain"];
+const ATTACHMENT_KEYWORDS: &[&str] = &["fixed", "scroll", "local"];
+const BOX_KEYWORDS: &[&str] = &["border-box", "padding-box", "content-box"];
+
+/// A single comma-separated layer of the `background` shorthand.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BackgroundLayer {
+    /// The URL of the image, or None if the layer has no image or uses `none`.
+    pub image: Option<String>,
+    pub repeat: Vec<String>,
+    pub position: Vec<Value>,
+    /// The values following the `/` after the position.
+    pub size: Vec<Value>,
+    /// One of `fixed`, `scroll` or `local`.
+    pub attachment: Option<String>,
+    /// The `background-origin` box. A single box sets both origin and clip.
+    pub origin: Option<String>,
+    /// The `background-clip` box.
+    pub clip: Option<String>,
+    /// The background color. Only the final layer can have a color.
+    pub color: Option<Color>,
+}
+
+/// Parse the value of the `background` shorthand into its layers.
+pub fn parse_background(input: &str) -> Result<Vec<BackgroundLayer>, ParseError> {
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+
+    parser.parse_entirely(parse_layers).map_err(|error| parse_error_from_cssparser_error(&error, String::new()))
+}
+
+/// Parse the value of a `background` declaration.
+///
+/// The value must form valid layers, but is returned as a flat list of values
+/// like the values of other properties.
+pub(crate) fn parse_background_value<'i, 't>(parser: &mut cssparser::Parser<'i, 't>, options: &ParserOptions) -> Result<Vec<Value>, cssparser::ParseError<'i, ParseError>> {
+    let start = parser.state();
+    parser.parse_until_before(cssparser::Delimiter::Bang, parse_layers)?;
+    parser.reset(&start);
+
+    parse_values_with_options(&ParsedPropertySyntax::Universal, parser, options)
+}
+
+fn parse_layers<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Result<Vec<BackgroundLayer>, cssparser::ParseError<'i, ParseError>> {
+    let layers = parser.parse_comma_separated(|parser| {
+        let values = parse_values(&ParsedPropertySyntax::Universal, parser)?;
+        parse_layer(parser, values)
+    })?;
+
+    if let Some(index) = layers[..layers.len() - 1].iter().position(|layer| layer.color.is_some()) {
+        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Only the final background layer can have a color, but layer {} has one", index + 1));
+    }
+
+    Ok(layers)
+}
+
+// What the previous value of a layer was, as position and size values need to
+// be adjacent.
+#[derive(PartialEq)]
+enum Previous {
+    Other,
+    Position,
+    Slash,
+    Size,
+}
+
+fn parse_layer<'i, 't>(parser: &cssparser::Parser<'i, 't>, values: Vec<Value>) -> Result<BackgroundLayer, cssparser::ParseError<'i, ParseError>> {
+    let mut layer = BackgroundLayer::default();
+    let mut has_image = false;
+    let mut previous = Previous::Other;
+
+    for value in values {
+        // Keywords are ASCII case-insensitive, so they are stored in lowercase.
+        let keyword = match &value.data {
+            ValueData::Keyword(keyword) => Some(keyword.to_ascii_lowercase()),
+            _ => None,
+        };
+        let is_keyword = |keywords: &[&str]| keyword.as_deref().is_some_and(|keyword| keywords.contains(&keyword));
+        let is_size = is_keyword(SIZE_KEYWORDS) || is_length_percentage(&value);
+        // `cover` and `contain` cannot be combined with a second size value.
+        let is_size_pair = !is_keyword(&["cover", "contain"]) && layer.size.iter().all(|size| !matches!(&size.data, ValueData::Keyword(keyword) if keyword == "cover" || keyword == "contain"));
+        let is_position = is_keyword(POSITION_KEYWORDS) || is_length_percentage(&value);
+
+        previous = match &value.data {
+            ValueData::Url(url) | ValueData::Image(url) if !has_image => {
+                layer.image = Some(url.clone());
+                has_image = true;
+                Previous::Other
+            }
+            ValueData::Keyword(_) if is_keyword(&["none"]) && !has_image => {
+                has_image = true;
+                Previous::Other
+            }
+            ValueData::Keyword(_) if is_keyword(REPEAT_KEYWORDS) && layer.repeat.len() < 2 => {
+                layer.repeat.push(keyword.unwrap());
+                Previous::Other
+            }
+            ValueData::Keyword(_) if is_keyword(ATTACHMENT_KEYWORDS) && layer.attachment.is_none() => {
+                layer.attachment = keyword;
+                Previous::Other
+            }
+            ValueData::Keyword(_) if is_keyword(BOX_KEYWORDS) && layer.clip.is_none() => {
+                if layer.origin.is_none() {
+                    layer.origin = keyword;
+                } else {
+                    layer.clip = keyword;
+                }
+                Previous::Other
+            }
+            ValueData::Color(color) if layer.color.is_none() => {
+                layer.color = Some(color.clone());
+                Previous::Other
+            }
+            ValueData::Slash if previous == Previous::Position => Previous::Slash,
+            _ if is_size && (previous == Previous::Slash || (previous == Previous::Size && layer.size.len() < 2 && is_size_pair)) => {
+                layer.size.push(keyword.map_or(value, |keyword| Value::new_keyword(&keyword)));
+                Previous::Size
+            }
+            _ if is_position && (layer.position.is_empty() || (previous == Previous::Position && layer.position.len() < 4)) => {
+                layer.position.push(keyword.map_or(value, |keyword| Value::new_keyword(&keyword)));
+                Previous::Position
+            }
+            _ => {
+                return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Unexpected value in background: {}", value));
+            }
+        };
+    }
+
+    if previous == Previous::Slash {
+        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Expected a background size after /"));
+    }
+
+    if layer.clip.is_none() {
+        layer.clip = layer.origin.clone();
+    }
+
+    Ok(layer)
+}
+
+fn is_length_percentage(value: &Value) -> bool {
+    match &value.data {
+        ValueData::Dimension(dimension) => dimension.is_length() || dimension.is_percent() || (dimension.is_number() && dimension.value == 0.0),
+        ValueData::Integer(integer) => *integer == 0,
+        _ => false,
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+// Collection of comments so they can be attached to rules and declarations.
+//
+// cssparser skips comments while parsing rules, so instead the input is scanned
+// once up front and comments are handed out based on the position of the rule
+// or declaration that follows them.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Default)]
+pub struct Comments {
+    // Comment text with the byte position directly after the comment.
+    comments: Vec<(usize, String)>,
+    next: usize,
+}
+
+pub type SharedComments = Rc<RefCell<Comments>>;
+
+fn collect_comments<'i, 't>(parser: &mut cssparser::Parser<'i, 't>, comments: &mut Vec<(usize, String)>) {
+    while let Ok(token) = parser.next_including_whitespace_and_comments() {
+        match token.clone() {
+            cssparser::Token::Comment(text) => comments.push((parser.position().byte_index(), text.trim().to_string())),
+            cssparser::Token::Function(_)
+            | cssparser::Token::ParenthesisBlock
+            | cssparser::Token::SquareBracketBlock
+            | cssparser::Token::CurlyBracketBlock => {
+                let _ = parser.parse_nested_block(|parser| {
+                    collect_comments(parser, comments);
+                    Ok::<(), cssparser::ParseError<'i, ()>>(())
+                });
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Comments {
+    pub fn collect(input: &str) -> Comments {
+        let mut parser_input = cssparser::ParserInput::new(input);
+        let mut parser = cssparser::Parser::new(&mut parser_input);
+
+        let mut comments = Vec::new();
+        collect_comments(&mut parser, &mut comments);
+
+        Comments { comments, next: 0 }
+    }
+
+    /// Returns all comments that end before byte `position` and were not returned yet.
+    pub fn take_before(&mut self, position: usize) -> Vec<String> {
+        let mut result = Vec::new();
+        while let Some((end, text)) = self.comments.get(self.next) {
+            if *end > position {
+                break;
+            }
+
+            result.push(text.clone());
+            self.next += 1;
+        }
+        result
+    }
+}
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+// Parsing of media queries as used by `@media` rules.
+
+use crate::media::{MediaComparison, MediaCondition, MediaFeature, MediaQuery};
+use crate::value::Unit;
+
+use super::{parse_error, ParseError, ParseErrorKind};
+
+type MediaParseResult<'i, T> = Result<T, cssparser::ParseError<'i, ParseError>>;
+
+fn parse_length<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> MediaParseResult<'i, f32> {
+    let token = parser.next()?.clone();
+    match token {
+        cssparser::Token::Dimension { value, unit, .. } => {
+            match Unit::parse(unit.as_ref()) {
+                Unit::Px => Ok(value),
+                Unit::Pt => Ok(value * 4.0 / 3.0),
+                _ => parse_error(parser, ParseErrorKind::InvalidAtRule, format!("Unsupported unit {} for media feature", unit)),
+            }
+        }
+        cssparser::Token::Number { value: 0.0, .. } => Ok(0.0),
+        _ => parse_error(parser, ParseErrorKind::InvalidAtRule, format!("Expected a length, got {:?}", token)),
+    }
+}
+
+fn parse_resolution<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> MediaParseResult<'i, f32> {
+    let token = parser.next()?.clone();
+    if let cssparser::Token::Dimension { value, unit, .. } = &token {
+        match Unit::parse(unit.as_ref()) {
+            Unit::Dppx => return Ok(*value),
+            Unit::Dpi => return Ok(value / 96.0),
+            Unit::Dpcm => return Ok(value * 2.54 / 96.0),
+            _ => (),
+        }
+    }
+
+    parse_error(parser, ParseErrorKind::InvalidAtRule, format!("Expected a resolution, got {:?}", token))
+}
+
+fn parse_media_feature<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> MediaParseResult<'i, MediaFeature> {
+    let name = parser.expect_ident()?.to_ascii_lowercase();
+
+    let (comparison, feature_name) = if let Some(feature_name) = name.strip_prefix("min-") {
+        (MediaComparison::Minimum, feature_name)
+    } else if let Some(feature_name) = name.strip_prefix("max-") {
+        (MediaComparison::Maximum, feature_name)
+    } else {
+        (MediaComparison::Exact, name.as_str())
+    };
+
+    match feature_name {
+        "width" => {
+            parser.expect_colon()?;
+            Ok(MediaFeature::Width { comparison, value: parse_length(parser)? })
+        }
+        "height" => {
+            parser.expect_colon()?;
+            Ok(MediaFeature::Height { comparison, value: parse_length(parser)? })
+        }
+        "resolution" => {
+            parser.expect_colon()?;
+            Ok(MediaFeature::Resolution { comparison, value: parse_resolution(parser)? })
+        }
+        "prefers-color-scheme" if comparison == MediaComparison::Exact => {
+            parser.expect_colon()?;
+            Ok(MediaFeature::PrefersColorScheme(parser.expect_ident()?.to_ascii_lowercase()))
+        }
+        _ => {
+            // Unknown features are not an error, they simply never match.
+            while parser.next().is_ok() {}
+            Ok(MediaFeature::Unknown(name))
+        }
+    }
+}
+
+// Whether the input starts with `(` or `not (`, which means it is a condition
+// rather than a media feature or media type.
+fn starts_with_condition(parser: &mut cssparser::Parser) -> bool {
+    let start = parser.state();
+    let result = match parser.next() {
+        Ok(cssparser::Token::ParenthesisBlock) => true,
+        Ok(cssparser::Token::Ident(ident)) if ident.eq_ignore_ascii_case("not") => {
+            matches!(parser.next(), Ok(cssparser::Token::ParenthesisBlock))
+        }
+        _ => false,
+    };
+    parser.reset(&start);
+    result
+}
+
+// Parse `( <media-condition> )` or `( <media-feature> )`.
+fn parse_in_parens<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> MediaParseResult<'i, MediaCondition> {
+    parser.expect_parenthesis_block()?;
+    parser.parse_nested_block(|parser| {
+        if starts_with_condition(parser) {
+            let condition = parse_condition(parser, true)?;
+            parser.expect_exhausted()?;
+            Ok(condition)
+        } else {
+            Ok(MediaCondition::Feature(parse_media_feature(parser)?))
+        }
+    })
+}
+
+// Parse `not <media-in-parens>` or `<media-in-parens>` followed by any number
+// of either `and <media-in-parens>` or `or <media-in-parens>`. `or` is not
+// allowed after a media type, which is what `allow_or` is for.
+fn parse_condition<'i, 't>(parser: &mut cssparser::Parser<'i, 't>, allow_or: bool) -> MediaParseResult<'i, MediaCondition> {
+    if parser.try_parse(|parser| parser.expect_ident_matching("not")).is_ok() {
+        return Ok(MediaCondition::Not(Box::new(parse_in_parens(parser)?)));
+    }
+
+    let mut conditions = vec![parse_in_parens(parser)?];
+    let mut operator: Option<String> = None;
+    while let Ok(keyword) = parser.try_parse(|parser| parser.expect_ident().map(|ident| ident.to_ascii_lowercase())) {
+        match (keyword.as_str(), &operator) {
+            ("or", _) if !allow_or => return parse_error(parser, ParseErrorKind::InvalidAtRule, String::from("Unexpected or after a media type")),
+            ("and" | "or", None) => operator = Some(keyword),
+            ("and" | "or", Some(current)) if *current == keyword => (),
+            ("and" | "or", Some(_)) => return parse_error(parser, ParseErrorKind::InvalidAtRule, String::from("Cannot mix and and or without parentheses")),
+            _ => return parse_error(parser, ParseErrorKind::InvalidAtRule, format!("Unexpected {} in media query", keyword)),
+        }
+        conditions.push(parse_in_parens(parser)?);
+    }
+
+    Ok(match operator.as_deref() {
+        Some("and") => MediaCondition::And(conditions),
+        Some(_) => MediaCondition::Or(conditions),
+        None => conditions.remove(0),
+    })
+}
+
+// Parse a single media query, like `not screen and (min-width: 600px)`.
+fn parse_single_query<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> MediaParseResult<'i, MediaCondition> {
+    if starts_with_condition(parser) {
+        let condition = parse_condition(parser, true)?;
+        parser.expect_exhausted()?;
+        return Ok(condition);
+    }
+
+    let mut media_type = parser.expect_ident()?.to_ascii_lowercase();
+    let negated = media_type == "not";
+    if negated || media_type == "only" {
+        media_type = parser.expect_ident()?.to_ascii_lowercase();
+    }
+    if matches!(media_type.as_str(), "not" | "only" | "and" | "or") {
+        return parse_error(parser, ParseErrorKind::InvalidAtRule, format!("Invalid media type {}", media_type));
+    }
+
+    let mut condition = MediaCondition::MediaType(media_type);
+    if parser.try_parse(|parser| parser.expect_ident_matching("and")).is_ok() {
+        condition = MediaCondition::And(vec![condition, parse_condition(parser, false)?]);
+    }
+    parser.expect_exhausted()?;
+
+    Ok(if negated { MediaCondition::Not(Box::new(condition)) } else { condition })
+}
+
+/// Parse a comma-separated list of media queries, which matches if any of the
+/// queries matches.
+pub fn parse_media_query<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> MediaParseResult<'i, MediaQuery> {
+    let mut queries = parser.parse_comma_separated(parse_single_query)?;
+    let condition = if queries.len() == 1 { queries.remove(0) } else { MediaCondition::Or(queries) };
+
+    Ok(MediaQuery { condition: Some(condition) })
+}
@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
 // SPDX-FileCopyrightText: 2025 Arjen Hiemstra <ahiemstra@heimr.nl>
 
+pub mod comments;
 pub mod identifier;
+pub mod mediaparser;
 pub mod rulesparser;
 pub mod selectorparser;
 
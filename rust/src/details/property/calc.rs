@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+use cssparser::Token;
+
+/// The result of folding a `calc()` expression.
+///
+/// Percentages are stored as fractions, like `Unit::Percent`, and angles in
+/// degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalcValue {
+    Number(f32),
+    Percentage(f32),
+    Angle(f32),
+}
+
+/// Parse a `calc()` function that only contains numbers, percentages and
+/// angles and fold it to a single value.
+///
+/// Operands of `+` and `-` must be of the same type, `*` needs at least one
+/// number and `/` needs a non-zero number as divisor.
+pub fn parse_calc<'i, 't, E>(parser: &mut cssparser::Parser<'i, 't>) -> Result<CalcValue, cssparser::ParseError<'i, E>> {
+    parser.expect_function_matching("calc")?;
+    parser.parse_nested_block(parse_sum)
+}
+
+fn parse_sum<'i, 't, E>(parser: &mut cssparser::Parser<'i, 't>) -> Result<CalcValue, cssparser::ParseError<'i, E>> {
+    let mut result = parse_product(parser)?;
+
+    loop {
+        let start = parser.state();
+        let location = parser.current_source_location();
+        let token = match parser.next() {
+            Ok(token) => token.clone(),
+            Err(_) => return Ok(result),
+        };
+
+        let folded = match token {
+            Token::Delim('+') => {
+                let operand = parse_product(parser)?;
+                result.add(operand, 1.0)
+            }
+            Token::Delim('-') => {
+                let operand = parse_product(parser)?;
+                result.add(operand, -1.0)
+            }
+            _ => {
+                parser.reset(&start);
+                return Ok(result);
+            }
+        };
+
+        match folded {
+            Some(value) => result = value,
+            None => return Err(location.new_unexpected_token_error(token)),
+        }
+    }
+}
+
+fn parse_product<'i, 't, E>(parser: &mut cssparser::Parser<'i, 't>) -> Result<CalcValue, cssparser::ParseError<'i, E>> {
+    let mut result = parse_operand(parser)?;
+
+    loop {
+        let start = parser.state();
+        let location = parser.current_source_location();
+        let token = match parser.next() {
+            Ok(token) => token.clone(),
+            Err(_) => return Ok(result),
+        };
+
+        let folded = match token {
+            Token::Delim('*') => {
+                let operand = parse_operand(parser)?;
+                result.multiply(operand)
+            }
+            Token::Delim('/') => {
+                let operand = parse_operand(parser)?;
+                result.divide(operand)
+            }
+            _ => {
+                parser.reset(&start);
+                return Ok(result);
+            }
+        };
+
+        match folded {
+            Some(value) => result = value,
+            None => return Err(location.new_unexpected_token_error(token)),
+        }
+    }
+}
+
+fn parse_operand<'i, 't, E>(parser: &mut cssparser::Parser<'i, 't>) -> Result<CalcValue, cssparser::ParseError<'i, E>> {
+    let location = parser.current_source_location();
+    let token = parser.next()?.clone();
+
+    match token {
+        Token::Number { value, .. } => Ok(CalcValue::Number(value)),
+        Token::Percentage { unit_value, .. } => Ok(CalcValue::Percentage(unit_value)),
+        Token::Dimension { value, ref unit, .. } => {
+            let degrees = match unit.to_ascii_lowercase().as_str() {
+                "deg" => value,
+                "grad" => value * 360.0 / 400.0,
+                "rad" => value.to_degrees(),
+                "turn" => value * 360.0,
+                _ => return Err(location.new_unexpected_token_error(token)),
+            };
+            Ok(CalcValue::Angle(degrees))
+        }
+        Token::ParenthesisBlock => parser.parse_nested_block(parse_sum),
+        Token::Function(ref name) if name.eq_ignore_ascii_case("calc") => parser.parse_nested_block(parse_sum),
+        _ => Err(location.new_unexpected_token_error(token)),
+    }
+}
+
+impl CalcValue {
+    fn value(&self) -> f32 {
+        match self {
+            CalcValue::Number(value) | CalcValue::Percentage(value) | CalcValue::Angle(value) => *value,
+        }
+    }
+
+    fn with_value(&self, value: f32) -> CalcValue {
+        match self {
+            CalcValue::Number(_) => CalcValue::Number(value),
+            CalcValue::Percentage(_) => CalcValue::Percentage(value),
+            CalcValue::Angle(_) => CalcValue::Angle(value),
+        }
+    }
+
+    fn add(&self, other: CalcValue, sign: f32) -> Option<CalcValue> {
+        if std::mem::discriminant(self) != std::mem::discriminant(&other) {
+            return None;
+        }
+        Some(self.with_value(self.value() + other.value() * sign))
+    }
+
+    fn multiply(&self, other: CalcValue) -> Option<CalcValue> {
+        match (self, other) {
+            (CalcValue::Number(value), other) => Some(other.with_value(value * other.value())),
+            (value, CalcValue::Number(factor)) => Some(value.with_value(value.value() * factor)),
+            _ => None,
+        }
+    }
+
+    fn divide(&self, other: CalcValue) -> Option<CalcValue> {
+        match other {
+            CalcValue::Number(divisor) if divisor != 0.0 => Some(self.with_value(self.value() / divisor)),
+            _ => None,
+        }
+    }
+}
@@ -129,5 +129,13 @@ pub fn parse_property_definition<'i, 't>(
         }
     }
 
-    Ok(parser.definition)
+    // A typed property that does not inherit has nothing to fall back to when
+    // it is not set, so an initial value is required. Universal syntax does not
+    // have this requirement as it is treated as a "guaranteed-invalid" value.
+    let definition = parser.definition;
+    if definition.syntax != ParsedPropertySyntax::Universal && !definition.inherit && definition.initial.is_empty() {
+        return parse_error(input, ParseErrorKind::InvalidPropertyDefinition, format!("'initial-value' is required for property {}", definition.name))
+    }
+
+    Ok(definition)
 }
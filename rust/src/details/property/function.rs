@@ -4,8 +4,8 @@
 use std::sync::{RwLock, OnceLock};
 use std::collections::hash_map::HashMap;
 
-use crate::property::property_definition;
-use crate::value::{Value, ValueData, Color, ColorOperation, Dimension};
+use crate::property::{property_definition, CustomProperty};
+use crate::value::{Value, ValueData, Color, ColorData, ColorOperation, Dimension, Unit};
 
 use crate::details::{parse_error, ParseError, ParseErrorKind, SourceLocation};
 
@@ -15,49 +15,118 @@ use super::value::parse_values;
 pub type PropertyFunctionResult<'i> = Result<Vec<Value>, cssparser::ParseError<'i, ParseError>>;
 pub type PropertyFunction = for <'a, 'i, 't> fn(&'a mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i>;
 
-fn property_functions() -> &'static RwLock<HashMap<String, PropertyFunction>> {
-    static FUNCTIONS: OnceLock<RwLock<HashMap<String, PropertyFunction>>> = OnceLock::new();
+const MIX_SYNTAX: &str = "<color>, <color>, <number>";
+const CUSTOM_COLOR_SYNTAX: &str = "<string>, <string>#";
+
+// A registered function, with the syntax of its arguments if it has one.
+#[derive(Clone)]
+struct RegisteredFunction {
+    function: PropertyFunction,
+    syntax: Option<String>,
+}
+
+fn property_functions() -> &'static RwLock<HashMap<String, RegisteredFunction>> {
+    static FUNCTIONS: OnceLock<RwLock<HashMap<String, RegisteredFunction>>> = OnceLock::new();
     FUNCTIONS.get_or_init(|| {
-        let mut map: HashMap<String, PropertyFunction> = HashMap::new();
-        map.insert(String::from("var"), var);
-        map.insert(String::from("mix"), mix);
-        map.insert(String::from("custom-color"), custom_color);
-        map.insert(String::from("modify-color"), modify_color);
+        let builtins: [(&str, PropertyFunction, Option<&str>); 8] = [
+            ("var", var, None),
+            ("mix", mix, Some(MIX_SYNTAX)),
+            ("custom-color", custom_color, Some(CUSTOM_COLOR_SYNTAX)),
+            ("modify-color", modify_color, None),
+            ("rgb", relative_rgb, None),
+            ("rgba", relative_rgb, None),
+            ("hsl", relative_hsl, None),
+            ("hsla", relative_hsl, None),
+        ];
+
+        let mut map: HashMap<String, RegisteredFunction> = HashMap::new();
+        for (name, function, syntax) in builtins {
+            map.insert(String::from(name), RegisteredFunction { function, syntax: syntax.map(String::from) });
+        }
         RwLock::new(map)
     })
 }
 
+// Function names are ASCII case-insensitive, so they are stored in lowercase.
 pub fn property_function(name: &str) -> Option<PropertyFunction> {
     if let Ok(functions) = property_functions().read() {
-        if let Some(function) = functions.get(name) {
-            return Some(*function);
+        if let Some(registered) = functions.get(&name.to_ascii_lowercase()) {
+            return Some(registered.function);
         }
     }
 
     None{}
 }
 
+/// The syntax of the arguments of the function `name`.
+///
+/// Returns None if there is no such function or it was registered without a syntax.
+pub fn property_function_syntax(name: &str) -> Option<String> {
+    property_functions().read().ok()?.get(&name.to_ascii_lowercase())?.syntax.clone()
+}
+
 #[allow(dead_code)]
 pub fn add_property_function(name: &str, function: PropertyFunction) -> bool {
+    register_property_function(name, RegisteredFunction { function, syntax: None })
+}
+
+/// Register `function` as `name`, with `syntax` describing its arguments.
+///
+/// Returns false if a function with `name` already exists or `syntax` is not a
+/// valid property syntax.
+#[allow(dead_code)]
+pub fn add_property_function_with_syntax(name: &str, syntax: &str, function: PropertyFunction) -> bool {
+    if parse_syntax(syntax, SourceLocation::from_file("inline")).is_err() {
+        return false;
+    }
+
+    register_property_function(name, RegisteredFunction { function, syntax: Some(syntax.to_string()) })
+}
+
+fn register_property_function(name: &str, registered: RegisteredFunction) -> bool {
     if let Ok(mut functions) = property_functions().write() {
-        if functions.get(name).is_some() {
+        let name = name.to_ascii_lowercase();
+        if functions.get(&name).is_some() {
             return false;
         }
 
-        functions.insert(name.to_string(), function);
+        functions.insert(name, registered);
     }
 
     true
 }
 
 // Helper function to parse function arguments based on a CSS property syntax
+//
+// Arguments that do not match the syntax are reported as an invalid value.
 fn parse_arguments<'i, 't>(syntax: &str, parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i> {
     let syntax_result = parse_syntax(syntax, SourceLocation::from_file("inline"));
     if let Err(error) = syntax_result {
         return Err(parser.new_custom_error(error));
     }
 
-    parse_values(syntax_result.as_ref().unwrap(), parser)
+    let values = parse_values(syntax_result.as_ref().unwrap(), parser).map_err(|mut error| {
+        if let cssparser::ParseErrorKind::Custom(ParseError { kind, message, .. }) = &mut error.kind {
+            if *kind == ParseErrorKind::PropertyValueDoesNotMatchSyntax {
+                *kind = ParseErrorKind::InvalidPropertyValue;
+                *message = format!("Invalid function arguments: {}", message);
+            }
+        }
+        error
+    })?;
+
+    expect_exhausted(parser)?;
+    Ok(values)
+}
+
+// Helper function to ensure no tokens remain after the arguments of a function
+fn expect_exhausted<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Result<(), cssparser::ParseError<'i, ParseError>> {
+    if parser.is_exhausted() {
+        return Ok(());
+    }
+
+    let token = parser.next()?.clone();
+    parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Unexpected trailing token {:?} in function arguments", token))
 }
 
 // Parse `var(<custom-property-name>, <declaration-value>?)`
@@ -65,6 +134,10 @@ fn var<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult
     let var_name = parser.expect_ident()?.to_string();
     let property_definition = property_definition(var_name.as_str());
     if let Some(definition) = property_definition {
+        // The fallback is not used, but it still needs to be valid.
+        if !parser.is_exhausted() {
+            var_fallback(parser)?;
+        }
         return Ok(definition.initial.clone());
     }
 
@@ -72,26 +145,140 @@ fn var<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult
         return parse_error(parser, ParseErrorKind::UnknownProperty, format!("No custom property {} was defined", var_name));
     }
 
+    var_fallback(parser)
+}
+
+// The fallback of var() is everything after the first comma, including any
+// further commas, so parse the remaining tokens as a whole.
+fn var_fallback<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i> {
     parser.expect_comma()?;
-    parse_values(&ParsedPropertySyntax::Universal, parser)
+
+    let values = parse_values(&ParsedPropertySyntax::Universal, parser)?;
+    expect_exhausted(parser)?;
+    Ok(values)
+}
+
+/// Resolve the value of the custom property `name` from `properties`.
+///
+/// `var()` references are resolved against `properties` rather than the
+/// registered property definitions, following chains of references. A
+/// reference to a property that is not in `properties` uses the fallback of
+/// `var()`, if it has one. Cyclic references are an error.
+pub fn resolve_custom_property(name: &str, properties: &[CustomProperty]) -> Result<Vec<Value>, ParseError> {
+    let property = properties.iter().find(|property| property.name == name).ok_or_else(|| ParseError {
+        kind: ParseErrorKind::UnknownProperty,
+        message: format!("No custom property {} was defined", name),
+        location: SourceLocation::from_file(""),
+    })?;
+
+    let source = substitute_custom_property(property, properties, &mut Vec::new())?;
+
+    let mut parser_input = cssparser::ParserInput::new(&source);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    parser.parse_entirely(|parser| parse_values(&ParsedPropertySyntax::Universal, parser))
+        .map_err(|error| ParseError { location: property.location.clone(), ..crate::details::parse_error_from_cssparser_error(&error, String::new()) })
+}
+
+// Returns the source of `property` with all var() references replaced.
+//
+// `resolving` contains the names of the properties that are being resolved,
+// to detect cycles.
+fn substitute_custom_property(property: &CustomProperty, properties: &[CustomProperty], resolving: &mut Vec<String>) -> Result<String, ParseError> {
+    if resolving.contains(&property.name) {
+        resolving.push(property.name.clone());
+        return Err(ParseError {
+            kind: ParseErrorKind::InvalidPropertyValue,
+            message: format!("Cyclic reference between custom properties {}", resolving.join(" -> ")),
+            location: property.location.clone(),
+        });
+    }
+
+    resolving.push(property.name.clone());
+
+    let mut parser_input = cssparser::ParserInput::new(&property.source);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let mut output = String::new();
+    let result = substitute_var_references(&mut parser, property, properties, resolving, &mut output);
+
+    resolving.pop();
+
+    match result {
+        Ok(()) => Ok(output),
+        Err(cssparser::ParseError { kind: cssparser::ParseErrorKind::Custom(error), .. }) => Err(error),
+        Err(error) => Err(ParseError { location: property.location.clone(), ..crate::details::parse_error_from_cssparser_error(&error, String::new()) }),
+    }
+}
+
+// Copy the tokens of `parser`, which parses the source of `property`, to
+// `output`, replacing var() references with the source of the referenced property.
+fn substitute_var_references<'i, 't>(parser: &mut cssparser::Parser<'i, 't>, property: &CustomProperty, properties: &[CustomProperty], resolving: &mut Vec<String>, output: &mut String) -> Result<(), cssparser::ParseError<'i, ParseError>> {
+    use cssparser::{ToCss, Token};
+
+    while let Ok(token) = parser.next_including_whitespace_and_comments() {
+        let token = token.clone();
+        let closing = match &token {
+            Token::Function(name) if name.eq_ignore_ascii_case("var") => {
+                parser.parse_nested_block(|parser| {
+                    let name = parser.expect_ident()?.to_string();
+                    if let Some(referenced) = properties.iter().find(|property| property.name == name) {
+                        let source = substitute_custom_property(referenced, properties, resolving).map_err(|error| parser.new_custom_error(error))?;
+                        output.push_str(&source);
+                        while parser.next().is_ok() {}
+                        Ok(())
+                    } else if parser.try_parse(|parser| parser.expect_comma()).is_ok() {
+                        substitute_var_references(parser, property, properties, resolving, output)
+                    } else {
+                        Err(parser.new_custom_error(ParseError {
+                            kind: ParseErrorKind::UnknownProperty,
+                            message: format!("No custom property {} was defined", name),
+                            location: property.location.clone(),
+                        }))
+                    }
+                })?;
+                continue;
+            }
+            Token::Function(_) | Token::ParenthesisBlock => ")",
+            Token::SquareBracketBlock => "]",
+            Token::CurlyBracketBlock => "}",
+            _ => {
+                output.push_str(&token.to_css_string());
+                continue;
+            }
+        };
+
+        output.push_str(&token.to_css_string());
+        parser.parse_nested_block(|parser| substitute_var_references(parser, property, properties, resolving, output))?;
+        output.push_str(closing);
+    }
+
+    Ok(())
+}
+
+// Returns the value of a number argument, which may have been parsed as integer.
+fn number_argument(value: &Value) -> Option<f32> {
+    match &value.data {
+        ValueData::Dimension(dimension) => Some(dimension.value),
+        ValueData::Integer(integer) => Some(*integer as f32),
+        _ => None,
+    }
 }
 
 // Parse `mix(<color>, <color>, <number>)`
 fn mix<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i> {
-    let values = parse_arguments("<color>, <color>, <number>", parser)?;
+    let values = parse_arguments(MIX_SYNTAX, parser)?;
 
     let first_color: Color = values[0].clone().into();
     let second_color: Box<Color> = Box::new(values[1].clone().into());
-    let amount: Dimension = values[2].clone().into();
+    let amount = number_argument(&values[2]).unwrap_or(0.0);
 
-    let mixed = Color::modified(&first_color, ColorOperation::mix(&second_color, amount.value));
+    let mixed = Color::modified(&first_color, ColorOperation::mix(&second_color, amount));
 
     Ok(vec![Value::from(mixed)])
 }
 
 // Parse `custom-color(<string>, <string>#)`
 fn custom_color<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i> {
-    let values = parse_arguments("<string>, <string>#", parser)?;
+    let values = parse_arguments(CUSTOM_COLOR_SYNTAX, parser)?;
 
     let (source, args) = values.split_first().unwrap();
 
@@ -100,28 +287,200 @@ fn custom_color<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunct
     Ok(vec![Value::from(Color::custom(source.to_string(), string_args))])
 }
 
-// Parse `modify-color(<color> (add | subtract | multiply | set-alpha) (<color> | <number>))
+// Returns the value of a color channel argument, clamped to [0, 255].
+//
+// The alpha channel takes a number between 0 and 1, the other channels take a
+// number between 0 and 255. Percentages are relative to the channel's range.
+fn channel_argument(value: &Value, alpha: bool) -> Option<u8> {
+    let channel = match &value.data {
+        ValueData::Dimension(Dimension { value, unit: Unit::Percent }) => value * 255.0,
+        ValueData::Dimension(Dimension { value, unit: Unit::Number }) => if alpha { value * 255.0 } else { *value },
+        ValueData::Integer(integer) => if alpha { *integer as f32 * 255.0 } else { *integer as f32 },
+        _ => return None,
+    };
+    Some(channel.clamp(0.0, 255.0) as u8)
+}
+
+// Parse `modify-color(<color> (((add | subtract | multiply) <color>) | (mix <color> <number>) | <set-channel>+))`
+//
+// Where `<set-channel>` is one of `set-red`, `set-green`, `set-blue` or
+// `set-alpha` followed by a channel value, `set-rgb` followed by three channel
+// values or `set-rgba` followed by four channel values.
 fn modify_color<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i> {
-    let values = parse_arguments("<color> (((add | subtract | multiply) <color>) | (set-alpha <number>))", parser)?;
-    let color: Color = values[0].clone().into();
-    let operation: String = values[1].to_string();
-    let data: Value = values[2].clone();
-
-    let result = match operation.as_str() {
-        "add" => Color::modified(&color, ColorOperation::add(&data.into())),
-        "subtract" => Color::modified(&color, ColorOperation::subtract(&data.into())),
-        "multiply" => Color::modified(&color, ColorOperation::multiply(&data.into())),
-        "set-alpha" => {
-            let alpha = match data.data {
-                ValueData::Dimension(dimension) => {
-                    Some((dimension.value * 255.0) as u8)
-                }
-                _ => None,
+    if let Ok(values) = parser.try_parse(|parser| parse_arguments("<color> (((add | subtract | multiply) <color>) | (mix <color> <number>))", parser)) {
+        let color: Color = values[0].clone().into();
+        let operation: String = values[1].to_string();
+        let data: Value = values[2].clone();
+
+        let result = match operation.as_str() {
+            "add" => Color::modified(&color, ColorOperation::add(&data.into())),
+            "subtract" => Color::modified(&color, ColorOperation::subtract(&data.into())),
+            "multiply" => Color::modified(&color, ColorOperation::multiply(&data.into())),
+            "mix" => {
+                let amount = number_argument(&values[3]).unwrap_or(0.0);
+                Color::modified(&color, ColorOperation::mix(&data.into(), amount))
+            },
+            _ => return parse_error(parser, ParseErrorKind::Unknown, String::from("Unexpected modifiy-color argument")),
+        };
+
+        return Ok(vec![Value::from(result)]);
+    }
+
+    let values = parse_arguments("*", parser)?;
+    let Some(ValueData::Color(color)) = values.first().map(|value| &value.data) else {
+        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Expected a color as first argument of modify-color"));
+    };
+
+    // Channels in the order red, green, blue, alpha.
+    let mut channels: [Option<u8>; 4] = [None; 4];
+    let mut remaining = &values[1..];
+    if remaining.is_empty() {
+        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Expected an operation for modify-color"));
+    }
+
+    while let Some((operation, rest)) = remaining.split_first() {
+        let indices: &[usize] = match operation.to_string().as_str() {
+            "set-red" => &[0],
+            "set-green" => &[1],
+            "set-blue" => &[2],
+            "set-alpha" => &[3],
+            "set-rgb" => &[0, 1, 2],
+            "set-rgba" => &[0, 1, 2, 3],
+            other => return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Unexpected modify-color operation {}", other)),
+        };
+
+        if rest.len() < indices.len() {
+            return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Expected {} values for {}", indices.len(), operation));
+        }
+
+        for (index, value) in indices.iter().zip(rest) {
+            let Some(channel) = channel_argument(value, *index == 3) else {
+                return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Invalid channel value {} for {}", value, operation));
             };
-            Color::modified(&color, ColorOperation::set(None, None, None, alpha))
-        },
-        _ => return parse_error(parser, ParseErrorKind::Unknown, String::from("Unexpected modifiy-color argument")),
+            channels[*index] = Some(channel);
+        }
+
+        remaining = &rest[indices.len()..];
+    }
+
+    let [r, g, b, a] = channels;
+    Ok(vec![Value::from(Color::modified(color, ColorOperation::set(r, g, b, a)))])
+}
+
+// Converts red, green and blue in [0, 1] to hue in degrees and saturation and lightness in [0, 1].
+fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+
+    (hue * 60.0, saturation, lightness)
+}
+
+// A channel of a relative color, either a channel of the base color or a literal value.
+enum RelativeChannel {
+    Base(usize),
+    Value(f32),
+}
+
+// Parse `rgb(from <color> <channel>{3} [/ <channel>]?)`
+//
+// Plain `rgb()` colors are handled by the color parser, so this only handles
+// the relative color syntax. Channels are either one of the channel keywords
+// `r`, `g`, `b` and `alpha`, a number or a percentage.
+fn relative_rgb<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i> {
+    relative_color(parser, ["r", "g", "b", "alpha"], [255.0, 255.0, 255.0, 1.0])
+}
+
+// Parse `hsl(from <color> <channel>{3} [/ <channel>]?)`
+//
+// Like `relative_rgb`, but with the channel keywords `h`, `s`, `l` and `alpha`.
+// Saturation and lightness are numbers in [0, 100].
+fn relative_hsl<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i> {
+    relative_color(parser, ["h", "s", "l", "alpha"], [360.0, 100.0, 100.0, 1.0])
+}
+
+// Helper for the relative color syntax.
+//
+// If the base color is a plain RGBA color the result is computed directly.
+// Otherwise, only RGB channels that are either passed through unchanged or
+// replaced with a literal can be represented, as a set operation.
+fn relative_color<'i, 't>(parser: &mut cssparser::Parser<'i, 't>, keywords: [&str; 4], ranges: [f32; 4]) -> PropertyFunctionResult<'i> {
+    if parser.try_parse(|parser| parser.expect_ident_matching("from")).is_err() {
+        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Invalid color function"));
+    }
+
+    let values = parse_arguments("*", parser)?;
+    let Some(ValueData::Color(color)) = values.first().map(|value| &value.data) else {
+        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Expected a color after from"));
+    };
+
+    let channel_values: Vec<&Value> = match &values[1..] {
+        [first, second, third] => vec![first, second, third],
+        [first, second, third, Value { data: ValueData::Slash }, alpha] => vec![first, second, third, alpha],
+        _ => return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Expected three channels and an optional alpha channel")),
+    };
+
+    let mut channels = [0, 1, 2, 3].map(RelativeChannel::Base);
+    for (index, value) in channel_values.iter().enumerate() {
+        channels[index] = match &value.data {
+            ValueData::Keyword(name) if keywords.contains(&name.as_str()) => RelativeChannel::Base(keywords.iter().position(|keyword| keyword == name).unwrap()),
+            ValueData::Dimension(Dimension { value, unit: Unit::Percent }) => RelativeChannel::Value(value * ranges[index]),
+            ValueData::Dimension(Dimension { value, unit: Unit::Number | Unit::Degrees }) => RelativeChannel::Value(*value),
+            ValueData::Integer(integer) => RelativeChannel::Value(*integer as f32),
+            _ => return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Invalid color channel {}", value)),
+        };
+    }
+
+    let ColorData::Rgba { r, g, b, a } = color.data else {
+        if keywords[0] != "r" {
+            return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Relative hsl() colors need a plain color as base"));
+        }
+
+        let mut set: [Option<u8>; 4] = [None; 4];
+        for (index, channel) in channels.iter().enumerate() {
+            match channel {
+                RelativeChannel::Base(source) if *source == index => (),
+                RelativeChannel::Base(_) => return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Channels of a relative color can only be reordered for a plain base color")),
+                RelativeChannel::Value(value) => set[index] = Some((value / ranges[index] * 255.0).round().clamp(0.0, 255.0) as u8),
+            }
+        }
+        let [r, g, b, a] = set;
+        return Ok(vec![Value::from(Color::modified(color, ColorOperation::set(r, g, b, a)))]);
+    };
+
+    let base = if keywords[0] == "r" {
+        [r as f32, g as f32, b as f32, a as f32 / 255.0]
+    } else {
+        let (h, s, l) = rgb_to_hsl(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+        [h, s * 100.0, l * 100.0, a as f32 / 255.0]
+    };
+
+    let resolved = channels.map(|channel| match channel {
+        RelativeChannel::Base(source) => base[source],
+        RelativeChannel::Value(value) => value,
+    });
+
+    let (red, green, blue) = if keywords[0] == "r" {
+        (resolved[0] / 255.0, resolved[1] / 255.0, resolved[2] / 255.0)
+    } else {
+        let hue = resolved[0].rem_euclid(360.0) / 360.0;
+        cssparser_color::hsl_to_rgb(hue, (resolved[1] / 100.0).clamp(0.0, 1.0), (resolved[2] / 100.0).clamp(0.0, 1.0))
     };
 
-    Ok(vec![Value::from(result)])
+    let to_u8 = |value: f32| (value * 255.0).round().clamp(0.0, 255.0) as u8;
+    Ok(vec![Value::from(Color::rgba(to_u8(red), to_u8(green), to_u8(blue), to_u8(resolved[3])))])
 }
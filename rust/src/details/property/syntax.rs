@@ -7,16 +7,17 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     combinator::recognize,
+    number::complete::float,
     character::complete::{char, satisfy, space0, digit1},
     error::ErrorKind,
-    multi::{many0_count, many1},
+    multi::{many0, many0_count, many1},
     sequence::{delimited, pair, preceded, terminated, separated_pair},
 };
 
 use crate::details::{ParseError, ParseErrorKind, SourceLocation};
 use super::value::ParseValuesResult;
 
-use crate::value::{Value, ValueData};
+use crate::value::{Dimension, DimensionClass, Value, ValueData};
 
 struct SyntaxParseError<I>(I, String);
 
@@ -65,10 +66,14 @@ pub enum DataType {
     Url,
     Integer,
     Angle,
+    AnglePercentage,
     Time,
+    TimePercentage,
     Resolution,
+    Flex,
     TransformFunction,
     CustomIdent,
+    UnicodeRange,
 }
 
 impl std::fmt::Display for DataType {
@@ -83,14 +88,28 @@ impl std::fmt::Display for DataType {
             Self::Url => write!(f, "url"),
             Self::Integer => write!(f, "integer"),
             Self::Angle => write!(f, "angle"),
+            Self::AnglePercentage => write!(f, "angle-percentage"),
             Self::Time => write!(f, "time"),
+            Self::TimePercentage => write!(f, "time-percentage"),
             Self::Resolution => write!(f, "resolution"),
+            Self::Flex => write!(f, "flex"),
             Self::TransformFunction => write!(f, "transform-function"),
             Self::CustomIdent => write!(f, "custom-ident"),
+            Self::UnicodeRange => write!(f, "unicode-range"),
         }
     }
 }
 
+fn range_bound_to_string(bound: f32) -> String {
+    if bound == f32::INFINITY {
+        String::from("∞")
+    } else if bound == f32::NEG_INFINITY {
+        String::from("-∞")
+    } else {
+        bound.to_string()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum SyntaxComponent {
     DataType(DataType),
@@ -98,7 +117,9 @@ pub enum SyntaxComponent {
     SpaceSeparatedList(DataType),
     CommaSeparatedList(DataType),
     Repeat{data_type: DataType, minimum: usize, maximum: usize},
+    Range{data_type: DataType, minimum: f32, maximum: f32},
     Comma,
+    Slash,
 }
 
 impl std::fmt::Display for SyntaxComponent {
@@ -108,8 +129,10 @@ impl std::fmt::Display for SyntaxComponent {
             Self::Keyword(keyword) => write!(f, "{}", keyword),
             Self::SpaceSeparatedList(data_type) => write!(f, "<{}>+", data_type),
             Self::CommaSeparatedList(data_type) => write!(f, "<{}>#", data_type),
-            Self::Repeat { data_type, minimum, maximum } => write!(f, "<{}>{{{}, {}}}", data_type, minimum, maximum),
-            Self::Comma => write!(f, ",")
+            Self::Repeat { data_type, minimum, maximum } => write!(f, "<{}>{{{},{}}}", data_type, minimum, maximum),
+            Self::Range { data_type, minimum, maximum } => write!(f, "<{} [{}, {}]>", data_type, range_bound_to_string(*minimum), range_bound_to_string(*maximum)),
+            Self::Comma => write!(f, ","),
+            Self::Slash => write!(f, "/"),
         }
     }
 }
@@ -153,6 +176,85 @@ pub enum ParsedPropertySyntax {
     Expression(Vec<SyntaxAlternatives>),
 }
 
+impl SyntaxComponent {
+    /// The data type this component refers to, if any.
+    pub fn data_type(&self) -> Option<&DataType> {
+        match self {
+            Self::DataType(data_type)
+            | Self::SpaceSeparatedList(data_type)
+            | Self::CommaSeparatedList(data_type)
+            | Self::Repeat { data_type, .. }
+            | Self::Range { data_type, .. } => Some(data_type),
+            Self::Keyword(_) | Self::Comma | Self::Slash => None,
+        }
+    }
+}
+
+fn collect_group_components<'a>(group: &'a SyntaxGroup, components: &mut Vec<&'a SyntaxComponent>) {
+    match group {
+        SyntaxGroup::Component(component) => components.push(component),
+        SyntaxGroup::Expression(expression) => collect_components(expression, components),
+    }
+}
+
+fn collect_components<'a>(expression: &'a [SyntaxAlternatives], components: &mut Vec<&'a SyntaxComponent>) {
+    for alternatives in expression {
+        match alternatives {
+            SyntaxAlternatives::Component(component) => components.push(component),
+            SyntaxAlternatives::Group(group) => collect_group_components(group, components),
+            SyntaxAlternatives::Alternatives(groups) => {
+                for group in groups {
+                    collect_group_components(group, components);
+                }
+            }
+        }
+    }
+}
+
+impl ParsedPropertySyntax {
+    /// Render this syntax back to its textual form, like `<length> | auto`.
+    pub fn to_syntax_string(&self) -> String {
+        match self {
+            Self::Empty => String::new(),
+            Self::Universal => String::from("*"),
+            Self::Expression(expression) => expression_to_string(expression, " "),
+        }
+    }
+
+    /// All components referenced by this syntax, in order of appearance.
+    pub fn components(&self) -> Vec<&SyntaxComponent> {
+        let mut components = Vec::new();
+        if let Self::Expression(expression) = self {
+            collect_components(expression, &mut components);
+        }
+        components
+    }
+
+    /// The data types referenced by this syntax, without duplicates.
+    pub fn data_types(&self) -> Vec<DataType> {
+        let mut result = Vec::new();
+        for data_type in self.components().into_iter().filter_map(SyntaxComponent::data_type) {
+            if !result.contains(data_type) {
+                result.push(data_type.clone());
+            }
+        }
+        result
+    }
+
+    /// The keywords referenced by this syntax, without duplicates.
+    pub fn keywords(&self) -> Vec<String> {
+        let mut result = Vec::new();
+        for component in self.components() {
+            if let SyntaxComponent::Keyword(keyword) = component {
+                if !result.contains(keyword) {
+                    result.push(keyword.clone());
+                }
+            }
+        }
+        result
+    }
+}
+
 
 fn expression_to_string<T>(expression: &[T], separator: &str) -> String
     where T: std::fmt::Display {
@@ -171,7 +273,9 @@ fn expression_to_string<T>(expression: &[T], separator: &str) -> String
  * space_separated_list ::= data_type "+"
  * comma_separated_list ::= data_type "#"
  * repeats ::= data_type "{" [0-9]+ "," [0-9]+ "}"
- * component ::= data_type | keyword | space_separated | comma_separated | repeats
+ * range_bound ::= number | "-"? "∞"
+ * range ::= "<" data_type_name " "? "[" range_bound "," range_bound "]" ">"
+ * component ::= data_type | keyword | space_separated | comma_separated | repeats | range | "," | "/"
  * group ::= component | ("(" expression ")")
  * alternatives ::= group (" | " group)*
  * expression ::= alternatives (" " alternatives)*
@@ -234,11 +338,15 @@ fn data_type_name(input: &str) -> SyntaxParseResult<&str, &str> {
         tag("color"),
         tag("url"),
         tag("integer"),
+        tag("angle-percentage"),
         tag("angle"),
+        tag("time-percentage"),
         tag("time"),
         tag("resolution"),
+        tag("flex"),
         tag("transform-function"),
         tag("custom-ident"),
+        tag("unicode-range"),
     )).parse(input);
 
     if let Ok((remain, name)) = result {
@@ -248,32 +356,82 @@ fn data_type_name(input: &str) -> SyntaxParseResult<&str, &str> {
     }
 }
 
+fn data_type_from_name(name: &str) -> Option<DataType> {
+    match name {
+        "length-percentage" => Some(DataType::LengthPercentage),
+        "length" => Some(DataType::Length),
+        "number" => Some(DataType::Number),
+        "percentage" => Some(DataType::Percentage),
+        "string" => Some(DataType::String),
+        "color" => Some(DataType::Color),
+        "url" => Some(DataType::Url),
+        "integer" => Some(DataType::Integer),
+        "angle" => Some(DataType::Angle),
+        "angle-percentage" => Some(DataType::AnglePercentage),
+        "time" => Some(DataType::Time),
+        "time-percentage" => Some(DataType::TimePercentage),
+        "resolution" => Some(DataType::Resolution),
+        "flex" => Some(DataType::Flex),
+        "transform-function" => Some(DataType::TransformFunction),
+        "custom-ident" => Some(DataType::CustomIdent),
+        "unicode-range" => Some(DataType::UnicodeRange),
+        _ => None,
+    }
+}
+
 fn data_type(input: &str) -> SyntaxParseResult<&str, SyntaxComponent> {
     let result = delimited(char('<'), data_type_name, char('>')).parse(input);
 
     if let Ok((remain, name)) = result {
-        let data_type = match name {
-            "length-percentage" => DataType::LengthPercentage,
-            "length" => DataType::Length,
-            "number" => DataType::Number,
-            "percentage" => DataType::Percentage,
-            "string" => DataType::String,
-            "color" => DataType::Color,
-            "url" => DataType::Url,
-            "integer" => DataType::Integer,
-            "angle" => DataType::Angle,
-            "time" => DataType::Time,
-            "resolution" => DataType::Resolution,
-            "transform-function" => DataType::TransformFunction,
-            "custom-ident" => DataType::CustomIdent,
-            _ => return make_failure(input, String::from("Invalid data type"))
-        };
-        Ok((remain, SyntaxComponent::DataType(data_type)))
+        if let Some(data_type) = data_type_from_name(name) {
+            Ok((remain, SyntaxComponent::DataType(data_type)))
+        } else {
+            make_failure(input, String::from("Invalid data type"))
+        }
     } else {
         make_error(input, String::from("Input is not a data type"))
     }
 }
 
+fn range_bound(input: &str) -> SyntaxParseResult<&str, f32> {
+    let result = alt((
+        tag::<&str, &str, SyntaxParseError<_>>("-∞").map(|_| f32::NEG_INFINITY),
+        tag("∞").map(|_| f32::INFINITY),
+        float,
+    )).parse(input);
+
+    if let Ok((remain, bound)) = result {
+        Ok((remain, bound))
+    } else {
+        make_error(input, String::from("Input is not a valid range bound"))
+    }
+}
+
+fn range(input: &str) -> SyntaxParseResult<&str, SyntaxComponent> {
+    let result = delimited(
+        char('<'),
+        pair(
+            data_type_name,
+            delimited(
+                pair(space0, char('[')),
+                separated_pair(range_bound, delimited(space0, char(','), space0), range_bound),
+                char(']'),
+            ),
+        ),
+        char('>'),
+    ).parse(input);
+
+    if let Ok((remain, (name, (minimum, maximum)))) = result {
+        if let Some(data_type) = data_type_from_name(name) {
+            if minimum <= maximum {
+                return Ok((remain, SyntaxComponent::Range{data_type, minimum, maximum}));
+            }
+        }
+    }
+
+    make_error(input, String::from("Input is not a valid range"))
+}
+
 fn space_separated_list(input: &str) -> SyntaxParseResult<&str, SyntaxComponent> {
     let (remain, data_type) = terminated(data_type, char('+')).parse(input)?;
     if let SyntaxComponent::DataType(data_type_name) = data_type {
@@ -317,16 +475,22 @@ fn comma(input: &str) -> SyntaxParseResult<&str, SyntaxComponent> {
     char(',').parse(input).map(|r| (r.0, SyntaxComponent::Comma))
 }
 
+fn slash(input: &str) -> SyntaxParseResult<&str, SyntaxComponent> {
+    char('/').parse(input).map(|r| (r.0, SyntaxComponent::Slash))
+}
+
 fn component(input: &str) -> SyntaxParseResult<&str, SyntaxComponent> {
     let result = delimited(
         space0,
         alt((
+            range,
             repeat,
             space_separated_list,
             comma_separated_list,
             data_type,
             keyword,
             comma,
+            slash,
         )),
         space0
     ).parse(input);
@@ -338,16 +502,19 @@ fn component(input: &str) -> SyntaxParseResult<&str, SyntaxComponent> {
     }
 }
 
-fn group(input: &str) -> SyntaxParseResult<&str, SyntaxGroup> {
+// The maximum number of nested groups, syntax strings may come from untrusted input.
+const MAX_GROUP_DEPTH: usize = 32;
+
+fn group(input: &str, depth: usize) -> SyntaxParseResult<&str, SyntaxGroup> {
     let expression = delimited(
         delimited(space0, char('('), space0),
-        expression,
+        |input| expression(input, depth + 1),
         delimited(space0, char(')'), space0),
     ).parse(input);
-    if let Ok((remain, result)) = expression {
-        if let ParsedPropertySyntax::Expression(exp) = result {
-            return Ok((remain, SyntaxGroup::Expression(exp)));
-        }
+    match expression {
+        Ok((remain, ParsedPropertySyntax::Expression(exp))) => return Ok((remain, SyntaxGroup::Expression(exp))),
+        Err(nom::Err::Failure(error)) => return Err(nom::Err::Failure(error)),
+        _ => (),
     }
 
     let component = component.parse(input);
@@ -358,34 +525,40 @@ fn group(input: &str) -> SyntaxParseResult<&str, SyntaxGroup> {
     }
 }
 
-fn alternatives(input: &str) -> SyntaxParseResult<&str, SyntaxAlternatives> {
-    let alternatives = pair(group, many1(preceded(char('|'), group))).parse(input);
-    if let Ok((remain, result)) = alternatives {
-        let mut output = Vec::new();
-        output.push(result.0);
-        output.extend(result.1);
+fn alternatives(input: &str, depth: usize) -> SyntaxParseResult<&str, SyntaxAlternatives> {
+    // The first group is only parsed once, as parsing it again for every
+    // level of nesting would take exponential time.
+    let (remain, first) = match group(input, depth) {
+        Ok(result) => result,
+        Err(nom::Err::Failure(error)) => return Err(nom::Err::Failure(error)),
+        Err(_) => return make_error(input, String::from("Input did not match an alternatives block")),
+    };
+
+    let (remain, rest) = many0(preceded(char('|'), |input| group(input, depth))).parse(remain)?;
+    if !rest.is_empty() {
+        let mut output = vec![first];
+        output.extend(rest);
         return Ok((remain, SyntaxAlternatives::Alternatives(output)));
     }
 
-    let group = group.parse(input);
-    if let Ok((remain, group_data)) = group {
-        if let SyntaxGroup::Component(comp) = group_data {
-            Ok((remain, SyntaxAlternatives::Component(comp)))
-        } else {
-            Ok((remain, SyntaxAlternatives::Group(group_data)))
-        }
+    if let SyntaxGroup::Component(comp) = first {
+        Ok((remain, SyntaxAlternatives::Component(comp)))
     } else {
-        make_error(input, String::from("Input did not match an alternatives block"))
+        Ok((remain, SyntaxAlternatives::Group(first)))
     }
 }
 
-fn expression(input: &str) ->SyntaxParseResult<&str, ParsedPropertySyntax> {
-    let result = many1(alternatives).parse(input);
+fn expression(input: &str, depth: usize) -> SyntaxParseResult<&str, ParsedPropertySyntax> {
+    if depth > MAX_GROUP_DEPTH {
+        return make_failure(input, format!("Groups are nested more than {} levels deep", MAX_GROUP_DEPTH));
+    }
+
+    let result = many1(|input| alternatives(input, depth)).parse(input);
 
-    if let Ok((remain, alternatives)) = result {
-        Ok((remain, ParsedPropertySyntax::Expression(alternatives)))
-    } else {
-        make_error(input, String::from("Input did not match an expression"))
+    match result {
+        Ok((remain, alternatives)) => Ok((remain, ParsedPropertySyntax::Expression(alternatives))),
+        Err(nom::Err::Failure(error)) => Err(nom::Err::Failure(error)),
+        Err(_) => make_error(input, String::from("Input did not match an expression")),
     }
 }
 
@@ -402,7 +575,7 @@ fn universal(input: &str) -> SyntaxParseResult<&str, ParsedPropertySyntax> {
 pub fn parse_syntax(input: &str, location: SourceLocation) -> Result<ParsedPropertySyntax, ParseError> {
     let result = alt((
         universal,
-        expression,
+        |input| expression(input, 0),
     )).parse(input);
 
     if let Ok((_, syntax)) = result {
@@ -420,41 +593,48 @@ pub fn parse_syntax(input: &str, location: SourceLocation) -> Result<ParsedPrope
 
 struct SyntaxValidateError(String);
 
-fn validate_datatype<'a>(datatype: &DataType, values: &'a [Value]) -> Result<&'a [Value], SyntaxValidateError> {
+fn validate_dimension<'a>(value: &Value, remain: &'a [Value], classes: &[DimensionClass], expected: &str) -> Result<&'a [Value], SyntaxValidateError> {
+    if let ValueData::Dimension(dimension) = &value.data {
+        if classes.contains(&dimension.unit_class()) {
+            return Ok(remain);
+        }
+    }
+    Err(SyntaxValidateError(format!("Expected {}, got {}", expected, value)))
+}
+
+// Lengths may be written as a unitless zero, other numbers need a unit.
+//
+// A unitless zero is recorded in `zero_lengths` as its number of trailing
+// values, so it can be normalized to `0px` once the whole syntax matched.
+fn validate_length<'a>(value: &Value, remain: &'a [Value], classes: &[DimensionClass], expected: &str, zero_lengths: &mut Vec<usize>) -> Result<&'a [Value], SyntaxValidateError> {
+    match &value.data {
+        ValueData::Integer(0) => {
+            zero_lengths.push(remain.len());
+            Ok(remain)
+        }
+        ValueData::Dimension(dimension) if dimension.is_number() && dimension.value == 0.0 => {
+            zero_lengths.push(remain.len());
+            Ok(remain)
+        }
+        _ => validate_dimension(value, remain, classes, expected),
+    }
+}
+
+fn validate_datatype<'a>(datatype: &DataType, values: &'a [Value], zero_lengths: &mut Vec<usize>) -> Result<&'a [Value], SyntaxValidateError> {
     if let Some((value, remain)) = values.split_first() {
         match datatype {
-            DataType::Length => {
-                if let ValueData::Dimension(dimension) = &value.data {
-                    if dimension.is_length() {
-                        return Ok(remain)
-                    }
-                }
-                Err(SyntaxValidateError(format!("Expected Length, got {}", value)))
-            },
+            DataType::Length => validate_length(value, remain, &[DimensionClass::Length], "Length", zero_lengths),
             DataType::Number => {
-                if let ValueData::Dimension(dimension) = &value.data {
-                    if dimension.is_number() {
-                        return Ok(remain)
-                    }
+                match &value.data {
+                    ValueData::Dimension(dimension) if dimension.is_number() => return Ok(remain),
+                    // Integers are a subset of numbers.
+                    ValueData::Integer(_) => return Ok(remain),
+                    _ => (),
                 }
                 Err(SyntaxValidateError(format!("Expected Number, got {}", value)))
             },
-            DataType::Percentage => {
-                if let ValueData::Dimension(dimension) = &value.data {
-                    if dimension.is_percent() {
-                        return Ok(remain)
-                    }
-                }
-                Err(SyntaxValidateError(format!("Expected Percentage, got {}", value)))
-            },
-            DataType::LengthPercentage => {
-                if let ValueData::Dimension(dimension) = &value.data {
-                    if dimension.is_length() || dimension.is_percent() {
-                        return Ok(remain)
-                    }
-                }
-                Err(SyntaxValidateError(format!("Expected Length or Percentage, got {}", value)))
-            },
+            DataType::Percentage => validate_dimension(value, remain, &[DimensionClass::Percentage], "Percentage"),
+            DataType::LengthPercentage => validate_length(value, remain, &[DimensionClass::Length, DimensionClass::Percentage], "Length or Percentage", zero_lengths),
             DataType::String => {
                 if let ValueData::String(_) = value.data {
                     Ok(remain)
@@ -469,14 +649,12 @@ fn validate_datatype<'a>(datatype: &DataType, values: &'a [Value]) -> Result<&'a
                     Err(SyntaxValidateError(format!("Expected Color, got {}", value)))
                 }
             },
-            DataType::Angle => {
-                if let ValueData::Dimension(dimension) = &value.data {
-                    if dimension.is_angle() {
-                        return Ok(remain);
-                    }
-                }
-                Err(SyntaxValidateError(format!("Expected Angle, got {}", value)))
-            },
+            DataType::Angle => validate_dimension(value, remain, &[DimensionClass::Angle], "Angle"),
+            DataType::AnglePercentage => validate_dimension(value, remain, &[DimensionClass::Angle, DimensionClass::Percentage], "Angle or Percentage"),
+            DataType::Time => validate_dimension(value, remain, &[DimensionClass::Time], "Time"),
+            DataType::TimePercentage => validate_dimension(value, remain, &[DimensionClass::Time, DimensionClass::Percentage], "Time or Percentage"),
+            DataType::Resolution => validate_dimension(value, remain, &[DimensionClass::Resolution], "Resolution"),
+            DataType::Flex => validate_dimension(value, remain, &[DimensionClass::Flex], "Flex"),
             DataType::Integer => {
                 if let ValueData::Integer(_) = &value.data {
                     return Ok(remain);
@@ -489,6 +667,12 @@ fn validate_datatype<'a>(datatype: &DataType, values: &'a [Value]) -> Result<&'a
                 }
                 Err(SyntaxValidateError(format!("Expected URL, got {}", value)))
             },
+            DataType::UnicodeRange => {
+                if let ValueData::UnicodeRange { .. } = &value.data {
+                    return Ok(remain);
+                }
+                Err(SyntaxValidateError(format!("Expected Unicode range, got {}", value)))
+            },
             _ => {
                 Err(SyntaxValidateError(format!("Unhandled data type {:?}", datatype)))
             }
@@ -500,7 +684,8 @@ fn validate_datatype<'a>(datatype: &DataType, values: &'a [Value]) -> Result<&'a
 
 fn validate_keyword<'a>(keyword: &String, values: &'a [Value]) -> Result<&'a [Value], SyntaxValidateError> {
     if let Some((value, remain)) = values.split_first() {
-        if let ValueData::String(data) = &value.data {
+        // Only identifiers are keywords, a quoted string never matches.
+        if let ValueData::Keyword(data) = &value.data {
             if data == keyword {
                 Ok(remain)
             } else {
@@ -514,11 +699,23 @@ fn validate_keyword<'a>(keyword: &String, values: &'a [Value]) -> Result<&'a [Va
     }
 }
 
-fn validate_list<'a>(datatype: &DataType, values: &'a [Value], minimum: usize, maximum: usize) -> Result<&'a [Value], SyntaxValidateError> {
+fn validate_slash(values: &[Value]) -> Result<&[Value], SyntaxValidateError> {
+    if let Some((value, remain)) = values.split_first() {
+        if value.data == ValueData::Slash {
+            Ok(remain)
+        } else {
+            Err(SyntaxValidateError(format!("Expected /, got {}", value)))
+        }
+    } else {
+        Err(SyntaxValidateError(String::from("Expected /")))
+    }
+}
+
+fn validate_list<'a>(datatype: &DataType, values: &'a [Value], minimum: usize, maximum: usize, zero_lengths: &mut Vec<usize>) -> Result<&'a [Value], SyntaxValidateError> {
     let mut count = 0;
     let mut remain = values;
     while !remain.is_empty() {
-        let result = validate_datatype(datatype, remain);
+        let result = validate_datatype(datatype, remain, zero_lengths);
         if let Ok(validate_remain) = result {
             count += 1;
             remain = validate_remain;
@@ -547,57 +744,75 @@ enum ListType {
     CommaSeparated,
 }
 
-fn validate_component<'a>(component: &SyntaxComponent, values: &'a [Value], list_type: &ListType) -> Result<&'a [Value], SyntaxValidateError> {
+fn validate_component<'a>(component: &SyntaxComponent, values: &'a [Value], list_type: &ListType, zero_lengths: &mut Vec<usize>) -> Result<&'a [Value], SyntaxValidateError> {
     match component {
-        SyntaxComponent::DataType(datatype) => validate_datatype(datatype, values),
+        SyntaxComponent::DataType(datatype) => validate_datatype(datatype, values, zero_lengths),
         SyntaxComponent::Keyword(keyword) => validate_keyword(keyword, values),
         SyntaxComponent::Comma => Ok(values),
+        SyntaxComponent::Slash => validate_slash(values),
         SyntaxComponent::SpaceSeparatedList(datatype) => {
             if list_type == &ListType::CommaSeparated {
                 return Err(SyntaxValidateError(format!("Expected space separated list, got comma separated")))
             }
 
-            validate_list(datatype, values, 0, usize::max_value())
+            validate_list(datatype, values, 0, usize::max_value(), zero_lengths)
         },
         SyntaxComponent::CommaSeparatedList(datatype) => {
             if list_type == &ListType::SpaceSeparated {
                 return Err(SyntaxValidateError(format!("Expected comma separated list, got space separated")))
             }
 
-            validate_list(datatype, values, 0, usize::max_value())
+            validate_list(datatype, values, 0, usize::max_value(), zero_lengths)
         },
         SyntaxComponent::Repeat { data_type, minimum, maximum } => {
             if list_type == &ListType::CommaSeparated {
                 return Err(SyntaxValidateError(format!("Expected space separated list, got comma separated")))
             }
-            validate_list(data_type, values, *minimum, *maximum)
+            validate_list(data_type, values, *minimum, *maximum, zero_lengths)
+        },
+        SyntaxComponent::Range { data_type, minimum, maximum } => {
+            let remain = validate_datatype(data_type, values, zero_lengths)?;
+            let value = match &values[0].data {
+                ValueData::Dimension(dimension) => dimension.value,
+                ValueData::Integer(integer) => *integer as f32,
+                _ => return Ok(remain),
+            };
+
+            if value < *minimum || value > *maximum {
+                Err(SyntaxValidateError(format!("Value {} is outside of the range [{}, {}]", values[0], range_bound_to_string(*minimum), range_bound_to_string(*maximum))))
+            } else {
+                Ok(remain)
+            }
         },
     }
 }
 
-fn validate_group<'a>(group: &SyntaxGroup, values: &'a [Value], list_type: &ListType) -> Result<&'a [Value], SyntaxValidateError> {
+fn validate_group<'a>(group: &SyntaxGroup, values: &'a [Value], list_type: &ListType, zero_lengths: &mut Vec<usize>) -> Result<&'a [Value], SyntaxValidateError> {
     match group {
-        SyntaxGroup::Component(component) => validate_component(component, values, list_type),
-        SyntaxGroup::Expression(expression) => validate_expression(expression, values, list_type),
+        SyntaxGroup::Component(component) => validate_component(component, values, list_type, zero_lengths),
+        SyntaxGroup::Expression(expression) => validate_expression(expression, values, list_type, zero_lengths),
     }
 }
 
-fn validate_alternatives<'a>(alternatives: &SyntaxAlternatives, values: &'a [Value], list_type: &ListType) -> Result<&'a [Value], SyntaxValidateError> {
+fn validate_alternatives<'a>(alternatives: &SyntaxAlternatives, values: &'a [Value], list_type: &ListType, zero_lengths: &mut Vec<usize>) -> Result<&'a [Value], SyntaxValidateError> {
     match alternatives {
-        SyntaxAlternatives::Component(component) => validate_component(component, values, list_type),
-        SyntaxAlternatives::Group(group) => validate_group(group, values, list_type),
+        SyntaxAlternatives::Component(component) => validate_component(component, values, list_type, zero_lengths),
+        SyntaxAlternatives::Group(group) => validate_group(group, values, list_type, zero_lengths),
         SyntaxAlternatives::Alternatives(alternatives) => {
             for group in alternatives {
-                if let Ok(remain) = validate_group(group, values, list_type) {
+                // Forget zero lengths recorded by an alternative that did not match.
+                let recorded = zero_lengths.len();
+                if let Ok(remain) = validate_group(group, values, list_type, zero_lengths) {
                     return Ok(remain);
                 }
+                zero_lengths.truncate(recorded);
             }
             Err(SyntaxValidateError(format!("None of the alternatives matched")))
         }
     }
 }
 
-fn validate_expression<'a>(expression: &[SyntaxAlternatives], values: &'a [Value], list_type: &ListType) -> Result<&'a [Value], SyntaxValidateError> {
+fn validate_expression<'a>(expression: &[SyntaxAlternatives], values: &'a [Value], list_type: &ListType, zero_lengths: &mut Vec<usize>) -> Result<&'a [Value], SyntaxValidateError> {
     let mut remaining_values = values;
     let mut remaining_expression = expression;
 
@@ -611,7 +826,7 @@ fn validate_expression<'a>(expression: &[SyntaxAlternatives], values: &'a [Value
             break;
         }
 
-        let result = validate_alternatives(alternative, remaining_values, list_type);
+        let result = validate_alternatives(alternative, remaining_values, list_type, zero_lengths);
         if let Ok(remain) = result {
             remaining_values = remain;
         } else {
@@ -626,13 +841,15 @@ fn validate_expression<'a>(expression: &[SyntaxAlternatives], values: &'a [Value
     }
 }
 
-pub(super) fn validate_syntax(syntax: &ParsedPropertySyntax, values_result: &ParseValuesResult, location: SourceLocation) -> Result<(), ParseError> {
+// Validate `values_result` against `syntax`, normalizing unitless zero lengths
+// to `0px` if it matches.
+pub(super) fn validate_syntax(syntax: &ParsedPropertySyntax, values_result: &mut ParseValuesResult, location: SourceLocation) -> Result<(), ParseError> {
     let expression = match syntax {
         ParsedPropertySyntax::Empty | ParsedPropertySyntax::Universal => return Ok(()),
         ParsedPropertySyntax::Expression(expression) => expression,
     };
 
-    let values: &[Value];
+    let values: &mut Vec<Value>;
     let list_type: ListType;
     match values_result {
         ParseValuesResult::Single(v) => {
@@ -649,9 +866,14 @@ pub(super) fn validate_syntax(syntax: &ParsedPropertySyntax, values_result: &Par
         }
     }
 
-    let result = validate_expression(expression, values, &list_type);
+    let mut zero_lengths = Vec::new();
+    let result = validate_expression(expression, values, &list_type, &mut zero_lengths);
     if let Ok(remain) = result {
         if remain.is_empty() {
+            let count = values.len();
+            for trailing in zero_lengths {
+                values[count - trailing - 1] = Value::from(Dimension::px(0.0));
+            }
             Ok(())
         } else {
             Err(ParseError{ kind: ParseErrorKind::PropertyValueDoesNotMatchSyntax, message: format!("Received too many values, remaining: {:?}", remain), location})
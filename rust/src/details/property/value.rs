@@ -3,11 +3,13 @@
 
 use super::syntax::*;
 use super::function::*;
+use super::calc::{parse_calc, CalcValue};
 
 use crate::details::unwrap_parse_error;
 use crate::details::SourceLocation;
 use crate::details::{parse_error, ParseError, ParseErrorKind};
-use crate::value::{Color, Dimension, Value, Unit};
+use crate::details::rulesparser::ParserOptions;
+use crate::value::{Color, CssWideKeyword, Dimension, Value, ValueData, Unit};
 
 #[derive(Debug, PartialEq)]
 pub(super) enum ParseValuesResult {
@@ -34,8 +36,9 @@ fn parse_dimension<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValue
         cssparser::Token::Dimension{has_sign: _, value, int_value: _, unit: unit_string} => {
             let unit = Unit::parse(unit_string.to_string().as_str());
             match unit {
+                // Unknown units are checked by parse_values, as they may be allowed.
                 Unit::Unknown | Unit::Unsupported => {
-                    return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Invalid unit for dimension: {}", unit_string));
+                    return Ok(Value::from(Dimension{value, unit: Unit::Other(unit_string.to_string())}));
                 }
                 _ => {
                     return Ok(Value::from(Dimension{value, unit}));
@@ -49,19 +52,74 @@ fn parse_dimension<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValue
     }
 }
 
+// A color parser that folds numeric calc() expressions in color channels.
+struct CalcColorParser;
+
+impl<'i> cssparser_color::ColorParser<'i> for CalcColorParser {
+    type Output = cssparser_color::Color;
+    type Error = ();
+
+    fn parse_angle_or_number<'t>(&self, parser: &mut cssparser::Parser<'i, 't>) -> Result<cssparser_color::AngleOrNumber, cssparser::ParseError<'i, ()>> {
+        match parser.try_parse(parse_calc::<()>) {
+            Ok(CalcValue::Number(value)) => Ok(cssparser_color::AngleOrNumber::Number { value }),
+            Ok(CalcValue::Angle(degrees)) => Ok(cssparser_color::AngleOrNumber::Angle { degrees }),
+            Ok(CalcValue::Percentage(_)) => Err(parser.new_custom_error(())),
+            Err(_) => cssparser_color::DefaultColorParser.parse_angle_or_number(parser),
+        }
+    }
+
+    fn parse_percentage<'t>(&self, parser: &mut cssparser::Parser<'i, 't>) -> Result<f32, cssparser::ParseError<'i, ()>> {
+        match parser.try_parse(parse_calc::<()>) {
+            Ok(CalcValue::Percentage(value)) => Ok(value),
+            Ok(_) => Err(parser.new_custom_error(())),
+            Err(_) => cssparser_color::DefaultColorParser.parse_percentage(parser),
+        }
+    }
+
+    fn parse_number<'t>(&self, parser: &mut cssparser::Parser<'i, 't>) -> Result<f32, cssparser::ParseError<'i, ()>> {
+        match parser.try_parse(parse_calc::<()>) {
+            Ok(CalcValue::Number(value)) => Ok(value),
+            Ok(_) => Err(parser.new_custom_error(())),
+            Err(_) => cssparser_color::DefaultColorParser.parse_number(parser),
+        }
+    }
+
+    fn parse_number_or_percentage<'t>(&self, parser: &mut cssparser::Parser<'i, 't>) -> Result<cssparser_color::NumberOrPercentage, cssparser::ParseError<'i, ()>> {
+        match parser.try_parse(parse_calc::<()>) {
+            Ok(CalcValue::Number(value)) => Ok(cssparser_color::NumberOrPercentage::Number { value }),
+            Ok(CalcValue::Percentage(unit_value)) => Ok(cssparser_color::NumberOrPercentage::Percentage { unit_value }),
+            Ok(CalcValue::Angle(_)) => Err(parser.new_custom_error(())),
+            Err(_) => cssparser_color::DefaultColorParser.parse_number_or_percentage(parser),
+        }
+    }
+}
+
+// cssparser_color normalizes alpha to the 0-1 range, including when it is
+// written as a percentage, so it can be passed to Color::from() as is. Hue is
+// returned in degrees while hsl_to_rgb() and hwb_to_rgb() expect it in 0-1.
+// Components that are absent fail to parse, a component is only None when it
+// was written as `none`, which is treated as 0.
 fn parse_color<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValueComponentResult<'i> {
-    let color_result = cssparser_color::Color::parse(parser);
+    let color_result = cssparser_color::parse_color_with(&CalcColorParser, parser);
 
     if let Ok(color) = color_result {
         match color {
             cssparser_color::Color::Rgba(rgba) => return Ok(Value::from(Color::from((rgba.red, rgba.green, rgba.blue, rgba.alpha)))),
             cssparser_color::Color::Hsl(hsl) => {
-                let rgb = cssparser_color::hsl_to_rgb(hsl.hue.unwrap_or(0.0), hsl.saturation.unwrap_or(0.0), hsl.lightness.unwrap_or(0.0));
-                return Ok(Value::from(Color::from((rgb.0, rgb.1, rgb.2, hsl.alpha.unwrap_or(1.0)))))
+                let rgb = cssparser_color::hsl_to_rgb(hsl.hue.unwrap_or(0.0) / 360.0, hsl.saturation.unwrap_or(0.0), hsl.lightness.unwrap_or(0.0));
+                let color = match hsl.alpha {
+                    Some(alpha) => Color::from((rgb.0, rgb.1, rgb.2, alpha)),
+                    None => Color::from(rgb),
+                };
+                return Ok(Value::from(color))
             }
             cssparser_color::Color::Hwb(hwb) => {
-                let rgb = cssparser_color::hwb_to_rgb(hwb.hue.unwrap_or(0.0), hwb.whiteness.unwrap_or(0.0), hwb.blackness.unwrap_or(0.0));
-                return Ok(Value::from(Color::from((rgb.0, rgb.1, rgb.2, hwb.alpha.unwrap_or(1.0)))))
+                let rgb = cssparser_color::hwb_to_rgb(hwb.hue.unwrap_or(0.0) / 360.0, hwb.whiteness.unwrap_or(0.0), hwb.blackness.unwrap_or(0.0));
+                let color = match hwb.alpha {
+                    Some(alpha) => Color::from((rgb.0, rgb.1, rgb.2, alpha)),
+                    None => Color::from(rgb),
+                };
+                return Ok(Value::from(color))
             }
             _ => (),
         }
@@ -76,7 +134,21 @@ fn parse_number<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValueCom
 }
 
 fn parse_integer<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValueComponentResult<'i> {
+    // Skip comments as well as whitespace, so only the integer is sliced below.
+    parser.skip_whitespace();
+    let start = parser.position();
     let integer = parser.expect_integer()?;
+
+    // cssparser clamps integers to the range of i32, so a value at either end
+    // of that range may have been out of range in the input. These are parsed
+    // as numbers instead, see out_of_range_integer().
+    if integer == i32::MAX || integer == i32::MIN {
+        let input = parser.slice_from(start);
+        if input.parse::<i32>().is_err() {
+            return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Integer {} is out of range", input));
+        }
+    }
+
     Ok(Value::from(integer))
 }
 
@@ -84,7 +156,7 @@ fn parse_string<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValueCom
     let token = parser.next()?.clone();
     match token {
         cssparser::Token::Ident(value) => {
-            return Ok(Value::from(value.as_ref()))
+            return Ok(Value::new_keyword(value.as_ref()))
         },
         cssparser::Token::QuotedString(value) => {
             return Ok(Value::from(value.as_ref()))
@@ -95,6 +167,11 @@ fn parse_string<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValueCom
     }
 }
 
+fn parse_unicode_range<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValueComponentResult<'i> {
+    let range = cssparser::UnicodeRange::parse(parser)?;
+    Ok(Value::new_unicode_range(range.start, range.end))
+}
+
 fn parse_url<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> ParseValueComponentResult<'i> {
     let url = parser.expect_url()?;
     return Ok(Value::new_url(url.as_ref()));
@@ -118,11 +195,13 @@ fn parse_function<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Result<Vec<
 }
 
 fn parse_value_component<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Result<Vec<Value>, cssparser::ParseError<'i, ParseError>> {
-    const PARSE_FUNCTIONS: [for<'i, 't> fn(&mut cssparser::Parser<'i, 't>) -> ParseValueComponentResult<'i>; 6] = [
+    const PARSE_FUNCTIONS: [for<'i, 't> fn(&mut cssparser::Parser<'i, 't>) -> ParseValueComponentResult<'i>; 7] = [
         parse_integer,
         parse_number,
         parse_color,
         parse_dimension,
+        // Before strings, as a Unicode range starts with the identifier `u`.
+        parse_unicode_range,
         parse_string,
         parse_url,
     ];
@@ -133,6 +212,16 @@ fn parse_value_component<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Resu
         }
     }
 
+    let start = parser.state();
+
+    // hwb() is only parsed as a color, so a malformed one is an invalid color
+    // rather than an unknown function.
+    if parser.try_parse(|parser| parser.expect_function_matching("hwb")).is_ok() {
+        parser.reset(&start);
+        parser.skip_whitespace();
+        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Invalid color function hwb(), expected hue, whiteness, blackness and an optional alpha"));
+    }
+
     let function_result = parse_function(parser);
     if let Ok(values) = function_result {
         return Ok(values)
@@ -140,10 +229,40 @@ fn parse_value_component<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Resu
         return function_result;
     }
 
-    parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Could not parse input"))
+    // Point at the token that could not be parsed rather than past it.
+    parser.reset(&start);
+    parser.skip_whitespace();
+    let location = parser.current_source_location();
+    let message = match parser.next() {
+        Ok(token) => format!("Unexpected token {:?}", token),
+        Err(_) => String::from("Unexpected end of input"),
+    };
+    Err(location.new_custom_error(ParseError {
+        kind: ParseErrorKind::InvalidPropertyValue,
+        message,
+        location: SourceLocation::from_file_location(parser.current_source_url().unwrap_or("").to_string(), location),
+    }))
+}
+
+/// Parse a value that consists of only a CSS-wide keyword.
+pub fn parse_css_wide_keyword<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Result<CssWideKeyword, cssparser::ParseError<'i, ParseError>> {
+    parser.parse_until_before(cssparser::Delimiter::Bang, |parser| {
+        let ident = parser.expect_ident()?.clone();
+        if let Some(keyword) = CssWideKeyword::parse(ident.as_ref()) {
+            parser.expect_exhausted()?;
+            Ok(keyword)
+        } else {
+            parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("{} is not a CSS-wide keyword", ident))
+        }
+    })
 }
 
 pub fn parse_values<'i, 't>(syntax: &ParsedPropertySyntax, parser: &mut cssparser::Parser<'i, 't>) -> Result<Vec<Value>, cssparser::ParseError<'i, ParseError>> {
+    parse_values_with_options(syntax, parser, &ParserOptions::default())
+}
+
+pub fn parse_values_with_options<'i, 't>(syntax: &ParsedPropertySyntax, parser: &mut cssparser::Parser<'i, 't>, options: &ParserOptions) -> Result<Vec<Value>, cssparser::ParseError<'i, ParseError>> {
+    let start = parser.state();
     let result = parser.parse_until_before(cssparser::Delimiter::Bang, |parser| {
         let mut values: Vec<Value> = Vec::new();
         let mut comma_separated = false;
@@ -151,6 +270,13 @@ pub fn parse_values<'i, 't>(syntax: &ParsedPropertySyntax, parser: &mut cssparse
         while !parser.is_exhausted() {
             let result = parse_value_component(parser);
             if let Ok(parsed_values) = result {
+                if options.strict_units {
+                    for value in &parsed_values {
+                        if let ValueData::Dimension(Dimension { unit: Unit::Other(unit), .. }) = &value.data {
+                            return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Invalid unit for dimension: {}", unit));
+                        }
+                    }
+                }
                 values.extend(parsed_values);
             } else {
                 return Err(result.err().unwrap());
@@ -158,6 +284,14 @@ pub fn parse_values<'i, 't>(syntax: &ParsedPropertySyntax, parser: &mut cssparse
 
             if let Ok(_) = parser.try_parse(|parser| { parser.expect_comma() }) {
                 comma_separated = true;
+
+                if options.strict_trailing_commas && parser.is_exhausted() {
+                    return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Unexpected trailing comma"));
+                }
+            }
+
+            if parser.try_parse(|parser| parser.expect_delim('/')).is_ok() {
+                values.push(Value::new_slash());
             }
         }
 
@@ -170,10 +304,12 @@ pub fn parse_values<'i, 't>(syntax: &ParsedPropertySyntax, parser: &mut cssparse
         }
     });
 
-    if let Ok(values) = result {
-        let validation_result = validate_syntax(syntax, &values, SourceLocation::from_file_location(parser.current_source_url().unwrap_or("").to_string(), parser.current_source_location()));
+    if let Ok(mut values) = result {
+        let validation_result = validate_syntax(syntax, &mut values, SourceLocation::from_file_location(parser.current_source_url().unwrap_or("").to_string(), parser.current_source_location()));
         if let Ok(_) = validation_result {
             Ok(values.into())
+        } else if let Some(integer) = out_of_range_integer(syntax, parser, &start) {
+            parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Integer {} is out of range", integer))
         } else {
             Err(parser.new_custom_error(validation_result.unwrap_err()))
         }
@@ -181,3 +317,36 @@ pub fn parse_values<'i, 't>(syntax: &ParsedPropertySyntax, parser: &mut cssparse
         Err(result.err().unwrap())
     }
 }
+
+// Find a number that was written as an integer too large for i32, to explain
+// why it does not match a syntax that expects integers.
+//
+// The values only contain the number rounded to f32, so the input from
+// `start` is scanned again to report the integer as written.
+fn out_of_range_integer<'i, 't>(syntax: &ParsedPropertySyntax, parser: &mut cssparser::Parser<'i, 't>, start: &cssparser::ParserState) -> Option<String> {
+    if !syntax.data_types().contains(&DataType::Integer) {
+        return None;
+    }
+
+    let end = parser.state();
+    parser.reset(start);
+
+    let mut result = None;
+    while result.is_none() {
+        parser.skip_whitespace();
+        let token_start = parser.position();
+        match parser.next() {
+            Ok(cssparser::Token::Number { int_value: Some(_), .. }) => {
+                let input = parser.slice_from(token_start);
+                if input.parse::<i32>().is_err() {
+                    result = Some(input.to_string());
+                }
+            }
+            Ok(_) => (),
+            Err(_) => break,
+        }
+    }
+
+    parser.reset(&end);
+    result
+}
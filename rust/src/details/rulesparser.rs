@@ -3,24 +3,36 @@
 
 // Implements the parts of cssparser that are required to parse things.
 
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use cssparser::{CowRcStr, RuleBodyParser};
 
-use crate::property::{add_property_definition, property_definition, Property, PropertyDefinition};
+use crate::background::parse_background_value;
+use crate::fontface::{FontFace, FontFaceSource};
+use crate::keyframes::{Keyframe, Keyframes};
+use crate::media::MediaQuery;
+use crate::property::{add_property_definition, cascade_property, property_definition, replace_property_definition, CustomProperty, Property, PropertyDefinition};
 use crate::selector::Selector;
+use crate::value::Value;
 
-use super::{parse_error, ParseError, ParseErrorKind};
-use super::selectorparser::{SelectorParser, ParseRelative};
+use super::{parse_error, parse_error_from_cssparser_error, ParseError, ParseErrorKind, SourceLocation};
+use super::comments::SharedComments;
+use super::mediaparser::parse_media_query;
+use super::selectorparser::{Namespaces, SelectorParser, ParseRelative};
 use super::property::syntax::ParsedPropertySyntax;
 use super::property::definitionparser::parse_property_definition;
-use super::property::value::parse_values;
+use super::property::value::{parse_css_wide_keyword, parse_values_with_options};
 
-#[derive(Debug)]
+/// A rule as written in the style sheet, before nested rules are flattened.
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParsedRule {
     pub selectors: Vec<Selector>,
     pub properties: Vec<Property>,
+    /// Rules nested inside this rule, with selectors relative to this rule.
     pub nested_rules: Vec<Self>,
+    pub comments: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -28,26 +40,183 @@ pub enum ParseResult {
     Property(Property),
     Rule(ParsedRule),
     PropertyDefinition(PropertyDefinition),
-    Import(String),
+    /// An `@import` rule, with the location of the rule in the importing sheet.
+    Import {
+        url: String,
+        location: cssparser::SourceLocation,
+    },
+    Namespace,
+    Media {
+        query: MediaQuery,
+        contents: Vec<ParseResult>,
+        errors: Vec<ParseError>,
+    },
+    /// A `@layer` rule with a block, containing the rules of layer `name`.
+    Layer {
+        name: String,
+        contents: Vec<ParseResult>,
+        errors: Vec<ParseError>,
+    },
+    /// A `@layer` statement declaring the order of layers.
+    LayerStatement(Vec<String>),
+    Keyframes {
+        keyframes: Keyframes,
+        errors: Vec<ParseError>,
+    },
+    FontFace {
+        font_face: FontFace,
+        errors: Vec<ParseError>,
+    },
+    /// An unsupported @-rule that was skipped.
+    Ignored,
 }
 
 #[derive(Debug)]
 pub enum AtRulePrelude {
     Property(String),
     Import(String),
+    Namespace(Option<String>, String),
+    Media(MediaQuery),
+    Layer(Vec<String>),
+    Keyframes(String),
+    FontFace,
+    Unknown,
 }
 
-pub struct RulesParser<const TOP_LEVEL: bool>;
+/// Options that change how strictly style sheets are parsed.
+#[derive(Debug, Clone, Copy)]
+pub struct ParserOptions {
+    /// Reject properties that do not have a registered definition. When false,
+    /// unknown properties are parsed using the universal syntax instead.
+    pub strict_unknown_properties: bool,
+    /// Reject @-rules that are not supported. When false, unsupported @-rules
+    /// are skipped and a warning is recorded instead.
+    pub strict_unknown_at_rules: bool,
+    /// Reject dimensions with an unknown unit. When false, these are kept
+    /// using `Unit::Other`.
+    pub strict_units: bool,
+    /// Reject a trailing comma in comma-separated values. When false, a single
+    /// trailing comma is ignored.
+    pub strict_trailing_commas: bool,
+    /// Reject `!` flags other than `!important`. When false, the identifier
+    /// following `!` is stored in `Property::flag`.
+    pub strict_bang_flags: bool,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            strict_unknown_properties: true,
+            strict_unknown_at_rules: true,
+            strict_units: true,
+            strict_trailing_commas: true,
+            strict_bang_flags: true,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RulesParser<const TOP_LEVEL: bool> {
+    pub namespaces: Namespaces,
+    pub options: ParserOptions,
+    /// When set, comments preceding rules and declarations are attached to them.
+    pub comments: Option<SharedComments>,
+    /// Warnings about parts of the input that were skipped.
+    pub warnings: Rc<RefCell<Vec<ParseError>>>,
+    /// The custom properties declared while parsing.
+    pub custom_properties: Rc<RefCell<Vec<CustomProperty>>>,
+}
 pub type TopLevelParser = RulesParser<true>;
 pub type NestedParser = RulesParser<false>;
 
+impl<const TOP_LEVEL: bool> RulesParser<TOP_LEVEL> {
+    fn with_state<const OTHER_LEVEL: bool>(&self) -> RulesParser<OTHER_LEVEL> {
+        RulesParser {
+            namespaces: self.namespaces.clone(),
+            options: self.options,
+            comments: self.comments.clone(),
+            warnings: self.warnings.clone(),
+            custom_properties: self.custom_properties.clone(),
+        }
+    }
+
+    fn parse_contents<'i, 't>(&self, input: &mut cssparser::Parser<'i, 't>) -> (Vec<ParseResult>, Vec<ParseError>) {
+        let file = input.current_source_url().unwrap_or("").to_string();
+        let mut rules_parser: TopLevelParser = self.with_state();
+        let mut contents = Vec::new();
+        let mut errors = Vec::new();
+        for entry in cssparser::StyleSheetParser::new(input, &mut rules_parser) {
+            match entry {
+                Ok(ParseResult::Import { location, .. }) => errors.push(ParseError {
+                    kind: ParseErrorKind::UnsupportedAtRule,
+                    message: String::from("@import can only be used at top level"),
+                    location: SourceLocation::from_file_location(file.clone(), location),
+                }),
+                Ok(result) => contents.push(result),
+                Err(error) => errors.push(parse_error_from_cssparser_error(&error.0, file.clone())),
+            }
+        }
+        (contents, errors)
+    }
+
+    fn take_comments(&self, position: cssparser::SourcePosition) -> Vec<String> {
+        match &self.comments {
+            Some(comments) => comments.borrow_mut().take_before(position.byte_index()),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Register a property definition parsed from a style sheet.
+///
+/// Like CSS, a later definition of a custom property replaces an earlier one.
+/// Definitions of other properties do not replace the built-in ones, the
+/// parser warns when such a definition differs from the registered one.
+pub fn register_parsed_definition(definition: PropertyDefinition) {
+    let definition = Arc::new(definition);
+    if definition.name.starts_with("--") {
+        replace_property_definition(&definition);
+    } else {
+        add_property_definition(&definition);
+    }
+}
+
+// Capture the value of a custom property as written, so that its var()
+// references can be resolved against the other custom properties of the style
+// sheet later on.
+fn custom_property_source<'i, 't>(name: &str, input: &mut cssparser::Parser<'i, 't>) -> CustomProperty {
+    let location = SourceLocation::from_file_location(input.current_source_url().unwrap_or("").to_string(), input.current_source_location());
+    let start = input.state();
+    let _ = input.parse_until_before(cssparser::Delimiter::Bang, |input| -> Result<(), cssparser::ParseError<'i, ParseError>> {
+        while input.next().is_ok() {}
+        Ok(())
+    });
+    let source = input.slice_from(start.position()).trim().to_string();
+    input.reset(&start);
+
+    CustomProperty { name: name.to_string(), source, location }
+}
+
+// Parses a layer name, which is a sequence of identifiers separated by periods.
+fn parse_layer_name<'i, 't>(input: &mut cssparser::Parser<'i, 't>) -> Result<String, cssparser::ParseError<'i, ParseError>> {
+    let mut name = input.expect_ident()?.to_string();
+    while let Ok(part) = input.try_parse(|input| {
+        input.expect_delim('.')?;
+        input.expect_ident().map(|part| part.to_string())
+    }) {
+        name.push('.');
+        name.push_str(&part);
+    }
+    Ok(name)
+}
+
 impl<'i, const TOP_LEVEL: bool> cssparser::QualifiedRuleParser<'i> for RulesParser<TOP_LEVEL> {
     type Prelude = Vec<Selector>;
     type QualifiedRule = ParseResult;
     type Error = ParseError;
 
     fn parse_prelude<'t>(&mut self, parser: &mut cssparser::Parser<'i, 't>) -> Result<Self::Prelude, cssparser::ParseError<'i, Self::Error>> {
-        let selector_parser = SelectorParser{};
+        let selector_parser = SelectorParser{ namespaces: self.namespaces.clone() };
         let relative = if TOP_LEVEL { ParseRelative::No } else { ParseRelative::Nested };
         let result = selector_parser.parse(parser, relative);
         match result {
@@ -65,10 +234,12 @@ impl<'i, const TOP_LEVEL: bool> cssparser::QualifiedRuleParser<'i> for RulesPars
     fn parse_block<'t>(
         &mut self,
         prelude: Self::Prelude,
-        _location: &cssparser::ParserState,
+        location: &cssparser::ParserState,
         parser: &mut cssparser::Parser<'i, 't>) -> Result<Self::QualifiedRule, cssparser::ParseError<'i, Self::Error>>
     {
-        let mut nested_parser = NestedParser{};
+        let comments = self.take_comments(location.position());
+
+        let mut nested_parser: NestedParser = self.with_state();
         let mut body_parser = RuleBodyParser::<NestedParser, Self::QualifiedRule, Self::Error>::new(parser, &mut nested_parser);
 
         let mut properties = Vec::new();
@@ -76,12 +247,18 @@ impl<'i, const TOP_LEVEL: bool> cssparser::QualifiedRuleParser<'i> for RulesPars
         while let Some(entry) = body_parser.next() {
             if let Ok(entry_contents) = entry {
                 match entry_contents {
-                    ParseResult::Property(property) => properties.push(property),
+                    ParseResult::Property(property) => cascade_property(&mut properties, property),
                     ParseResult::Rule(rule) => nested.push(rule),
                     ParseResult::PropertyDefinition(definition) => {
-                        add_property_definition(&Arc::new(definition));
+                        register_parsed_definition(definition);
                     },
-                    ParseResult::Import(_) => return parse_error(parser, ParseErrorKind::UnsupportedAtRule, String::from("@import can only be used at top level")),
+                    ParseResult::Import { .. } => return parse_error(parser, ParseErrorKind::UnsupportedAtRule, String::from("@import can only be used at top level")),
+                    ParseResult::Namespace => return parse_error(parser, ParseErrorKind::UnsupportedAtRule, String::from("@namespace can only be used at top level")),
+                    ParseResult::Media { .. } => return parse_error(parser, ParseErrorKind::UnsupportedAtRule, String::from("@media can only be used at top level")),
+                    ParseResult::Layer { .. } | ParseResult::LayerStatement(_) => return parse_error(parser, ParseErrorKind::UnsupportedAtRule, String::from("@layer can only be used at top level")),
+                    ParseResult::Keyframes { .. } => return parse_error(parser, ParseErrorKind::UnsupportedAtRule, String::from("@keyframes can only be used at top level")),
+                    ParseResult::FontFace { .. } => return parse_error(parser, ParseErrorKind::UnsupportedAtRule, String::from("@font-face can only be used at top level")),
+                    ParseResult::Ignored => (),
                 }
             } else {
                 return Err(entry.unwrap_err().0)
@@ -92,6 +269,7 @@ impl<'i, const TOP_LEVEL: bool> cssparser::QualifiedRuleParser<'i> for RulesPars
             selectors: prelude,
             properties,
             nested_rules: nested,
+            comments,
         }))
     }
 }
@@ -115,6 +293,48 @@ impl<'i, const TOP_LEVEL: bool> cssparser::AtRuleParser<'i> for RulesParser<TOP_
                 let url = input.expect_url_or_string()?.to_string();
                 return Ok(AtRulePrelude::Import(url));
             }
+            "namespace" => {
+                let prefix = input.try_parse(|parser| parser.expect_ident().map(|prefix| prefix.to_string())).ok();
+                let url = input.expect_url_or_string()?.to_string();
+                Ok(AtRulePrelude::Namespace(prefix, url))
+            }
+            "media" => {
+                let result = parse_media_query(input);
+                match result {
+                    Ok(query) => Ok(AtRulePrelude::Media(query)),
+                    Err(error) => {
+                        let message = if let cssparser::ParseErrorKind::Custom(error) = error.kind {
+                            error.message
+                        } else {
+                            format!("{:?}", error.kind)
+                        };
+                        parse_error(input, ParseErrorKind::InvalidAtRule, format!("Invalid media query: {}", message))
+                    }
+                }
+            }
+            "layer" => {
+                if input.is_exhausted() {
+                    return parse_error(input, ParseErrorKind::InvalidAtRule, String::from("Anonymous layers are not supported"));
+                }
+                Ok(AtRulePrelude::Layer(input.parse_comma_separated(parse_layer_name)?))
+            }
+            "keyframes" => {
+                let name = input.expect_ident_or_string()?.to_string();
+                Ok(AtRulePrelude::Keyframes(name))
+            }
+            "font-face" => {
+                input.expect_exhausted()?;
+                Ok(AtRulePrelude::FontFace)
+            }
+            _ if !self.options.strict_unknown_at_rules => {
+                self.warnings.borrow_mut().push(ParseError {
+                    kind: ParseErrorKind::UnsupportedAtRule,
+                    message: format!("Ignoring unsupported @-rule {}", name),
+                    location: SourceLocation::from_file_location(input.current_source_url().unwrap_or("").to_string(), input.current_source_location()),
+                });
+                while input.next().is_ok() {}
+                Ok(AtRulePrelude::Unknown)
+            }
             _ => parse_error(input, ParseErrorKind::UnsupportedAtRule, format!("Unsupported @-rule {}", name)),
         }
     }
@@ -122,17 +342,75 @@ impl<'i, const TOP_LEVEL: bool> cssparser::AtRuleParser<'i> for RulesParser<TOP_
     fn parse_block<'t>(
         &mut self,
         prelude: Self::Prelude,
-        _start: &cssparser::ParserState,
+        start: &cssparser::ParserState,
         input: &mut cssparser::Parser<'i, 't>,
     ) -> Result<Self::AtRule, cssparser::ParseError<'i, Self::Error>> {
         match prelude {
             AtRulePrelude::Property(name) => {
                 let result = parse_property_definition(input, name.to_string());
                 match result {
-                    Ok(definition) => return Ok(ParseResult::PropertyDefinition(definition)),
+                    Ok(definition) => {
+                        // See register_parsed_definition(), only custom properties can be redefined.
+                        let existing = property_definition(&definition.name).filter(|_| !definition.name.starts_with("--"));
+                        if let Some(existing) = existing {
+                            if existing.syntax != definition.syntax || existing.inherit != definition.inherit || existing.initial != definition.initial {
+                                self.warnings.borrow_mut().push(ParseError {
+                                    kind: ParseErrorKind::InvalidPropertyDefinition,
+                                    message: format!("Ignoring redefinition of property {}", definition.name),
+                                    location: SourceLocation::from_file_location(input.current_source_url().unwrap_or("").to_string(), start.source_location()),
+                                });
+                            }
+                        }
+                        return Ok(ParseResult::PropertyDefinition(definition))
+                    },
                     Err(error) => return parse_error(input, ParseErrorKind::InvalidPropertyDefinition, error.to_string())
                 }
             },
+            AtRulePrelude::Media(query) => {
+                let (contents, errors) = self.parse_contents(input);
+                Ok(ParseResult::Media { query, contents, errors })
+            },
+            AtRulePrelude::Layer(mut names) => {
+                if names.len() != 1 {
+                    return parse_error(input, ParseErrorKind::InvalidAtRule, String::from("A @layer block must have exactly one layer name"));
+                }
+                let (contents, errors) = self.parse_contents(input);
+                Ok(ParseResult::Layer { name: names.remove(0), contents, errors })
+            },
+            AtRulePrelude::Keyframes(name) => {
+                let file = input.current_source_url().unwrap_or("").to_string();
+                let mut keyframes_parser = KeyframesParser { declarations: self.with_state() };
+                let mut stops = Vec::new();
+                let mut errors = Vec::new();
+                for entry in cssparser::StyleSheetParser::new(input, &mut keyframes_parser) {
+                    match entry {
+                        Ok(stop) => stops.push(stop),
+                        Err(error) => errors.push(parse_error_from_cssparser_error(&error.0, file.clone())),
+                    }
+                }
+                Ok(ParseResult::Keyframes { keyframes: Keyframes { name, stops }, errors })
+            },
+            AtRulePrelude::FontFace => {
+                let file = input.current_source_url().unwrap_or("").to_string();
+                let mut font_face_parser = FontFaceParser::default();
+                let mut errors = Vec::new();
+                for entry in RuleBodyParser::<FontFaceParser, (), ParseError>::new(input, &mut font_face_parser) {
+                    if let Err(error) = entry {
+                        errors.push(parse_error_from_cssparser_error(&error.0, file.clone()));
+                    }
+                }
+
+                if font_face_parser.font_face.family.is_empty() {
+                    return parse_error(input, ParseErrorKind::InvalidAtRule, String::from("@font-face requires a font-family descriptor"));
+                }
+
+                Ok(ParseResult::FontFace { font_face: font_face_parser.font_face, errors })
+            },
+            AtRulePrelude::Unknown => {
+                // Nested blocks are skipped as a whole, so this keeps braces balanced.
+                while input.next().is_ok() {}
+                Ok(ParseResult::Ignored)
+            }
             _ => {
                 return parse_error(input, ParseErrorKind::UnsupportedAtRule, format!("Got @-rule: {:?}", prelude));
             }
@@ -142,12 +420,21 @@ impl<'i, const TOP_LEVEL: bool> cssparser::AtRuleParser<'i> for RulesParser<TOP_
     fn rule_without_block(
         &mut self,
         prelude: Self::Prelude,
-        _start: &cssparser::ParserState,
+        start: &cssparser::ParserState,
     ) -> Result<Self::AtRule, ()> {
         match prelude {
             AtRulePrelude::Import(url) => {
-                return Ok(ParseResult::Import(url))
+                return Ok(ParseResult::Import { url, location: start.source_location() })
+            },
+            AtRulePrelude::Namespace(prefix, url) => {
+                match prefix {
+                    Some(prefix) => self.namespaces.prefixes.insert(prefix, url),
+                    None => self.namespaces.default.replace(url),
+                };
+                Ok(ParseResult::Namespace)
             },
+            AtRulePrelude::Layer(names) => Ok(ParseResult::LayerStatement(names)),
+            AtRulePrelude::Unknown => Ok(ParseResult::Ignored),
             _ => {
                 return Err(())
             }
@@ -159,37 +446,90 @@ impl<'i, const TOP_LEVEL: bool> cssparser::DeclarationParser<'i> for RulesParser
     type Declaration = ParseResult;
     type Error = ParseError;
 
-    fn parse_value<'t>(&mut self, name: CowRcStr<'i>, input: &mut cssparser::Parser<'i, 't>, _state: &cssparser::ParserState) -> Result<Self::Declaration, cssparser::ParseError<'i, Self::Error>> {
-        let definition = property_definition(name.to_string().as_str());
+    fn parse_value<'t>(&mut self, name: CowRcStr<'i>, input: &mut cssparser::Parser<'i, 't>, state: &cssparser::ParserState) -> Result<Self::Declaration, cssparser::ParseError<'i, Self::Error>> {
+        let comments = self.take_comments(state.position());
+
+        // Property names are ASCII case-insensitive, except for custom properties.
+        let name = if name.starts_with("--") { name.to_string() } else { name.to_ascii_lowercase() };
+        let mut definition = property_definition(name.as_str());
+
+        if name.starts_with("--") {
+            self.custom_properties.borrow_mut().push(custom_property_source(&name, input));
+        }
+
+        // `all` resets all properties, it only accepts CSS-wide keywords.
+        if name == "all" {
+            definition = Some(Arc::new(PropertyDefinition {
+                name: name.clone(),
+                syntax: ParsedPropertySyntax::Empty,
+                inherit: false,
+                initial: Vec::new(),
+                shorthand: None,
+            }));
+        }
+
         if definition.is_none() {
-            if !name.starts_with("--") {
-                return parse_error(input, ParseErrorKind::UnknownProperty, format!("No definition for property {}", name));
+            // An undefined custom property defines itself, using its value as
+            // the initial value.
+            if name.starts_with("--") {
+                let values_result = parse_values_with_options(&ParsedPropertySyntax::Universal, input, &self.options);
+                match values_result {
+                    Ok(values) => {
+                        return Ok(ParseResult::PropertyDefinition(PropertyDefinition {
+                            name: name.to_string(),
+                            syntax: ParsedPropertySyntax::Universal,
+                            inherit: false,
+                            initial: values,
+                            shorthand: None,
+                        }));
+                    }
+                    Err(error) => {
+                        return parse_error(input, ParseErrorKind::InvalidPropertyValue, format!("Parsing values for property {} failed: {}", name, error));
+                    }
+                }
             }
 
-            let values_result = parse_values(&ParsedPropertySyntax::Universal, input);
-            match values_result {
-                Ok(values) => {
-                    return Ok(ParseResult::PropertyDefinition(PropertyDefinition {
-                        name: name.to_string(),
-                        syntax: ParsedPropertySyntax::Universal,
-                        inherit: false,
-                        initial: values,
-                    }));
-                }
-                Err(error) => {
-                    return parse_error(input, ParseErrorKind::InvalidPropertyValue, format!("Parsing values for property {} failed: {}", name, error));
-                }
+            if self.options.strict_unknown_properties {
+                return parse_error(input, ParseErrorKind::UnknownProperty, format!("No definition for property {}", name));
             }
+
+            // Keep the property, but accept anything as value since we do not
+            // know what it is supposed to be.
+            definition = Some(Arc::new(PropertyDefinition {
+                name: name.clone(),
+                syntax: ParsedPropertySyntax::Universal,
+                inherit: false,
+                initial: Vec::new(),
+                shorthand: None,
+            }));
         }
 
         let pd = definition.unwrap();
-        let values_result = parse_values(&pd.syntax, input);
+        let values_result = match input.try_parse(parse_css_wide_keyword) {
+            Ok(keyword) => Ok(vec![Value::from(keyword)]),
+            Err(_) if name == "all" => {
+                return parse_error(input, ParseErrorKind::InvalidPropertyValue, String::from("Property all only accepts CSS-wide keywords"));
+            }
+            Err(_) if name == "background" => parse_background_value(input, &self.options),
+            Err(_) => parse_values_with_options(&pd.syntax, input, &self.options),
+        };
         match values_result {
             Ok(values) => {
+                let flag = input.try_parse(|input| -> Result<String, cssparser::ParseError<'i, ParseError>> {
+                    input.expect_delim('!')?;
+                    Ok(input.expect_ident()?.to_string())
+                }).ok();
+                let important = flag.as_ref().is_some_and(|flag| flag.eq_ignore_ascii_case("important"));
+                if let Some(flag) = flag.as_ref().filter(|_| !important && self.options.strict_bang_flags) {
+                    return parse_error(input, ParseErrorKind::UnexpectedToken, format!("Unknown flag !{} for property {}", flag, name));
+                }
                 Ok(ParseResult::Property(Property {
                     name: name.to_string(),
                     definition: pd,
                     values,
+                    important,
+                    flag,
+                    comments,
                 }))
             }
             Err(error) => {
@@ -214,3 +554,140 @@ impl<'i, const TOP_LEVEL: bool> cssparser::RuleBodyItemParser<'i, ParseResult, P
         true
     }
 }
+
+// Parses the stops inside a `@keyframes` block.
+struct KeyframesParser {
+    declarations: NestedParser,
+}
+
+// Parses a keyframe selector, which is a percentage or one of `from` and `to`.
+fn parse_keyframe_offset<'i, 't>(input: &mut cssparser::Parser<'i, 't>) -> Result<f32, cssparser::ParseError<'i, ParseError>> {
+    let location = input.current_source_location();
+    match input.next()?.clone() {
+        cssparser::Token::Percentage { unit_value, .. } if (0.0..=1.0).contains(&unit_value) => Ok(unit_value),
+        cssparser::Token::Ident(ident) if ident.eq_ignore_ascii_case("from") => Ok(0.0),
+        cssparser::Token::Ident(ident) if ident.eq_ignore_ascii_case("to") => Ok(1.0),
+        token => Err(location.new_custom_error(ParseError {
+            kind: ParseErrorKind::InvalidSelectors,
+            message: format!("Invalid keyframe selector {:?}", token),
+            location: SourceLocation::from_file_location(input.current_source_url().unwrap_or("").to_string(), location),
+        })),
+    }
+}
+
+impl<'i> cssparser::QualifiedRuleParser<'i> for KeyframesParser {
+    type Prelude = Vec<f32>;
+    type QualifiedRule = Keyframe;
+    type Error = ParseError;
+
+    fn parse_prelude<'t>(&mut self, parser: &mut cssparser::Parser<'i, 't>) -> Result<Self::Prelude, cssparser::ParseError<'i, Self::Error>> {
+        parser.parse_comma_separated(parse_keyframe_offset)
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: Self::Prelude,
+        _start: &cssparser::ParserState,
+        parser: &mut cssparser::Parser<'i, 't>) -> Result<Self::QualifiedRule, cssparser::ParseError<'i, Self::Error>>
+    {
+        let body_parser = RuleBodyParser::<NestedParser, ParseResult, ParseError>::new(parser, &mut self.declarations);
+
+        let mut properties = Vec::new();
+        let mut only_declarations = true;
+        for entry in body_parser {
+            match entry {
+                Ok(ParseResult::Property(property)) => cascade_property(&mut properties, property),
+                Ok(_) => only_declarations = false,
+                Err(error) => return Err(error.0),
+            }
+        }
+
+        if !only_declarations {
+            return parse_error(parser, ParseErrorKind::InvalidQualifiedRule, String::from("Keyframes can only contain declarations"));
+        }
+
+        Ok(Keyframe { offsets: prelude, properties })
+    }
+}
+
+impl<'i> cssparser::AtRuleParser<'i> for KeyframesParser {
+    type Prelude = ();
+    type AtRule = Keyframe;
+    type Error = ParseError;
+}
+
+// Parses the descriptors inside a `@font-face` block.
+#[derive(Default)]
+struct FontFaceParser {
+    font_face: FontFace,
+}
+
+// Parses a font family name, which is either a string or a sequence of identifiers.
+fn parse_font_family<'i, 't>(input: &mut cssparser::Parser<'i, 't>) -> Result<String, cssparser::ParseError<'i, ParseError>> {
+    if let Ok(family) = input.try_parse(|input| input.expect_string().map(|family| family.to_string())) {
+        return Ok(family);
+    }
+
+    let mut parts = vec![input.expect_ident()?.to_string()];
+    while let Ok(part) = input.try_parse(|input| input.expect_ident().map(|part| part.to_string())) {
+        parts.push(part);
+    }
+    Ok(parts.join(" "))
+}
+
+// Parses a single entry of the `src` descriptor, like `url(font.ttf) format("truetype")`.
+fn parse_font_source<'i, 't>(input: &mut cssparser::Parser<'i, 't>) -> Result<FontFaceSource, cssparser::ParseError<'i, ParseError>> {
+    let url = input.expect_url()?.to_string();
+    let format = input.try_parse(|input| -> Result<String, cssparser::ParseError<'i, ParseError>> {
+        input.expect_function_matching("format")?;
+        input.parse_nested_block(|input| {
+            let format = input.expect_ident_or_string()?.to_string();
+            input.expect_exhausted()?;
+            Ok(format)
+        })
+    }).ok();
+    Ok(FontFaceSource { url, format })
+}
+
+impl<'i> cssparser::DeclarationParser<'i> for FontFaceParser {
+    type Declaration = ();
+    type Error = ParseError;
+
+    fn parse_value<'t>(&mut self, name: CowRcStr<'i>, input: &mut cssparser::Parser<'i, 't>, _state: &cssparser::ParserState) -> Result<Self::Declaration, cssparser::ParseError<'i, Self::Error>> {
+        match name.to_ascii_lowercase().as_str() {
+            "font-family" => self.font_face.family = parse_font_family(input)?,
+            "src" => self.font_face.sources = input.parse_comma_separated(parse_font_source)?,
+            "unicode-range" => {
+                self.font_face.unicode_ranges = input.parse_comma_separated(|input| {
+                    let range = cssparser::UnicodeRange::parse(input)?;
+                    Ok((range.start, range.end))
+                })?
+            }
+            // Other descriptors do not affect which font is loaded.
+            _ => while input.next().is_ok() {},
+        }
+        Ok(())
+    }
+}
+
+impl<'i> cssparser::QualifiedRuleParser<'i> for FontFaceParser {
+    type Prelude = ();
+    type QualifiedRule = ();
+    type Error = ParseError;
+}
+
+impl<'i> cssparser::AtRuleParser<'i> for FontFaceParser {
+    type Prelude = ();
+    type AtRule = ();
+    type Error = ParseError;
+}
+
+impl<'i> cssparser::RuleBodyItemParser<'i, (), ParseError> for FontFaceParser {
+    fn parse_declarations(&self) -> bool {
+        true
+    }
+
+    fn parse_qualified(&self) -> bool {
+        false
+    }
+}
@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
 // SPDX-FileCopyrightText: 2025 Arjen Hiemstra <ahiemstra@heimr.nl>
 
+use std::collections::HashMap;
+
 use crate::parseerror::ParseError;
 use crate::selector::{AttributeOperator, Selector, SelectorKind, SelectorPart, SelectorValue};
 use crate::value::Value;
@@ -26,17 +28,27 @@ impl cssparser::ToCss for PseudoElement {
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
-pub struct PseudoClass(String);
+pub struct PseudoClass {
+    name: String,
+    // The argument of a functional pseudo-class like `:lang(en)`.
+    argument: Option<String>,
+}
+
+impl PseudoClass {
+    fn is_one_of(&self, names: &[&str]) -> bool {
+        self.argument.is_none() && names.iter().any(|name| self.name.eq_ignore_ascii_case(name))
+    }
+}
 
 impl selectors::parser::NonTSPseudoClass for PseudoClass {
     type Impl = SelectorImpl;
 
     fn is_active_or_hover(&self) -> bool {
-        return false;
+        self.is_one_of(&["active", "hover"])
     }
 
     fn is_user_action_state(&self) -> bool {
-        return false;
+        self.is_one_of(&["active", "hover", "focus"])
     }
 
     fn visit<V>(&self, _visitor: &mut V) -> bool
@@ -50,7 +62,11 @@ impl cssparser::ToCss for PseudoClass {
     fn to_css<W>(&self, dest: &mut W) -> std::fmt::Result
     where
         W: std::fmt::Write {
-        dest.write_str(self.0.as_str())
+        dest.write_str(self.name.as_str())?;
+        if let Some(argument) = &self.argument {
+            dest.write_fmt(format_args!("({})", argument))?;
+        }
+        Ok(())
     }
 }
 
@@ -78,7 +94,18 @@ pub enum ParseRelative {
     Nested,
 }
 
-pub struct SelectorParser;
+/// The namespaces declared using `@namespace`, used to resolve namespace
+/// prefixes in selectors.
+#[derive(Debug, Default, Clone)]
+pub struct Namespaces {
+    pub default: Option<String>,
+    pub prefixes: HashMap<String, String>,
+}
+
+#[derive(Default)]
+pub struct SelectorParser {
+    pub namespaces: Namespaces,
+}
 
 impl SelectorParser {
     pub fn parse<'i, 't>(&self, parser: &mut cssparser::Parser<'i, 't>, relative: ParseRelative) -> Result<Vec<Selector>, cssparser::ParseError<'i, ParseError>> {
@@ -92,76 +119,108 @@ impl SelectorParser {
             return Err(parser.new_custom_error(parse_error_from_cssparser_error(&error, parser.current_source_url().unwrap_or("").to_string())))
         }
 
-        let mut selectors = Vec::new();
-        for entry in result.unwrap().slice() {
-            let mut selector = Selector::new();
-            let mut parts: Vec<SelectorPart> = Vec::new();
-
-            // Neither parse_order nor match_order actually return parts in parsing order.
-            // Instead, the parts between combinators seem to be always reversed in order.
-            // So what we do here is collect parts in the right order into a separate vec,
-            // then when there's a combinator we combine the parts with the combinator in
-            // the resulting selector.
-            for part in entry.iter_raw_parse_order_from(0) {
-                match part {
-                    selectors::parser::Component::LocalName(local_name) => parts.insert(0, SelectorPart::new_with_value(SelectorKind::Type, Value::from(&local_name.name))),
-                    selectors::parser::Component::ID(name) => parts.insert(0, SelectorPart::new_with_value(SelectorKind::Id, Value::from(name))),
-                    selectors::parser::Component::Class(name) => parts.insert(0, SelectorPart::new_with_value(SelectorKind::Class, Value::from(name))),
-                    selectors::parser::Component::NonTSPseudoClass(pseudo_class) => parts.insert(0, SelectorPart::new_with_value(SelectorKind::PseudoClass, Value::from(pseudo_class.0.as_str()))),
-                    selectors::parser::Component::ParentSelector => parts.insert(0, SelectorPart::new_with_empty(SelectorKind::RelativeParent)),
-                    selectors::parser::Component::Root => parts.insert(0, SelectorPart::new_with_empty(SelectorKind::DocumentRoot)),
-                    selectors::parser::Component::ExplicitUniversalType => parts.insert(0, SelectorPart::new_with_empty(SelectorKind::AnyElement)),
-
-                    selectors::parser::Component::AttributeInNoNamespaceExists { local_name, local_name_lower: _ } => {
-                        parts.insert(0, SelectorPart {
-                            kind: SelectorKind::Attribute,
-                            value: SelectorValue::Attribute {
-                                name: local_name.to_string(),
-                                operator: AttributeOperator::Exists,
-                                value: Value::empty(),
-                            }
-                        })
-                    }
+        Ok(result.unwrap().slice().iter().map(convert_selector).collect())
+    }
+}
 
-                    selectors::parser::Component::AttributeInNoNamespace { local_name, operator, value, case_sensitivity: _ } => {
-                        let attribute_operator = match operator {
-                            selectors::attr::AttrSelectorOperator::Equal => AttributeOperator::Equals,
-                            selectors::attr::AttrSelectorOperator::Includes => AttributeOperator::Includes,
-                            selectors::attr::AttrSelectorOperator::Prefix => AttributeOperator::Prefixed,
-                            selectors::attr::AttrSelectorOperator::Suffix => AttributeOperator::Suffixed,
-                            selectors::attr::AttrSelectorOperator::Substring => AttributeOperator::Substring,
-                            selectors::attr::AttrSelectorOperator::DashMatch => AttributeOperator::DashMatch,
-                        };
-                        parts.insert(0, SelectorPart {
-                            kind: SelectorKind::Attribute,
-                            value: SelectorValue::Attribute {
-                                name: local_name.to_string(),
-                                operator: attribute_operator,
-                                value: Value::from(value),
-                            }
-                        });
-                    },
-
-                    selectors::parser::Component::Combinator(combinator) => {
-                        selector.parts.extend(parts);
-                        parts = Vec::new();
-
-                        match combinator {
-                            selectors::parser::Combinator::Descendant => selector.parts.push(SelectorPart::new_with_empty(SelectorKind::DescendantCombinator)),
-                            selectors::parser::Combinator::Child => selector.parts.push(SelectorPart::new_with_empty(SelectorKind::ChildCombinator)),
-                            _ => println!("Warning: Combinator {:#?} not implemented", combinator),
-                        }
+fn convert_selector(entry: &selectors::parser::Selector<SelectorImpl>) -> Selector {
+    let mut selector = Selector::new();
+    let mut parts: Vec<SelectorPart> = Vec::new();
+
+    // Neither parse_order nor match_order actually return parts in parsing order.
+    // Instead, the parts between combinators seem to be always reversed in order.
+    // So what we do here is collect parts in the right order into a separate vec,
+    // then when there's a combinator we combine the parts with the combinator in
+    // the resulting selector.
+    for part in entry.iter_raw_parse_order_from(0) {
+        match part {
+            selectors::parser::Component::LocalName(local_name) => parts.insert(0, SelectorPart::new_with_value(SelectorKind::Type, Value::from(&local_name.name))),
+            selectors::parser::Component::ID(name) => parts.insert(0, SelectorPart::new_with_value(SelectorKind::Id, Value::from(name))),
+            selectors::parser::Component::Class(name) => parts.insert(0, SelectorPart::new_with_value(SelectorKind::Class, Value::from(name))),
+            selectors::parser::Component::NonTSPseudoClass(pseudo_class) => {
+                let name = Value::from(pseudo_class.name.as_str());
+                match &pseudo_class.argument {
+                    Some(argument) => parts.insert(0, SelectorPart::new_with_argument(SelectorKind::PseudoClass, name, argument)),
+                    None => parts.insert(0, SelectorPart::new_with_value(SelectorKind::PseudoClass, name)),
+                }
+            }
+            selectors::parser::Component::ParentSelector => parts.insert(0, SelectorPart::new_with_empty(SelectorKind::RelativeParent)),
+            selectors::parser::Component::Root => parts.insert(0, SelectorPart::new_with_empty(SelectorKind::DocumentRoot)),
+            selectors::parser::Component::ExplicitUniversalType => parts.insert(0, SelectorPart::new_with_empty(SelectorKind::AnyElement)),
+
+            selectors::parser::Component::Namespace(prefix, url) => parts.insert(0, SelectorPart::new_with_namespace(&prefix.to_string(), &url.to_string())),
+            selectors::parser::Component::DefaultNamespace(url) => parts.insert(0, SelectorPart::new_with_namespace("", &url.to_string())),
+            selectors::parser::Component::ExplicitAnyNamespace => parts.insert(0, SelectorPart::new_with_namespace("*", "")),
+            selectors::parser::Component::ExplicitNoNamespace => parts.insert(0, SelectorPart::new_with_namespace("", "")),
+
+            selectors::parser::Component::PseudoElement(pseudo_element) => parts.insert(0, SelectorPart::new_with_value(SelectorKind::PseudoElement, Value::from(pseudo_element.0.as_str()))),
+            selectors::parser::Component::Part(names) => {
+                let mut argument = String::new();
+                for (index, name) in names.iter().enumerate() {
+                    if index > 0 {
+                        argument.push(' ');
                     }
-                    _ => println!("Warning: Selector part {:#?} not implemented", part),
+                    let _ = cssparser::serialize_identifier(&name.to_string(), &mut argument);
                 }
+                parts.insert(0, SelectorPart::new_with_argument(SelectorKind::PseudoElement, Value::from("part"), &argument))
+            }
+            selectors::parser::Component::Slotted(slotted) => {
+                parts.insert(0, SelectorPart::new_with_argument(SelectorKind::PseudoElement, Value::from("slotted"), &convert_selector(slotted).to_css()))
             }
 
-            selector.parts.extend(parts);
-            selectors.push(selector);
-        }
+            selectors::parser::Component::Negation(list) => {
+                let inner: Vec<_> = list.slice().iter().map(convert_selector).collect();
+                parts.insert(0, SelectorPart::new_with_selectors(SelectorKind::Negation, &inner))
+            }
 
-        Ok(selectors)
+            selectors::parser::Component::AttributeInNoNamespaceExists { local_name, local_name_lower: _ } => {
+                parts.insert(0, SelectorPart {
+                    kind: SelectorKind::Attribute,
+                    value: SelectorValue::Attribute {
+                        name: local_name.to_string(),
+                        operator: AttributeOperator::Exists,
+                        value: Value::empty(),
+                    }
+                })
+            }
+
+            selectors::parser::Component::AttributeInNoNamespace { local_name, operator, value, case_sensitivity: _ } => {
+                let attribute_operator = match operator {
+                    selectors::attr::AttrSelectorOperator::Equal => AttributeOperator::Equals,
+                    selectors::attr::AttrSelectorOperator::Includes => AttributeOperator::Includes,
+                    selectors::attr::AttrSelectorOperator::Prefix => AttributeOperator::Prefixed,
+                    selectors::attr::AttrSelectorOperator::Suffix => AttributeOperator::Suffixed,
+                    selectors::attr::AttrSelectorOperator::Substring => AttributeOperator::Substring,
+                    selectors::attr::AttrSelectorOperator::DashMatch => AttributeOperator::DashMatch,
+                };
+                parts.insert(0, SelectorPart {
+                    kind: SelectorKind::Attribute,
+                    value: SelectorValue::Attribute {
+                        name: local_name.to_string(),
+                        operator: attribute_operator,
+                        value: Value::from(value),
+                    }
+                });
+            },
+
+            selectors::parser::Component::Combinator(combinator) => {
+                selector.parts.extend(parts);
+                parts = Vec::new();
+
+                match combinator {
+                    selectors::parser::Combinator::Descendant => selector.parts.push(SelectorPart::new_with_empty(SelectorKind::DescendantCombinator)),
+                    selectors::parser::Combinator::Child => selector.parts.push(SelectorPart::new_with_empty(SelectorKind::ChildCombinator)),
+                    // These only separate a pseudo-element from the compound it applies to.
+                    selectors::parser::Combinator::PseudoElement | selectors::parser::Combinator::Part | selectors::parser::Combinator::SlotAssignment => (),
+                    _ => println!("Warning: Combinator {:#?} not implemented", combinator),
+                }
+            }
+            _ => println!("Warning: Selector part {:#?} not implemented", part),
+        }
     }
+
+    selector.parts.extend(parts);
+    selector
 }
 
 impl <'i> ::selectors::Parser<'i> for SelectorParser {
@@ -173,7 +232,22 @@ impl <'i> ::selectors::Parser<'i> for SelectorParser {
         _location: cssparser::SourceLocation,
         name: cssparser::CowRcStr<'i>,
     ) -> Result<<Self::Impl as selectors::SelectorImpl>::NonTSPseudoClass, cssparser::ParseError<'i, Self::Error>> {
-        Ok(PseudoClass(name.to_string()))
+        Ok(PseudoClass { name: name.to_string(), argument: None })
+    }
+
+    fn parse_non_ts_functional_pseudo_class<'t>(
+        &self,
+        name: cssparser::CowRcStr<'i>,
+        parser: &mut cssparser::Parser<'i, 't>,
+        _after_part: bool,
+    ) -> Result<<Self::Impl as selectors::SelectorImpl>::NonTSPseudoClass, cssparser::ParseError<'i, Self::Error>> {
+        match name.as_ref() {
+            "dir" | "lang" => {
+                let argument = parser.expect_ident_or_string()?.to_string();
+                Ok(PseudoClass { name: name.to_string(), argument: Some(argument) })
+            }
+            _ => Err(parser.new_custom_error(selectors::parser::SelectorParseErrorKind::UnsupportedPseudoClassOrElement(name))),
+        }
     }
 
     fn parse_pseudo_element(
@@ -187,4 +261,20 @@ impl <'i> ::selectors::Parser<'i> for SelectorParser {
     fn parse_parent_selector(&self) -> bool {
         true
     }
+
+    fn parse_part(&self) -> bool {
+        true
+    }
+
+    fn parse_slotted(&self) -> bool {
+        true
+    }
+
+    fn default_namespace(&self) -> Option<Identifier> {
+        self.namespaces.default.as_deref().map(Identifier::from)
+    }
+
+    fn namespace_for_prefix(&self, prefix: &Identifier) -> Option<Identifier> {
+        self.namespaces.prefixes.get(&prefix.to_string()).map(|url| Identifier::from(url.as_str()))
+    }
 }
@@ -6,8 +6,13 @@ use std::path::PathBuf;
 use ffi::ValueConversionError;
 
 use crate::selector::{Selector, SelectorPart, SelectorKind, SelectorValue};
-use crate::parseerror::ParseError;
-use crate::property::Property;
+use crate::background::BackgroundLayer;
+use crate::fontface::FontFace;
+use crate::grid::GridTrack;
+use crate::keyframes::{Keyframe, Keyframes};
+use crate::media::{MediaContext, MediaQuery};
+use crate::parseerror::{ParseError, ParseErrorKind, SourceLocation};
+use crate::property::{Property, PropertyDefinition};
 use crate::stylerule::StyleRule;
 use crate::stylesheet::StyleSheet;
 use crate::value;
@@ -17,7 +22,7 @@ use crate::value::{Color, ColorOperation};
 
 
 #[cxx::bridge(namespace = "cssparser::rust")]
-mod ffi {
+pub(crate) mod ffi {
     #[derive(Debug, Clone, Copy)]
     pub enum Unit {
         Unknown,
@@ -32,6 +37,23 @@ mod ffi {
         Radians,
         Seconds,
         Milliseconds,
+        Dppx,
+        Dpi,
+        Dpcm,
+        Fr,
+        Other,
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    pub enum DimensionClass {
+        Unknown,
+        Number,
+        Length,
+        Percentage,
+        Angle,
+        Time,
+        Resolution,
+        Flex,
     }
 
     pub enum ColorType {
@@ -61,8 +83,9 @@ mod ffi {
         Mix,
     }
 
-    // This uses -1 to indicate the value should not be set, which is why this
-    // uses i16 for values.
+    // Channels, including alpha, are in the range [0, 255]. This uses -1 to
+    // indicate the value should not be set, which is why this uses i16 for
+    // values. See set_channels().
     pub struct SetColorOperationValues {
         r: i16,
         g: i16,
@@ -84,10 +107,34 @@ mod ffi {
         Empty,
         Dimension,
         String,
+        Keyword,
         Color,
         Image,
         Url,
         Integer,
+        Slash,
+        CssWideKeyword,
+        UnicodeRange,
+    }
+
+    pub enum GridTrackType {
+        Breadth,
+        Keyword,
+        MinMax,
+        FitContent,
+    }
+
+    pub struct UnicodeRange {
+        start: u32,
+        end: u32,
+    }
+
+    pub enum CssWideKeyword {
+        Initial,
+        Inherit,
+        Unset,
+        Revert,
+        RevertLayer,
     }
 
     pub enum AttributeOperator {
@@ -112,6 +159,9 @@ mod ffi {
         Attribute,
         RelativeParent,
         DocumentRoot,
+        Namespace,
+        Negation,
+        PseudoElement,
         DescendantCombinator,
         ChildCombinator,
     }
@@ -126,15 +176,58 @@ mod ffi {
         message: String,
     }
 
+    pub struct FontFaceSource {
+        url: String,
+        format: String,
+    }
+
+    pub struct MediaContext {
+        media_type: String,
+        width: f32,
+        height: f32,
+        device_pixel_ratio: f32,
+        color_scheme: String,
+    }
+
+    pub enum ParseErrorKind {
+        Unspecified,
+        Unimplemented,
+        UnexpectedEndOfInput,
+        Unknown,
+        UnknownProperty,
+        UnexpectedToken,
+        InvalidSelectors,
+        InvalidPropertySyntax,
+        InvalidPropertyValue,
+        UnknownFunction,
+        InvalidPropertyDefinition,
+        PropertyValueDoesNotMatchSyntax,
+        UnsupportedAtRule,
+        InvalidAtRule,
+        InvalidQualifiedRule,
+        FileError,
+        StyleSheetParseError,
+    }
+
     pub struct StyleSheetError {
+        kind: ParseErrorKind,
         file: String,
         line: u32,
         column: u32,
         message: String,
     }
 
+    pub struct ParseStats {
+        path: String,
+        rules: usize,
+        errors: usize,
+        bytes: usize,
+        duration_microseconds: u64,
+    }
+
     extern "Rust" {
         fn to_string(self: &Dimension) -> String;
+        fn unit_class(unit: Unit) -> DimensionClass;
 
         fn operation_type(self: &ModifiedColor) -> ColorOperationType;
         fn color_value(self: &ModifiedColor) -> Result<Box<Color>>;
@@ -149,15 +242,25 @@ mod ffi {
         fn to_rgba(self: &Color) -> Result<Rgba>;
         fn to_custom(self: &Color) -> Result<CustomColor>;
         fn to_modified(self: &Color) -> Result<ModifiedColor>;
+        fn red(self: &Color) -> Result<u8>;
+        fn green(self: &Color) -> Result<u8>;
+        fn blue(self: &Color) -> Result<u8>;
+        fn alpha(self: &Color) -> Result<u8>;
+        fn luminance(self: &Color) -> Result<f32>;
 
         type Value;
         fn value_type(self: &Value) -> ValueType;
+        fn is_empty(self: &Value) -> bool;
         fn to_dimension(self: &Value) -> Result<Dimension>;
         fn to_string(self: &Value) -> String;
         fn to_color(self: &Value) -> Result<Box<Color>>;
         fn to_image(self: &Value) -> Result<&str>;
         fn to_url(self: &Value) -> Result<&str>;
         fn to_integer(self: &Value) -> Result<i32>;
+        fn to_number(self: &Value) -> Result<f32>;
+        fn unit_name(self: &Value) -> String;
+        fn to_css_wide_keyword(self: &Value) -> Result<CssWideKeyword>;
+        fn to_unicode_range(self: &Value) -> Result<UnicodeRange>;
 
         type SelectorPart;
         fn kind(self: &SelectorPart) -> SelectorKind;
@@ -165,24 +268,94 @@ mod ffi {
         fn attribute_name(self: &SelectorPart) -> String;
         fn attribute_operator(self: &SelectorPart) -> AttributeOperator;
         fn attribute_value(self: &SelectorPart) -> &Value;
+        fn namespace_prefix(self: &SelectorPart) -> String;
+        fn namespace_url(self: &SelectorPart) -> String;
+        fn argument(self: &SelectorPart) -> String;
+        fn negated_selectors(self: &SelectorPart) -> Vec<Selector>;
 
         type Selector;
         fn parts(self: &Selector) -> Vec<SelectorPart>;
+        fn has_relative_parent(self: &Selector) -> bool;
+        fn complexity(self: &Selector) -> usize;
 
         type Property;
         fn name(self: &Property) -> String;
         fn values(self: &Property) -> Vec<Value>;
+        fn important(self: &Property) -> bool;
+        fn flag(self: &Property) -> String;
+        fn merge_properties(base: &Vec<Property>, overrides: &Vec<Property>) -> Vec<Property>;
+        fn property_accepted_types(name: &str) -> Vec<String>;
+        fn registered_property_names() -> Vec<String>;
+        fn property_syntax(name: &str) -> String;
+        fn property_function_syntax(name: &str) -> String;
+        fn parse_property_value(name: &str, value: &str) -> Result<Vec<Value>>;
+
+        type PropertyDefinition;
+        fn name(self: &PropertyDefinition) -> String;
+        fn syntax_string(self: &PropertyDefinition) -> String;
+        fn inherits(self: &PropertyDefinition) -> bool;
+        fn initial_values(self: &PropertyDefinition) -> Vec<Value>;
+        fn to_css(self: &PropertyDefinition) -> String;
+        fn property_definition(name: &str) -> Result<Box<PropertyDefinition>>;
+
+        type MediaQuery;
+        #[cxx_name = "evaluate"]
+        fn evaluate_context(self: &MediaQuery, context: &MediaContext) -> bool;
 
         type StyleRule;
         fn selector(self: &StyleRule) -> &Selector;
         fn properties(self: &StyleRule) -> Vec<Property>;
+        fn media(self: &StyleRule) -> Box<MediaQuery>;
+        fn layer(self: &StyleRule) -> String;
+
+        type Keyframe;
+        fn offsets(self: &Keyframe) -> Vec<f32>;
+        fn properties(self: &Keyframe) -> Vec<Property>;
+
+        type Keyframes;
+        fn name(self: &Keyframes) -> String;
+        fn stops(self: &Keyframes) -> Vec<Keyframe>;
+
+        type FontFace;
+        fn family(self: &FontFace) -> String;
+        fn sources(self: &FontFace) -> Vec<FontFaceSource>;
+        fn unicode_ranges(self: &FontFace) -> Vec<UnicodeRange>;
+
+        type BackgroundLayer;
+        fn image(self: &BackgroundLayer) -> String;
+        fn repeat(self: &BackgroundLayer) -> Vec<String>;
+        fn position(self: &BackgroundLayer) -> Vec<Value>;
+        fn size(self: &BackgroundLayer) -> Vec<Value>;
+        fn attachment(self: &BackgroundLayer) -> String;
+        fn origin(self: &BackgroundLayer) -> String;
+        fn clip(self: &BackgroundLayer) -> String;
+        fn color(self: &BackgroundLayer) -> Box<Color>;
+        fn parse_background(value: &str) -> Result<Vec<BackgroundLayer>>;
+
+        type GridTrack;
+        fn track_type(self: &GridTrack) -> GridTrackType;
+        fn breadth(self: &GridTrack) -> Result<Dimension>;
+        fn keyword(self: &GridTrack) -> Result<String>;
+        fn minimum(self: &GridTrack) -> Result<Box<GridTrack>>;
+        fn maximum(self: &GridTrack) -> Result<Box<GridTrack>>;
+        fn parse_track_list(value: &str) -> Result<Vec<GridTrack>>;
 
         type StyleSheet;
         fn rules(self: &StyleSheet) -> Vec<StyleRule>;
         fn errors(self: &StyleSheet) -> Vec<StyleSheetError>;
+        fn errors_json(self: &StyleSheet) -> String;
         fn paths(self: &StyleSheet) -> Vec<String>;
+        fn layers(self: &StyleSheet) -> Vec<String>;
+        fn keyframes(self: &StyleSheet) -> Vec<Keyframes>;
+        fn font_faces(self: &StyleSheet) -> Vec<FontFace>;
+        fn variables(self: &StyleSheet) -> Vec<Property>;
+        fn variable_errors(self: &StyleSheet) -> Vec<StyleSheetError>;
+        fn parse_stats(self: &StyleSheet) -> Vec<ParseStats>;
+        fn set_record_stats(self: &mut StyleSheet, enabled: bool);
         fn parse(self: &mut StyleSheet) -> Result<()>;
         fn parse_string(self: &mut StyleSheet, data: &str) -> Result<()>;
+        fn parse_bytes(self: &mut StyleSheet, data: &[u8]) -> Result<()>;
+        fn parse_inline(self: &mut StyleSheet, declarations: &str) -> Result<Vec<Property>>;
         fn import_file(self: &mut StyleSheet, path: &str) -> Result<()>;
 
         fn create_stylesheet(path: &str) -> Box<StyleSheet>;
@@ -203,6 +376,17 @@ macro_rules! convert_enum {
     };
 }
 
+convert_enum!(value::DimensionClass, ffi::DimensionClass, {
+    value::DimensionClass::Unknown => Unknown,
+    value::DimensionClass::Number => Number,
+    value::DimensionClass::Length => Length,
+    value::DimensionClass::Percentage => Percentage,
+    value::DimensionClass::Angle => Angle,
+    value::DimensionClass::Time => Time,
+    value::DimensionClass::Resolution => Resolution,
+    value::DimensionClass::Flex => Flex,
+});
+
 convert_enum!(value::ColorData, ffi::ColorType, {
     value::ColorData::Empty => Empty,
     value::ColorData::Rgba{ r: _, g: _, b: _, a: _ } => Rgba,
@@ -214,10 +398,29 @@ convert_enum!(value::ValueData, ffi::ValueType, {
     value::ValueData::Empty => Empty,
     value::ValueData::Dimension(_) => Dimension,
     value::ValueData::String(_) => String,
+    value::ValueData::Keyword(_) => Keyword,
     value::ValueData::Color(_) => Color,
     value::ValueData::Image(_) => Image,
     value::ValueData::Url(_) => Url,
     value::ValueData::Integer(_) => Integer,
+    value::ValueData::Slash => Slash,
+    value::ValueData::CssWideKeyword(_) => CssWideKeyword,
+    value::ValueData::UnicodeRange { .. } => UnicodeRange,
+});
+
+convert_enum!(GridTrack, ffi::GridTrackType, {
+    GridTrack::Breadth(_) => Breadth,
+    GridTrack::Keyword(_) => Keyword,
+    GridTrack::MinMax { .. } => MinMax,
+    GridTrack::FitContent(_) => FitContent,
+});
+
+convert_enum!(value::CssWideKeyword, ffi::CssWideKeyword, {
+    value::CssWideKeyword::Initial => Initial,
+    value::CssWideKeyword::Inherit => Inherit,
+    value::CssWideKeyword::Unset => Unset,
+    value::CssWideKeyword::Revert => Revert,
+    value::CssWideKeyword::RevertLayer => RevertLayer,
 });
 
 convert_enum!(value::Unit, ffi::Unit, {
@@ -233,6 +436,11 @@ convert_enum!(value::Unit, ffi::Unit, {
     value::Unit::Radians => Radians,
     value::Unit::Seconds => Seconds,
     value::Unit::Milliseconds => Milliseconds,
+    value::Unit::Dppx => Dppx,
+    value::Unit::Dpi => Dpi,
+    value::Unit::Dpcm => Dpcm,
+    value::Unit::Fr => Fr,
+    value::Unit::Other(_) => Other,
 });
 
 convert_enum!(crate::selector::AttributeOperator, ffi::AttributeOperator, {
@@ -256,10 +464,33 @@ convert_enum!(SelectorKind, ffi::SelectorKind, {
     SelectorKind::Attribute => Attribute,
     SelectorKind::RelativeParent => RelativeParent,
     SelectorKind::DocumentRoot => DocumentRoot,
+    SelectorKind::Namespace => Namespace,
+    SelectorKind::Negation => Negation,
+    SelectorKind::PseudoElement => PseudoElement,
     SelectorKind::DescendantCombinator => DescendantCombinator,
     SelectorKind::ChildCombinator => ChildCombinator,
 });
 
+convert_enum!(ParseErrorKind, ffi::ParseErrorKind, {
+    ParseErrorKind::Unspecified => Unspecified,
+    ParseErrorKind::Unimplemented => Unimplemented,
+    ParseErrorKind::UnexpectedEndOfInput => UnexpectedEndOfInput,
+    ParseErrorKind::Unknown => Unknown,
+    ParseErrorKind::UnknownProperty => UnknownProperty,
+    ParseErrorKind::UnexpectedToken => UnexpectedToken,
+    ParseErrorKind::InvalidSelectors => InvalidSelectors,
+    ParseErrorKind::InvalidPropertySyntax => InvalidPropertySyntax,
+    ParseErrorKind::InvalidPropertyValue => InvalidPropertyValue,
+    ParseErrorKind::UnknownFunction => UnknownFunction,
+    ParseErrorKind::InvalidPropertyDefinition => InvalidPropertyDefinition,
+    ParseErrorKind::PropertyValueDoesNotMatchSyntax => PropertyValueDoesNotMatchSyntax,
+    ParseErrorKind::UnsupportedAtRule => UnsupportedAtRule,
+    ParseErrorKind::InvalidAtRule => InvalidAtRule,
+    ParseErrorKind::InvalidQualifiedRule => InvalidQualifiedRule,
+    ParseErrorKind::FileError => FileError,
+    ParseErrorKind::StyleSheetParseError => StyleSheetParseError,
+});
+
 convert_enum!(value::ColorOperation, ffi::ColorOperationType, {
     value::ColorOperation::Set { r: _, g: _, b: _, a: _ } => Set,
     value::ColorOperation::Add { other: _ } => Add,
@@ -277,12 +508,48 @@ impl From<&value::Dimension> for ffi::Dimension {
     }
 }
 
+fn unit_class(unit: ffi::Unit) -> ffi::DimensionClass {
+    let unit = match unit {
+        ffi::Unit::Number => value::Unit::Number,
+        ffi::Unit::Px => value::Unit::Px,
+        ffi::Unit::Em => value::Unit::Em,
+        ffi::Unit::Rem => value::Unit::Rem,
+        ffi::Unit::Pt => value::Unit::Pt,
+        ffi::Unit::Percent => value::Unit::Percent,
+        ffi::Unit::Degrees => value::Unit::Degrees,
+        ffi::Unit::Radians => value::Unit::Radians,
+        ffi::Unit::Seconds => value::Unit::Seconds,
+        ffi::Unit::Milliseconds => value::Unit::Milliseconds,
+        ffi::Unit::Dppx => value::Unit::Dppx,
+        ffi::Unit::Dpi => value::Unit::Dpi,
+        ffi::Unit::Dpcm => value::Unit::Dpcm,
+        ffi::Unit::Fr => value::Unit::Fr,
+        _ => value::Unit::Unknown,
+    };
+    value::Dimension { value: 0.0, unit }.unit_class().into()
+}
+
 impl ffi::Dimension {
     fn to_string(&self) -> String {
         format!("{}{:?}", self.value, self.unit)
     }
 }
 
+/// The value of a channel in `SetColorOperationValues` that should not be set.
+pub(crate) const UNSET_CHANNEL: i16 = -1;
+
+/// Encode the red, green, blue and alpha channels of a set operation the way
+/// `SetColorOperationValues` expects them.
+///
+/// Returns None if `operation` is not a set operation.
+pub(crate) fn set_channels(operation: &ColorOperation) -> Option<[i16; 4]> {
+    if let ColorOperation::Set { r, g, b, a } = operation {
+        Some([r, g, b, a].map(|channel| channel.map_or(UNSET_CHANNEL, i16::from)))
+    } else {
+        None
+    }
+}
+
 impl ffi::ModifiedColor {
     fn operation_type(&self) -> ffi::ColorOperationType {
         (*self.operation.clone()).into()
@@ -298,13 +565,8 @@ impl ffi::ModifiedColor {
     }
 
     fn set_values(&self) -> Result<ffi::SetColorOperationValues, ffi::ValueConversionError> {
-        if let value::ColorOperation::Set { r, g, b, a } = self.operation.as_ref() {
-            Ok(ffi::SetColorOperationValues {
-                r: r.map_or(-1, |v| v as i16),
-                g: g.map_or(-1, |v| v as i16),
-                b: b.map_or(-1, |v| v as i16),
-                a: a.map_or(-1, |v| v as i16),
-            })
+        if let Some([r, g, b, a]) = set_channels(&self.operation) {
+            Ok(ffi::SetColorOperationValues { r, g, b, a })
         } else {
             Err(ValueConversionError { message: String::from("Not a set color operation") })
         }
@@ -325,6 +587,7 @@ impl ffi::ModifiedColor {
 impl ffi::StyleSheetError {
     fn from_parse_error(error: &ParseError) -> ffi::StyleSheetError {
         ffi::StyleSheetError{
+            kind: error.kind.clone().into(),
             file: error.location.file.clone(),
             line: error.location.line,
             column: error.location.column,
@@ -370,10 +633,34 @@ impl value::Color {
             Err(ValueConversionError{ message: String::from("Not a Modified color") })
         }
     }
+
+    fn resolved(&self) -> Result<(u8, u8, u8, u8), ffi::ValueConversionError> {
+        self.resolve().ok_or_else(|| ValueConversionError{ message: String::from("Color cannot be resolved") })
+    }
+
+    fn red(&self) -> Result<u8, ffi::ValueConversionError> {
+        self.resolved().map(|(r, _, _, _)| r)
+    }
+
+    fn green(&self) -> Result<u8, ffi::ValueConversionError> {
+        self.resolved().map(|(_, g, _, _)| g)
+    }
+
+    fn blue(&self) -> Result<u8, ffi::ValueConversionError> {
+        self.resolved().map(|(_, _, b, _)| b)
+    }
+
+    fn alpha(&self) -> Result<u8, ffi::ValueConversionError> {
+        self.resolved().map(|(_, _, _, a)| a)
+    }
+
+    fn luminance(&self) -> Result<f32, ffi::ValueConversionError> {
+        self.relative_luminance().ok_or_else(|| ValueConversionError{ message: String::from("Color cannot be resolved") })
+    }
 }
 
 impl value::Value {
-    fn value_type(&self) -> ffi::ValueType {
+    pub(crate) fn value_type(&self) -> ffi::ValueType {
         self.data.clone().into()
     }
 
@@ -401,8 +688,45 @@ impl value::Value {
         }
     }
 
-    fn to_image(&self) -> Result<&str, ffi::ValueConversionError> {
-        Err(ffi::ValueConversionError{ message: String::from("Unimplemented") })
+    /// Returns the number of an integer or a dimension without unit.
+    pub(crate) fn to_number(&self) -> Result<f32, ffi::ValueConversionError> {
+        match self.data {
+            value::ValueData::Integer(integer) => Ok(integer as f32),
+            value::ValueData::Dimension(value::Dimension { value, unit: value::Unit::Number }) => Ok(value),
+            _ => Err(ffi::ValueConversionError{ message: String::from("Not a number value") }),
+        }
+    }
+
+    fn to_css_wide_keyword(&self) -> Result<ffi::CssWideKeyword, ffi::ValueConversionError> {
+        if let value::ValueData::CssWideKeyword(keyword) = self.data {
+            Ok(keyword.into())
+        } else {
+            Err(ffi::ValueConversionError{ message: String::from("Not a CSS-wide keyword") })
+        }
+    }
+
+    fn to_unicode_range(&self) -> Result<ffi::UnicodeRange, ffi::ValueConversionError> {
+        if let value::ValueData::UnicodeRange { start, end } = self.data {
+            Ok(ffi::UnicodeRange { start, end })
+        } else {
+            Err(ffi::ValueConversionError{ message: String::from("Not a Unicode range") })
+        }
+    }
+
+    fn unit_name(&self) -> String {
+        if let value::ValueData::Dimension(value::Dimension { unit: value::Unit::Other(unit), .. }) = &self.data {
+            unit.clone()
+        } else {
+            String::new()
+        }
+    }
+
+    pub(crate) fn to_image(&self) -> Result<&str, ffi::ValueConversionError> {
+        if let value::ValueData::Image(image) = &self.data {
+            Ok(image.as_str())
+        } else {
+            Err(ffi::ValueConversionError{ message: String::from("Not an image") })
+        }
     }
 
     fn to_url(&self) -> Result<&str, ffi::ValueConversionError> {
@@ -420,10 +744,9 @@ impl SelectorPart {
     }
 
     fn value(&self) -> &value::Value {
-        if let SelectorValue::Value(value) = &self.value {
-            value
-        } else {
-            Value::empty_ref()
+        match &self.value {
+            SelectorValue::Value(value) | SelectorValue::Function { name: value, argument: _ } => value,
+            _ => Value::empty_ref(),
         }
     }
 
@@ -450,11 +773,43 @@ impl SelectorPart {
             Value::empty_ref()
         }
     }
+
+    fn namespace_prefix(&self) -> String {
+        if let SelectorValue::Namespace { prefix, url: _ } = &self.value {
+            prefix.clone()
+        } else {
+            String::new()
+        }
+    }
+
+    fn namespace_url(&self) -> String {
+        if let SelectorValue::Namespace { prefix: _, url } = &self.value {
+            url.clone()
+        } else {
+            String::new()
+        }
+    }
+
+    fn argument(&self) -> String {
+        if let SelectorValue::Function { name: _, argument } = &self.value {
+            argument.clone()
+        } else {
+            String::new()
+        }
+    }
+
+    fn negated_selectors(&self) -> Vec<Selector> {
+        if let SelectorValue::Selectors(selectors) = &self.value {
+            selectors.clone()
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 impl Selector {
     fn parts(&self) -> Vec<SelectorPart> {
-        self.parts.clone()
+        self.iter_parts().cloned().collect()
     }
 }
 
@@ -466,6 +821,92 @@ impl Property {
     fn values(&self) -> Vec<value::Value> {
         self.values.clone()
     }
+
+    fn important(&self) -> bool {
+        self.important
+    }
+
+    fn flag(&self) -> String {
+        self.flag.clone().unwrap_or_default()
+    }
+}
+
+// cxx only supports passing vectors of opaque types, not slices.
+#[allow(clippy::ptr_arg)]
+fn merge_properties(base: &Vec<Property>, overrides: &Vec<Property>) -> Vec<Property> {
+    crate::property::merge_properties(base, overrides)
+}
+
+fn property_accepted_types(name: &str) -> Vec<String> {
+    crate::property::property_definition(name).map(|definition| definition.accepted_types()).unwrap_or_default()
+}
+
+fn property_syntax(name: &str) -> String {
+    crate::property::property_definition(name).map(|definition| definition.syntax_string()).unwrap_or_default()
+}
+
+fn property_function_syntax(name: &str) -> String {
+    crate::details::property::function::property_function_syntax(name).unwrap_or_default()
+}
+
+fn registered_property_names() -> Vec<String> {
+    crate::property::registered_property_names()
+}
+
+fn parse_property_value(name: &str, value: &str) -> Result<Vec<Value>, ParseError> {
+    if let Some(definition) = crate::property::property_definition(name) {
+        crate::property::parse_property_value(&definition, value)
+    } else {
+        Err(ParseError {
+            kind: ParseErrorKind::UnknownProperty,
+            message: format!("Unknown property {}", name),
+            location: SourceLocation::from_file(""),
+        })
+    }
+}
+
+impl PropertyDefinition {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn inherits(&self) -> bool {
+        self.inherit
+    }
+
+    fn initial_values(&self) -> Vec<Value> {
+        self.initial.clone()
+    }
+}
+
+fn property_definition(name: &str) -> Result<Box<PropertyDefinition>, ParseError> {
+    if let Some(definition) = crate::property::property_definition(name) {
+        Ok(Box::new(definition.as_ref().clone()))
+    } else {
+        Err(ParseError {
+            kind: ParseErrorKind::UnknownProperty,
+            message: format!("Unknown property {}", name),
+            location: SourceLocation::from_file(""),
+        })
+    }
+}
+
+impl From<&ffi::MediaContext> for MediaContext {
+    fn from(value: &ffi::MediaContext) -> Self {
+        MediaContext {
+            media_type: value.media_type.clone(),
+            width: value.width,
+            height: value.height,
+            device_pixel_ratio: value.device_pixel_ratio,
+            color_scheme: value.color_scheme.clone(),
+        }
+    }
+}
+
+impl MediaQuery {
+    fn evaluate_context(&self, context: &ffi::MediaContext) -> bool {
+        self.evaluate(&context.into())
+    }
 }
 
 impl StyleRule {
@@ -473,11 +914,135 @@ impl StyleRule {
         &self.selector
     }
 
+    fn properties(&self) -> Vec<Property> {
+        self.properties.to_vec()
+    }
+
+    fn media(&self) -> Box<MediaQuery> {
+        Box::new(self.media.clone().unwrap_or_else(MediaQuery::empty))
+    }
+
+    fn layer(&self) -> String {
+        self.layer.clone().unwrap_or_default()
+    }
+}
+
+impl Keyframe {
+    fn offsets(&self) -> Vec<f32> {
+        self.offsets.clone()
+    }
+
     fn properties(&self) -> Vec<Property> {
         self.properties.clone()
     }
 }
 
+impl Keyframes {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn stops(&self) -> Vec<Keyframe> {
+        self.stops.clone()
+    }
+}
+
+impl FontFace {
+    fn family(&self) -> String {
+        self.family.clone()
+    }
+
+    fn sources(&self) -> Vec<ffi::FontFaceSource> {
+        self.sources.iter().map(|source| ffi::FontFaceSource {
+            url: source.url.clone(),
+            format: source.format.clone().unwrap_or_default(),
+        }).collect()
+    }
+
+    fn unicode_ranges(&self) -> Vec<ffi::UnicodeRange> {
+        self.unicode_ranges.iter().map(|(start, end)| ffi::UnicodeRange { start: *start, end: *end }).collect()
+    }
+}
+
+impl BackgroundLayer {
+    fn image(&self) -> String {
+        self.image.clone().unwrap_or_default()
+    }
+
+    fn repeat(&self) -> Vec<String> {
+        self.repeat.clone()
+    }
+
+    fn position(&self) -> Vec<Value> {
+        self.position.clone()
+    }
+
+    fn size(&self) -> Vec<Value> {
+        self.size.clone()
+    }
+
+    fn attachment(&self) -> String {
+        self.attachment.clone().unwrap_or_default()
+    }
+
+    fn origin(&self) -> String {
+        self.origin.clone().unwrap_or_default()
+    }
+
+    fn clip(&self) -> String {
+        self.clip.clone().unwrap_or_default()
+    }
+
+    fn color(&self) -> Box<Color> {
+        Box::new(self.color.clone().unwrap_or_default())
+    }
+}
+
+fn parse_background(value: &str) -> Result<Vec<BackgroundLayer>, ParseError> {
+    crate::background::parse_background(value)
+}
+
+impl GridTrack {
+    fn track_type(&self) -> ffi::GridTrackType {
+        self.clone().into()
+    }
+
+    fn breadth(&self) -> Result<ffi::Dimension, ffi::ValueConversionError> {
+        match self {
+            GridTrack::Breadth(dimension) | GridTrack::FitContent(dimension) => Ok(dimension.into()),
+            _ => Err(ffi::ValueConversionError{ message: String::from("Grid track has no breadth") }),
+        }
+    }
+
+    fn keyword(&self) -> Result<String, ffi::ValueConversionError> {
+        if let GridTrack::Keyword(keyword) = self {
+            Ok(keyword.clone())
+        } else {
+            Err(ffi::ValueConversionError{ message: String::from("Not a keyword grid track") })
+        }
+    }
+
+    fn minimum(&self) -> Result<Box<GridTrack>, ffi::ValueConversionError> {
+        if let GridTrack::MinMax { min, .. } = self {
+            Ok(min.clone())
+        } else {
+            Err(ffi::ValueConversionError{ message: String::from("Not a minmax() grid track") })
+        }
+    }
+
+    fn maximum(&self) -> Result<Box<GridTrack>, ffi::ValueConversionError> {
+        if let GridTrack::MinMax { max, .. } = self {
+            Ok(max.clone())
+        } else {
+            Err(ffi::ValueConversionError{ message: String::from("Not a minmax() grid track") })
+        }
+    }
+}
+
+fn parse_track_list(value: &str) -> Result<Vec<GridTrack>, ParseError> {
+    crate::grid::parse_track_list(value)
+}
+
 impl StyleSheet {
     fn rules(&self) -> Vec<StyleRule> {
         self.all_rules()
@@ -491,6 +1056,56 @@ impl StyleSheet {
         self.all_paths().iter().map(|path| path.to_string_lossy().to_string()).collect()
     }
 
+    fn layers(&self) -> Vec<String> {
+        self.layer_order()
+    }
+
+    fn keyframes(&self) -> Vec<Keyframes> {
+        self.all_keyframes()
+    }
+
+    fn font_faces(&self) -> Vec<FontFace> {
+        self.all_font_faces()
+    }
+
+    fn variables(&self) -> Vec<Property> {
+        self.resolved_variables().0.into_iter().map(|(name, values)| {
+            let definition = crate::property::property_definition(&name).unwrap_or_else(|| std::sync::Arc::new(PropertyDefinition {
+                name: name.clone(),
+                syntax: crate::details::property::syntax::ParsedPropertySyntax::Universal,
+                inherit: false,
+                initial: values.clone(),
+                shorthand: None,
+            }));
+            Property {
+                name,
+                definition,
+                values,
+                important: false,
+                flag: None,
+                comments: Vec::new(),
+            }
+        }).collect()
+    }
+
+    fn variable_errors(&self) -> Vec<ffi::StyleSheetError> {
+        self.resolved_variables().1.iter().map(ffi::StyleSheetError::from_parse_error).collect()
+    }
+
+    fn parse_stats(&self) -> Vec<ffi::ParseStats> {
+        self.all_parse_stats().iter().map(|stats| ffi::ParseStats {
+            path: stats.path.to_string_lossy().to_string(),
+            rules: stats.rules,
+            errors: stats.errors,
+            bytes: stats.bytes,
+            duration_microseconds: stats.duration.as_micros() as u64,
+        }).collect()
+    }
+
+    fn set_record_stats(&mut self, enabled: bool) {
+        self.record_stats = enabled;
+    }
+
     fn import_file(&mut self, path: &str) -> Result<(), ParseError> {
         self.import(PathBuf::from(path))
     }
@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+/// A single entry of the `src` descriptor of a `@font-face` rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontFaceSource {
+    pub url: String,
+    /// The hint given by `format()`, if any.
+    pub format: Option<String>,
+}
+
+/// The descriptors of a `@font-face` rule.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FontFace {
+    pub family: String,
+    /// The sources of this font, in order of preference.
+    pub sources: Vec<FontFaceSource>,
+    /// The ranges of code points this font supports, as inclusive start and end.
+    ///
+    /// Empty if the rule has no `unicode-range` descriptor.
+    pub unicode_ranges: Vec<(u32, u32)>,
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+use crate::{
+    details::{parse_error, parse_error_from_cssparser_error},
+    parseerror::{ParseError, ParseErrorKind},
+    value::{Dimension, Unit},
+};
+
+const TRACK_KEYWORDS: &[&str] = &["auto", "min-content", "max-content"];
+
+/// The size of a single track of a grid, as used by `grid-template-columns`
+/// and `grid-template-rows`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridTrack {
+    /// A length, a percentage or a flexible length in `fr`.
+    Breadth(Dimension),
+    /// One of `auto`, `min-content` or `max-content`.
+    Keyword(String),
+    /// `minmax(min, max)`. The minimum cannot be a flexible length.
+    MinMax { min: Box<GridTrack>, max: Box<GridTrack> },
+    /// `fit-content(limit)`, with a length or percentage as limit.
+    FitContent(Dimension),
+}
+
+/// Parse a grid track list, like `1fr 2fr minmax(100px, 1fr)`.
+///
+/// `repeat()` is not supported yet and is reported as an error.
+pub fn parse_track_list(input: &str) -> Result<Vec<GridTrack>, ParseError> {
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+
+    let result = parser.parse_entirely(|parser| {
+        let mut tracks = Vec::new();
+        while !parser.is_exhausted() {
+            tracks.push(parse_track(parser)?);
+        }
+
+        if tracks.is_empty() {
+            return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Expected at least one grid track"));
+        }
+
+        Ok(tracks)
+    });
+
+    result.map_err(|error| parse_error_from_cssparser_error(&error, String::new()))
+}
+
+fn parse_track<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Result<GridTrack, cssparser::ParseError<'i, ParseError>> {
+    if let Ok(name) = parser.try_parse(|parser| parser.expect_function().cloned()) {
+        return match name.to_ascii_lowercase().as_str() {
+            "minmax" => parser.parse_nested_block(|parser| {
+                let min = parse_breadth(parser)?;
+                if let GridTrack::Breadth(dimension) = &min {
+                    if dimension.is_flex() {
+                        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("The minimum of minmax() cannot be a flexible length"));
+                    }
+                }
+                parser.expect_comma()?;
+                let max = parse_breadth(parser)?;
+                Ok(GridTrack::MinMax { min: Box::new(min), max: Box::new(max) })
+            }),
+            "fit-content" => parser.parse_nested_block(|parser| {
+                match parse_breadth(parser)? {
+                    GridTrack::Breadth(dimension) if !dimension.is_flex() => Ok(GridTrack::FitContent(dimension)),
+                    _ => parse_error(parser, ParseErrorKind::InvalidPropertyValue, String::from("Expected a length or percentage for fit-content()")),
+                }
+            }),
+            "repeat" => parse_error(parser, ParseErrorKind::Unimplemented, String::from("repeat() in grid track lists is not supported yet")),
+            _ => parse_error(parser, ParseErrorKind::UnknownFunction, format!("Unknown grid track function {:?}", name.as_ref())),
+        };
+    }
+
+    parse_breadth(parser)
+}
+
+// Parse a single length, percentage, flexible length or keyword.
+fn parse_breadth<'i, 't>(parser: &mut cssparser::Parser<'i, 't>) -> Result<GridTrack, cssparser::ParseError<'i, ParseError>> {
+    let token = parser.next()?.clone();
+    let dimension = match &token {
+        cssparser::Token::Dimension { value, unit, .. } => Dimension { value: *value, unit: Unit::parse(unit) },
+        cssparser::Token::Percentage { unit_value, .. } => Dimension { value: *unit_value, unit: Unit::Percent },
+        cssparser::Token::Number { value, .. } if *value == 0.0 => Dimension::px(0.0),
+        cssparser::Token::Ident(ident) if TRACK_KEYWORDS.contains(&ident.to_ascii_lowercase().as_str()) => {
+            return Ok(GridTrack::Keyword(ident.to_ascii_lowercase()));
+        }
+        _ => return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Unexpected token {:?} in grid track list", token)),
+    };
+
+    if !(dimension.is_length() || dimension.is_percent() || dimension.is_flex()) || dimension.value < 0.0 {
+        return parse_error(parser, ParseErrorKind::InvalidPropertyValue, format!("Invalid grid track size {}", dimension.to_css()));
+    }
+
+    Ok(GridTrack::Breadth(dimension))
+}
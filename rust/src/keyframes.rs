@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+use crate::property::Property;
+
+/// A single stop of a `@keyframes` rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframe {
+    /// The offsets this stop applies to, between 0.0 and 1.0.
+    ///
+    /// `from` is 0.0 and `to` is 1.0.
+    pub offsets: Vec<f32>,
+    pub properties: Vec<Property>,
+}
+
+/// The contents of a `@keyframes` rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframes {
+    pub name: String,
+    /// The stops of this animation, in source order.
+    pub stops: Vec<Keyframe>,
+}
+
+impl Keyframes {
+    /// Returns the properties that apply at `offset`, in source order.
+    pub fn properties_at(&self, offset: f32) -> Vec<Property> {
+        self.stops
+            .iter()
+            .filter(|stop| stop.offsets.contains(&offset))
+            .flat_map(|stop| stop.properties.iter().cloned())
+            .collect()
+    }
+}
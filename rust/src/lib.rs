@@ -4,6 +4,11 @@
 mod details;
 
 pub mod value;
+pub mod media;
+pub mod keyframes;
+pub mod fontface;
+pub mod background;
+pub mod grid;
 pub mod selector;
 pub mod parseerror;
 pub mod property;
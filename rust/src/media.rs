@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+/// The properties of the output device that media queries are evaluated against.
+///
+/// `color_scheme` is the preferred color scheme, like `light` or `dark`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaContext {
+    pub media_type: String,
+    pub width: f32,
+    pub height: f32,
+    pub device_pixel_ratio: f32,
+    pub color_scheme: String,
+}
+
+impl Default for MediaContext {
+    fn default() -> Self {
+        MediaContext {
+            media_type: String::from("screen"),
+            width: 0.0,
+            height: 0.0,
+            device_pixel_ratio: 1.0,
+            color_scheme: String::from("light"),
+        }
+    }
+}
+
+/// How the value of a media feature is compared against the context.
+///
+/// These correspond to the `min-` and `max-` prefixes of media features.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaComparison {
+    Exact,
+    Minimum,
+    Maximum,
+}
+
+impl MediaComparison {
+    fn compare(&self, actual: f32, expected: f32) -> bool {
+        match self {
+            MediaComparison::Exact => actual == expected,
+            MediaComparison::Minimum => actual >= expected,
+            MediaComparison::Maximum => actual <= expected,
+        }
+    }
+}
+
+/// A single media feature.
+///
+/// Widths and heights are stored in pixels, resolutions in dots per pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaFeature {
+    Width { comparison: MediaComparison, value: f32 },
+    Height { comparison: MediaComparison, value: f32 },
+    Resolution { comparison: MediaComparison, value: f32 },
+    PrefersColorScheme(String),
+    Unknown(String),
+}
+
+impl MediaFeature {
+    pub fn evaluate(&self, context: &MediaContext) -> bool {
+        match self {
+            MediaFeature::Width { comparison, value } => comparison.compare(context.width, *value),
+            MediaFeature::Height { comparison, value } => comparison.compare(context.height, *value),
+            MediaFeature::Resolution { comparison, value } => comparison.compare(context.device_pixel_ratio, *value),
+            MediaFeature::PrefersColorScheme(scheme) => scheme.eq_ignore_ascii_case(&context.color_scheme),
+            MediaFeature::Unknown(_) => false,
+        }
+    }
+}
+
+/// The condition of a media query.
+///
+/// A comma-separated list of queries is represented as `Or`, the `only`
+/// keyword has no effect and is not represented.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaCondition {
+    Feature(MediaFeature),
+    /// A media type like `screen`, `all` matches any media type.
+    MediaType(String),
+    Not(Box<MediaCondition>),
+    And(Vec<MediaCondition>),
+    Or(Vec<MediaCondition>),
+}
+
+impl MediaCondition {
+    pub fn evaluate(&self, context: &MediaContext) -> bool {
+        match self {
+            MediaCondition::Feature(feature) => feature.evaluate(context),
+            MediaCondition::MediaType(media_type) => media_type == "all" || media_type.eq_ignore_ascii_case(&context.media_type),
+            MediaCondition::Not(condition) => !condition.evaluate(context),
+            MediaCondition::And(conditions) => conditions.iter().all(|condition| condition.evaluate(context)),
+            MediaCondition::Or(conditions) => conditions.iter().any(|condition| condition.evaluate(context)),
+        }
+    }
+}
+
+/// A media query, as used by `@media` rules.
+///
+/// A query without condition always matches.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MediaQuery {
+    pub condition: Option<MediaCondition>,
+}
+
+impl MediaQuery {
+    pub const fn empty() -> MediaQuery {
+        MediaQuery { condition: None }
+    }
+
+    pub fn evaluate(&self, context: &MediaContext) -> bool {
+        match &self.condition {
+            Some(condition) => condition.evaluate(context),
+            None => true,
+        }
+    }
+
+    /// Returns a query that matches when both this query and `other` match.
+    pub fn and(&self, other: &MediaQuery) -> MediaQuery {
+        let condition = match (&self.condition, &other.condition) {
+            (Some(first), Some(second)) => Some(MediaCondition::And(vec![first.clone(), second.clone()])),
+            (first, second) => first.clone().or_else(|| second.clone()),
+        };
+        MediaQuery { condition }
+    }
+}
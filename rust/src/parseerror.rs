@@ -25,6 +25,7 @@ pub enum ParseErrorKind {
 #[derive(Debug, Clone, PartialEq)]
 pub struct SourceLocation {
     pub file: String,
+    /// The 1-based line in the style sheet, or 0 if the location is unknown.
     pub line: u32,
     pub column: u32,
 }
@@ -46,6 +47,41 @@ pub struct ParseError {
     pub location: SourceLocation,
 }
 
+impl ParseError {
+    /// Serialize this error as a JSON object with the fields `kind`, `message`,
+    /// `file`, `line` and `column`.
+    ///
+    /// `kind` is the name of the `ParseErrorKind` variant.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"kind\":{},\"message\":{},\"file\":{},\"line\":{},\"column\":{}}}",
+            json_string(&format!("{:?}", self.kind)),
+            json_string(&self.message),
+            json_string(&self.location.file),
+            self.location.line,
+            self.location.column,
+        )
+    }
+}
+
+fn json_string(input: &str) -> String {
+    let mut result = String::with_capacity(input.len() + 2);
+    result.push('"');
+    for character in input.chars() {
+        match character {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            character if (character as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", character as u32)),
+            character => result.push(character),
+        }
+    }
+    result.push('"');
+    result
+}
+
 impl std::error::Error for ParseError {
 }
 
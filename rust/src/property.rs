@@ -1,20 +1,53 @@
 // SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
 // SPDX-FileCopyrightText: 2025 Arjen Hiemstra <ahiemstra@heimr.nl>
 
-use std::sync::{Arc, RwLock, OnceLock};
+use std::sync::{Arc, Once, RwLock, OnceLock};
 
 use crate::{
-    details::property::syntax::{parse_syntax, ParsedPropertySyntax},
+    details::parse_error_from_cssparser_error,
+    details::property::syntax::{parse_syntax, ParsedPropertySyntax, SyntaxComponent},
+    details::property::value::parse_values,
     parseerror::{ParseError, SourceLocation},
-    value::Value
+    value::{CssWideKeyword, Value, ValueData},
 };
 
+pub use crate::details::property::syntax::DataType;
+
+/// How the values of a shorthand property are distributed over its longhands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShorthandDistribution {
+    /// Four longhands in clockwise order starting at the top. One value sets
+    /// all sides, two set the vertical and horizontal sides, three set the top,
+    /// horizontal and bottom sides and four set each side.
+    Sides,
+}
+
+impl ShorthandDistribution {
+    /// For each longhand, the index of the value it receives, or None if
+    /// `count` values are not valid for this distribution.
+    fn value_indices(&self, count: usize) -> Option<&'static [usize]> {
+        const SIDES: [[usize; 4]; 4] = [[0, 0, 0, 0], [0, 1, 0, 1], [0, 1, 2, 1], [0, 1, 2, 3]];
+        match self {
+            Self::Sides => SIDES.get(count.checked_sub(1)?).map(|indices| indices.as_slice()),
+        }
+    }
+}
+
+/// The longhands a shorthand property expands to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shorthand {
+    pub longhands: Vec<String>,
+    pub distribution: ShorthandDistribution,
+}
+
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct PropertyDefinition {
     pub name: String,
     pub syntax: ParsedPropertySyntax,
     pub inherit: bool,
     pub initial: Vec<Value>,
+    /// The longhands this property sets, if it is a shorthand.
+    pub shorthand: Option<Shorthand>,
 }
 
 fn property_definitions() -> &'static RwLock<Vec<Arc<PropertyDefinition>>> {
@@ -33,6 +66,16 @@ pub fn property_definition(name: &str) -> Option<Arc<PropertyDefinition>> {
     None{}
 }
 
+/// Returns the names of all registered properties, sorted by name.
+pub fn registered_property_names() -> Vec<String> {
+    let mut names: Vec<String> = match property_definitions().read() {
+        Ok(definitions) => definitions.iter().map(|definition| definition.name.clone()).collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+    names
+}
+
 pub fn add_property_definition(definition: &Arc<PropertyDefinition>) -> bool {
     let defs = property_definitions().write();
     if let Ok(mut definitions) = defs {
@@ -46,6 +89,110 @@ pub fn add_property_definition(definition: &Arc<PropertyDefinition>) -> bool {
     true
 }
 
+/// Register `definition`, replacing an existing definition with the same name.
+///
+/// Returns true if an existing definition was replaced.
+pub fn replace_property_definition(definition: &Arc<PropertyDefinition>) -> bool {
+    let Ok(mut definitions) = property_definitions().write() else {
+        return false;
+    };
+
+    if let Some(existing) = definitions.iter_mut().find(|existing| existing.name == definition.name) {
+        *existing = definition.clone();
+        true
+    } else {
+        definitions.push(definition.clone());
+        false
+    }
+}
+
+/// Parse `input` as the value of a property defined by `definition`.
+///
+/// The entire input must match the syntax of the definition. Error locations
+/// are relative to `input`, starting at line 1.
+pub fn parse_property_value(definition: &PropertyDefinition, input: &str) -> Result<Vec<Value>, ParseError> {
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+
+    parser.parse_entirely(|parser| parse_values(&definition.syntax, parser))
+        .map_err(|error| {
+            // cssparser counts lines from 0, while a line of 0 means the
+            // location is unknown.
+            let mut error = parse_error_from_cssparser_error(&error, String::new());
+            error.location.line += 1;
+            error
+        })
+}
+
+/// The properties that are registered by default.
+///
+/// Each entry is the name, syntax, whether the property inherits and the
+/// initial value, matching the fields of an `@property` rule.
+const BUILTIN_PROPERTIES: &[(&str, &str, bool, &str)] = &[
+    ("width", "<length>", false, "0px"),
+    ("height", "<length>", false, "0px"),
+    ("color", "<color>", true, "#ffffff"),
+    ("padding", "<length>+", false, "0px"),
+    ("padding-top", "<length>", false, "0px"),
+    ("background-image", "<url> | none", false, "none"),
+    ("background", "*", false, "none"),
+    ("padding-right", "<length>", false, "0px"),
+    ("padding-bottom", "<length>", false, "0px"),
+    ("padding-left", "<length>", false, "0px"),
+    ("margin", "<length>+", false, "0px"),
+    ("margin-top", "<length>", false, "0px"),
+    ("margin-right", "<length>", false, "0px"),
+    ("margin-bottom", "<length>", false, "0px"),
+    ("margin-left", "<length>", false, "0px"),
+    ("inset", "<length>+", false, "0px"),
+    ("top", "<length>", false, "0px"),
+    ("right", "<length>", false, "0px"),
+    ("bottom", "<length>", false, "0px"),
+    ("left", "<length>", false, "0px"),
+];
+
+/// The shorthands among the properties that are registered by default, with
+/// their longhands in the order expected by the distribution.
+const BUILTIN_SHORTHANDS: &[(&str, [&str; 4], ShorthandDistribution)] = &[
+    ("padding", ["padding-top", "padding-right", "padding-bottom", "padding-left"], ShorthandDistribution::Sides),
+    ("margin", ["margin-top", "margin-right", "margin-bottom", "margin-left"], ShorthandDistribution::Sides),
+    ("inset", ["top", "right", "bottom", "left"], ShorthandDistribution::Sides),
+];
+
+/// Returns the definitions of the properties that are registered by default.
+pub fn builtin_property_definitions() -> Vec<PropertyDefinition> {
+    BUILTIN_PROPERTIES.iter().map(|(name, syntax, inherit, initial)| {
+        let mut definition = PropertyDefinition::from_name_syntax(name, syntax, "builtin", 0, 0).unwrap();
+        definition.inherit = *inherit;
+
+        let mut parser_input = cssparser::ParserInput::new(initial);
+        let mut parser = cssparser::Parser::new(&mut parser_input);
+        definition.initial = parse_values(&definition.syntax, &mut parser).unwrap();
+
+        definition.shorthand = BUILTIN_SHORTHANDS.iter()
+            .find(|(shorthand, _, _)| shorthand == name)
+            .map(|(_, longhands, distribution)| Shorthand {
+                longhands: longhands.iter().map(|longhand| longhand.to_string()).collect(),
+                distribution: *distribution,
+            });
+
+        definition
+    }).collect()
+}
+
+/// Register the built-in property definitions.
+///
+/// This only registers the definitions once, and does not replace definitions
+/// that were already registered with the same name.
+pub fn register_builtin_properties() {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        for definition in builtin_property_definitions() {
+            add_property_definition(&Arc::new(definition));
+        }
+    });
+}
+
 impl PropertyDefinition {
     pub fn empty() -> PropertyDefinition {
         PropertyDefinition {
@@ -53,6 +200,7 @@ impl PropertyDefinition {
             syntax: ParsedPropertySyntax::Empty,
             inherit: false,
             initial: Vec::new(),
+            shorthand: None,
         }
     }
 
@@ -65,6 +213,7 @@ impl PropertyDefinition {
                     syntax: parsed_syntax,
                     inherit: false,
                     initial: Vec::new(),
+                    shorthand: None,
                 }
             )
         } else {
@@ -77,6 +226,66 @@ impl PropertyDefinition {
         pd.initial = Vec::from(initial);
         Ok(pd)
     }
+
+    /// The syntax of this property, as it would be written in `@property`.
+    pub fn syntax_string(&self) -> String {
+        self.syntax.to_syntax_string()
+    }
+
+    /// Serialize this definition as an `@property` rule.
+    ///
+    /// Initial values are separated by commas if the syntax contains a
+    /// comma-separated list, by spaces otherwise.
+    pub fn to_css(&self) -> String {
+        let mut result = String::from("@property ");
+        let _ = cssparser::serialize_identifier(&self.name, &mut result);
+        result.push_str(" { syntax: ");
+        let _ = cssparser::serialize_string(&self.syntax_string(), &mut result);
+        result.push_str(&format!("; inherits: {};", self.inherit));
+
+        if !self.initial.is_empty() {
+            let comma_separated = self.syntax.components().iter().any(|component| matches!(component, SyntaxComponent::CommaSeparatedList(_) | SyntaxComponent::Comma));
+            let separator = if comma_separated { ", " } else { " " };
+            let values: Vec<_> = self.initial.iter().map(Value::to_css).collect();
+            result.push_str(&format!(" initial-value: {};", values.join(separator)));
+        }
+
+        result.push_str(" }");
+        result
+    }
+
+    /// The data types accepted by this property.
+    pub fn accepted_data_types(&self) -> Vec<DataType> {
+        self.syntax.data_types()
+    }
+
+    /// The keywords accepted by this property.
+    pub fn accepted_keywords(&self) -> Vec<String> {
+        self.syntax.keywords()
+    }
+
+    /// The index of the keyword `value` in `accepted_keywords()`.
+    ///
+    /// Returns None if `value` is not a keyword accepted by this property.
+    pub fn keyword_index(&self, value: &Value) -> Option<usize> {
+        let keyword = value.as_keyword()?;
+        self.accepted_keywords().iter().position(|accepted| accepted == keyword)
+    }
+
+    /// The data types and keywords accepted by this property, formatted as in a syntax string.
+    pub fn accepted_types(&self) -> Vec<String> {
+        let data_types = self.accepted_data_types().into_iter().map(|data_type| format!("<{}>", data_type));
+        data_types.chain(self.accepted_keywords()).collect()
+    }
+}
+
+/// A custom property as declared in a style sheet, before `var()` references are resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomProperty {
+    pub name: String,
+    /// The value as written, without `!important`.
+    pub source: String,
+    pub location: SourceLocation,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -84,4 +293,97 @@ pub struct Property {
     pub name: String,
     pub definition: Arc<PropertyDefinition>,
     pub values: Vec<Value>,
+    /// Whether this property was declared with `!important`.
+    pub important: bool,
+    /// The identifier of the `!` flag this property was declared with, if any.
+    ///
+    /// This is `important` for `!important`, other flags like `!default` are
+    /// only accepted if `strict_bang_flags` is disabled.
+    pub flag: Option<String>,
+    /// Comments directly preceding this property, only retained if requested.
+    pub comments: Vec<String>,
+}
+
+impl Property {
+    /// The CSS-wide keyword this property is set to, if any.
+    pub fn css_wide_keyword(&self) -> Option<CssWideKeyword> {
+        match self.values.as_slice() {
+            [Value { data: ValueData::CssWideKeyword(keyword) }] => Some(*keyword),
+            _ => None,
+        }
+    }
+
+    /// Expand this property into its longhands if it is a shorthand.
+    ///
+    /// Returns None if this property is not a shorthand, if the number of
+    /// values does not fit the shorthand or if a longhand is not registered.
+    pub fn longhands(&self) -> Option<Vec<Property>> {
+        let shorthand = self.definition.shorthand.as_ref()?;
+        let indices = shorthand.distribution.value_indices(self.values.len())?;
+
+        shorthand.longhands.iter().zip(indices).map(|(name, index)| {
+            Some(Property {
+                name: name.clone(),
+                definition: property_definition(name)?,
+                values: vec![self.values[*index].clone()],
+                important: self.important,
+                flag: self.flag.clone(),
+                comments: Vec::new(),
+            })
+        }).collect()
+    }
+
+    /// The values of a single longhand of this shorthand, without expanding the others.
+    ///
+    /// Returns None if this property is not a shorthand, if `longhand_name`
+    /// is not one of its longhands or if the number of values does not fit.
+    pub fn longhand_value(&self, longhand_name: &str) -> Option<Vec<Value>> {
+        let shorthand = self.definition.shorthand.as_ref()?;
+        let position = shorthand.longhands.iter().position(|name| name == longhand_name)?;
+        let index = shorthand.distribution.value_indices(self.values.len())?.get(position)?;
+        Some(vec![self.values[*index].clone()])
+    }
+}
+
+/// Merge two lists of properties according to CSS cascade precedence.
+///
+/// Properties in `override_` replace properties with the same name in `base`,
+/// unless the property in `base` is important and the one in `override_` is not.
+pub fn merge_properties(base: &[Property], override_: &[Property]) -> Vec<Property> {
+    let mut result = Vec::from(base);
+
+    for property in override_ {
+        cascade_property(&mut result, property.clone());
+    }
+
+    result
+}
+
+/// Add `property` to `properties`, replacing an existing property with the same name.
+///
+/// An existing important property is only replaced by another important property.
+/// The `all` property resets every existing property to its keyword, except
+/// custom properties, `direction` and `unicode-bidi`, and is kept itself so it
+/// can be applied to properties that are not in the list.
+pub fn cascade_property(properties: &mut Vec<Property>, property: Property) {
+    if property.name == "all" {
+        if let Some(keyword) = property.css_wide_keyword() {
+            let affected = |existing: &&mut Property| {
+                !existing.name.starts_with("--") && existing.name != "direction" && existing.name != "unicode-bidi"
+            };
+            for existing in properties.iter_mut().filter(affected).filter(|existing| !existing.important || property.important) {
+                existing.values = vec![Value::from(keyword)];
+                existing.important = property.important;
+                existing.flag = property.flag.clone();
+            }
+        }
+    }
+
+    if let Some(existing) = properties.iter_mut().find(|existing| existing.name == property.name) {
+        if !existing.important || property.important {
+            *existing = property;
+        }
+    } else {
+        properties.push(property);
+    }
 }
@@ -26,15 +26,109 @@ pub enum SelectorKind {
     Attribute,
     RelativeParent,
     DocumentRoot,
+    Namespace,
+    /// A negation like `:not(.a, .b)`, which matches if none of its selectors match.
+    Negation,
+    /// A pseudo-element like `::before`, or a functional one like `::part(track)`.
+    PseudoElement,
     DescendantCombinator,
     ChildCombinator,
 }
 
+impl SelectorKind {
+    /// A descriptive name for this kind, like `"class"` or `"child-combinator"`.
+    ///
+    /// This is a name rather than the CSS prefix, as several kinds do not
+    /// have one. Use `Selector::to_css()` to get the CSS representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SelectorKind::Unknown => "unknown",
+            SelectorKind::AnyElement => "any-element",
+            SelectorKind::Type => "type",
+            SelectorKind::Class => "class",
+            SelectorKind::Id => "id",
+            SelectorKind::PseudoClass => "pseudo-class",
+            SelectorKind::Attribute => "attribute",
+            SelectorKind::RelativeParent => "relative-parent",
+            SelectorKind::DocumentRoot => "document-root",
+            SelectorKind::Namespace => "namespace",
+            SelectorKind::Negation => "negation",
+            SelectorKind::PseudoElement => "pseudo-element",
+            SelectorKind::DescendantCombinator => "descendant-combinator",
+            SelectorKind::ChildCombinator => "child-combinator",
+        }
+    }
+}
+
+impl std::fmt::Display for SelectorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The specificity of a selector.
+///
+/// This follows the CSS definition of specificity, where the first field counts
+/// ID selectors, the second class, attribute and pseudo-class selectors and the
+/// third type selectors. Specificities compare lexicographically.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Specificity(pub u32, pub u32, pub u32);
+
+impl std::fmt::Display for Specificity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.0, self.1, self.2)
+    }
+}
+
+/// A description of an element that selectors can be matched against.
+///
+/// `parent` is used for matching combinators, an element without parent is
+/// considered to be the document root. `namespace` is the namespace URL of the
+/// element, an empty string means the element is not in any namespace.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ElementContext {
+    pub type_name: String,
+    pub namespace: String,
+    pub id: String,
+    pub classes: Vec<String>,
+    pub pseudo_classes: Vec<String>,
+    pub attributes: Vec<(String, String)>,
+    pub parent: Option<Box<ElementContext>>,
+}
+
+impl ElementContext {
+    pub fn new(type_name: &str) -> ElementContext {
+        ElementContext {
+            type_name: type_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(attribute, _)| attribute == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the value of attribute `name` of this element or the closest parent that has it.
+    pub fn inherited_attribute(&self, name: &str) -> Option<&str> {
+        self.attribute(name).or_else(|| self.parent.as_ref().and_then(|parent| parent.inherited_attribute(name)))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum SelectorValue {
     Empty,
     Value(Value),
     Attribute{name: String, operator: AttributeOperator, value: Value},
+    /// The namespace of a type selector.
+    ///
+    /// `prefix` is "*" for any namespace and empty for the default namespace or
+    /// no namespace, `url` is empty when the selector matches elements without
+    /// namespace.
+    Namespace{prefix: String, url: String},
+    /// A functional pseudo-class, like `:lang(en)`, with its argument.
+    Function{name: Value, argument: String},
+    /// The selectors of a negation.
+    Selectors(Vec<Selector>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -51,11 +145,156 @@ impl SelectorPart {
     pub fn new_with_value(kind: SelectorKind, value: Value) -> SelectorPart {
         SelectorPart { kind, value: SelectorValue::Value(value) }
     }
+
+    pub fn new_with_argument(kind: SelectorKind, name: Value, argument: &str) -> SelectorPart {
+        SelectorPart { kind, value: SelectorValue::Function { name, argument: argument.to_string() } }
+    }
+
+    pub fn new_with_namespace(prefix: &str, url: &str) -> SelectorPart {
+        SelectorPart { kind: SelectorKind::Namespace, value: SelectorValue::Namespace { prefix: prefix.to_string(), url: url.to_string() } }
+    }
+
+    pub fn new_with_selectors(kind: SelectorKind, selectors: &[Selector]) -> SelectorPart {
+        SelectorPart { kind, value: SelectorValue::Selectors(Vec::from(selectors)) }
+    }
+
+    pub fn is_combinator(&self) -> bool {
+        matches!(self.kind, SelectorKind::DescendantCombinator | SelectorKind::ChildCombinator)
+    }
+
+    fn to_css(&self, dest: &mut String) {
+        match (&self.kind, &self.value) {
+            (SelectorKind::AnyElement, _) => dest.push('*'),
+            (SelectorKind::RelativeParent, _) => dest.push('&'),
+            (SelectorKind::DocumentRoot, _) => dest.push_str(":root"),
+            (SelectorKind::DescendantCombinator, _) => dest.push(' '),
+            (SelectorKind::ChildCombinator, _) => dest.push_str(" > "),
+            (SelectorKind::Namespace, SelectorValue::Namespace { prefix, url }) => {
+                match (prefix.as_str(), url.is_empty()) {
+                    // The default namespace is implied and needs no prefix.
+                    ("", false) => (),
+                    ("", true) => dest.push('|'),
+                    ("*", _) => dest.push_str("*|"),
+                    (prefix, _) => {
+                        let _ = cssparser::serialize_identifier(prefix, dest);
+                        dest.push('|');
+                    }
+                }
+            }
+            (SelectorKind::Type, SelectorValue::Value(value)) => {
+                let _ = cssparser::serialize_identifier(&value.to_string(), dest);
+            }
+            (SelectorKind::Class, SelectorValue::Value(value)) => {
+                dest.push('.');
+                let _ = cssparser::serialize_identifier(&value.to_string(), dest);
+            }
+            (SelectorKind::Id, SelectorValue::Value(value)) => {
+                dest.push('#');
+                let _ = cssparser::serialize_identifier(&value.to_string(), dest);
+            }
+            (SelectorKind::PseudoClass, SelectorValue::Value(value)) => {
+                dest.push(':');
+                let _ = cssparser::serialize_identifier(&value.to_string(), dest);
+            }
+            (SelectorKind::PseudoClass, SelectorValue::Function { name, argument }) => {
+                dest.push(':');
+                let _ = cssparser::serialize_identifier(&name.to_string(), dest);
+                dest.push('(');
+                let _ = cssparser::serialize_identifier(argument, dest);
+                dest.push(')');
+            }
+            (SelectorKind::PseudoElement, SelectorValue::Value(value)) => {
+                dest.push_str("::");
+                let _ = cssparser::serialize_identifier(&value.to_string(), dest);
+            }
+            // The argument of a functional pseudo-element is stored as CSS.
+            (SelectorKind::PseudoElement, SelectorValue::Function { name, argument }) => {
+                dest.push_str("::");
+                let _ = cssparser::serialize_identifier(&name.to_string(), dest);
+                dest.push('(');
+                dest.push_str(argument);
+                dest.push(')');
+            }
+            (SelectorKind::Attribute, SelectorValue::Attribute { name, operator, value }) => {
+                dest.push('[');
+                let _ = cssparser::serialize_identifier(name, dest);
+                let operator = match operator {
+                    AttributeOperator::None | AttributeOperator::Exists => None,
+                    AttributeOperator::Equals => Some("="),
+                    AttributeOperator::Includes => Some("~="),
+                    AttributeOperator::Prefixed => Some("^="),
+                    AttributeOperator::Suffixed => Some("$="),
+                    AttributeOperator::Substring => Some("*="),
+                    AttributeOperator::DashMatch => Some("|="),
+                };
+                if let Some(operator) = operator {
+                    dest.push_str(operator);
+                    let _ = cssparser::serialize_string(&value.to_string(), dest);
+                }
+                dest.push(']');
+            }
+            (SelectorKind::Negation, SelectorValue::Selectors(selectors)) => {
+                dest.push_str(":not(");
+                for (index, selector) in selectors.iter().enumerate() {
+                    if index > 0 {
+                        dest.push_str(", ");
+                    }
+                    dest.push_str(&selector.to_css());
+                }
+                dest.push(')');
+            }
+            _ => (),
+        }
+    }
+
+    fn matches(&self, element: &ElementContext) -> bool {
+        match (&self.kind, &self.value) {
+            (SelectorKind::AnyElement, _) => true,
+            (SelectorKind::RelativeParent, _) => true,
+            (SelectorKind::DocumentRoot, _) => element.parent.is_none(),
+            (SelectorKind::Namespace, SelectorValue::Namespace { prefix, url }) => prefix == "*" || element.namespace == *url,
+            (SelectorKind::Type, SelectorValue::Value(value)) => element.type_name == value.to_string(),
+            (SelectorKind::Id, SelectorValue::Value(value)) => element.id == value.to_string(),
+            (SelectorKind::Class, SelectorValue::Value(value)) => element.classes.contains(&value.to_string()),
+            (SelectorKind::PseudoClass, SelectorValue::Value(value)) => element.pseudo_classes.contains(&value.to_string()),
+            (SelectorKind::PseudoClass, SelectorValue::Function { name, argument }) => {
+                match name.to_string().as_str() {
+                    "lang" => element.inherited_attribute("lang").is_some_and(|lang| AttributeOperator::DashMatch.matches(&lang.to_ascii_lowercase(), &argument.to_ascii_lowercase())),
+                    "dir" => element.inherited_attribute("dir").unwrap_or("ltr") == argument,
+                    _ => false,
+                }
+            }
+            (SelectorKind::Attribute, SelectorValue::Attribute { name, operator, value }) => {
+                if let Some(attribute) = element.attribute(name) {
+                    operator.matches(attribute, &value.to_string())
+                } else {
+                    false
+                }
+            }
+            (SelectorKind::Negation, SelectorValue::Selectors(selectors)) => !selectors.iter().any(|selector| selector.matches(element)),
+            _ => false,
+        }
+    }
+}
+
+impl AttributeOperator {
+    fn matches(&self, attribute: &str, expected: &str) -> bool {
+        match self {
+            AttributeOperator::None => false,
+            AttributeOperator::Exists => true,
+            AttributeOperator::Equals => attribute == expected,
+            AttributeOperator::Includes => attribute.split_whitespace().any(|word| word == expected),
+            AttributeOperator::Prefixed => !expected.is_empty() && attribute.starts_with(expected),
+            AttributeOperator::Suffixed => !expected.is_empty() && attribute.ends_with(expected),
+            AttributeOperator::Substring => !expected.is_empty() && attribute.contains(expected),
+            AttributeOperator::DashMatch => attribute == expected || attribute.starts_with(&format!("{}-", expected)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Selector {
-    pub parts: Vec<SelectorPart>,
+    pub(crate) parts: Vec<SelectorPart>,
 }
 
 impl Selector {
@@ -70,7 +309,7 @@ impl Selector {
     }
 
     pub fn combine(first: &Selector, second: &Selector) -> Selector {
-        let mut parts = first.parts.clone();
+        let mut parts: Vec<SelectorPart> = first.iter_parts().cloned().collect();
 
         if !second.parts.is_empty() {
             // Find all the indices of RelativeParent so we can replace them
@@ -83,7 +322,7 @@ impl Selector {
             }
 
             if relative_indices.is_empty() {
-                parts.extend(second.parts.clone())
+                parts.extend(second.iter_parts().cloned())
             } else {
                 // Since we modify the list and introduce potentially multiple
                 // new items, our indices may shift. We need to keep track of
@@ -92,7 +331,7 @@ impl Selector {
                 for index in relative_indices {
                     let i = index + offset;
                     parts.remove(i);
-                    parts.splice(i..i, second.parts.clone());
+                    parts.splice(i..i, second.iter_parts().cloned());
                     offset = offset + second.parts.len() - 1;
                 }
             }
@@ -101,6 +340,73 @@ impl Selector {
         Selector { parts }
     }
 
+    /// Whether this selector refers to its parent, either explicitly with `&`
+    /// or implicitly because it was parsed in a nested block.
+    pub fn has_relative_parent(&self) -> bool {
+        self.parts.iter().any(|part| part.kind == SelectorKind::RelativeParent)
+    }
+
+    /// The number of compound selectors in this selector, which are separated by combinators.
+    pub fn complexity(&self) -> usize {
+        if self.parts.is_empty() {
+            return 0;
+        }
+
+        self.parts.iter().filter(|part| part.is_combinator()).count() + 1
+    }
+
+    pub fn specificity(&self) -> Specificity {
+        let mut result = Specificity::default();
+
+        for part in &self.parts {
+            // A negation counts as its most specific selector.
+            if let SelectorValue::Selectors(selectors) = &part.value {
+                let most_specific = selectors.iter().map(Selector::specificity).max().unwrap_or_default();
+                result.0 += most_specific.0;
+                result.1 += most_specific.1;
+                result.2 += most_specific.2;
+                continue;
+            }
+
+            match part.kind {
+                SelectorKind::Id => result.0 += 1,
+                SelectorKind::Class
+                | SelectorKind::PseudoClass
+                | SelectorKind::Attribute
+                | SelectorKind::DocumentRoot => result.1 += 1,
+                SelectorKind::Type | SelectorKind::PseudoElement => result.2 += 1,
+                _ => (),
+            }
+        }
+
+        result
+    }
+
+    pub fn matches(&self, element: &ElementContext) -> bool {
+        // Split the parts into compound selectors, with the combinator that
+        // precedes each compound. Matching then happens from right to left,
+        // starting at the element itself and walking up through its parents.
+        let mut compounds: Vec<(Option<SelectorKind>, Vec<&SelectorPart>)> = vec![(None, Vec::new())];
+        for part in self.iter_parts() {
+            if part.is_combinator() {
+                compounds.push((Some(part.kind), Vec::new()));
+            } else {
+                compounds.last_mut().unwrap().1.push(part);
+            }
+        }
+
+        match_compounds(&compounds, element)
+    }
+
+    /// Returns an iterator over the parts of this selector, in parsing order.
+    pub fn iter_parts(&self) -> impl Iterator<Item = &SelectorPart> {
+        self.parts.iter()
+    }
+
+    pub fn push(&mut self, part: SelectorPart) {
+        self.parts.push(part)
+    }
+
     pub fn push_with_empty(&mut self, kind: SelectorKind) {
         self.parts.push(SelectorPart::new_with_empty(kind))
     }
@@ -108,4 +414,68 @@ impl Selector {
     pub fn push_with_value(&mut self, kind: SelectorKind, value: Value) {
         self.parts.push(SelectorPart::new_with_value(kind, value));
     }
+
+    /// Check that the parts of this selector form a well-formed sequence.
+    ///
+    /// A selector must not be empty, must not start or end with a combinator
+    /// and must not contain two combinators in a row.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.parts.is_empty() {
+            return Err(String::from("Selector is empty"));
+        }
+
+        if self.parts.first().is_some_and(SelectorPart::is_combinator) {
+            return Err(String::from("Selector starts with a combinator"));
+        }
+
+        if self.parts.last().is_some_and(SelectorPart::is_combinator) {
+            return Err(String::from("Selector ends with a combinator"));
+        }
+
+        if let Some(index) = self.parts.windows(2).position(|pair| pair[0].is_combinator() && pair[1].is_combinator()) {
+            return Err(format!("Adjacent combinators at position {}", index + 1));
+        }
+
+        Ok(())
+    }
+
+    /// Serialize this selector to CSS syntax that can be parsed again.
+    pub fn to_css(&self) -> String {
+        let mut result = String::new();
+        for part in &self.parts {
+            part.to_css(&mut result);
+        }
+        result
+    }
+}
+
+fn match_compounds(compounds: &[(Option<SelectorKind>, Vec<&SelectorPart>)], element: &ElementContext) -> bool {
+    let Some(((combinator, parts), remain)) = compounds.split_last() else {
+        return true;
+    };
+
+    if !parts.iter().all(|part| part.matches(element)) {
+        return false;
+    }
+
+    if remain.is_empty() {
+        return true;
+    }
+
+    match combinator {
+        Some(SelectorKind::ChildCombinator) => {
+            element.parent.as_ref().is_some_and(|parent| match_compounds(remain, parent))
+        }
+        Some(SelectorKind::DescendantCombinator) => {
+            let mut ancestor = element.parent.as_deref();
+            while let Some(current) = ancestor {
+                if match_compounds(remain, current) {
+                    return true;
+                }
+                ancestor = current.parent.as_deref();
+            }
+            false
+        }
+        _ => false,
+    }
 }
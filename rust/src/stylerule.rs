@@ -1,17 +1,27 @@
 // SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
 // SPDX-FileCopyrightText: 2025 Arjen Hiemstra <ahiemstra@heimr.nl>
 
+use std::sync::Arc;
+
+use crate::media::MediaQuery;
 use crate::property::Property;
 use crate::selector::Selector;
 use crate::value::ValueData;
 
-use crate::details::rulesparser::ParsedRule;
-use crate::stylesheet::StyleSheet;
+pub use crate::details::rulesparser::ParsedRule;
+use crate::stylesheet::{has_url_scheme, StyleSheet};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct StyleRule {
     pub selector: Selector,
-    pub properties: Vec<Property>,
+    /// Properties of this rule, shared between all rules created from the same block.
+    pub properties: Arc<Vec<Property>>,
+    /// The media query of the `@media` rule containing this rule, if any.
+    pub media: Option<MediaQuery>,
+    /// The name of the cascade layer containing this rule, if any.
+    pub layer: Option<String>,
+    /// Comments directly preceding this rule, only retained if requested.
+    pub comments: Vec<String>,
 }
 
 fn resolve_urls(properties: &Vec<Property>, style_sheet: &StyleSheet) -> Vec<Property> {
@@ -19,7 +29,11 @@ fn resolve_urls(properties: &Vec<Property>, style_sheet: &StyleSheet) -> Vec<Pro
 
     for property in &mut result {
         for value in &mut property.values {
+            // URLs with a scheme, like `data:` or `https:`, are kept as they are.
             if let ValueData::Url(url) = &mut value.data {
+                if has_url_scheme(url) {
+                    continue;
+                }
                 *url = style_sheet.path.parent().unwrap().join(url.clone()).to_string_lossy().to_string()
             }
         }
@@ -32,6 +46,12 @@ impl StyleRule {
     pub fn from_parsed_rule(parsed: &ParsedRule, style_sheet: &StyleSheet) -> Vec<StyleRule> {
         let mut result = Vec::new();
 
+        let properties = Arc::new(resolve_urls(&parsed.properties, style_sheet));
+        let nested_rules: Vec<StyleRule> = parsed.nested_rules
+            .iter()
+            .flat_map(|nested_rule| StyleRule::from_parsed_rule(nested_rule, style_sheet))
+            .collect();
+
         for selector in &parsed.selectors {
             if selector.parts.is_empty() && parsed.properties.is_empty() {
                 continue;
@@ -39,16 +59,20 @@ impl StyleRule {
 
             result.push(StyleRule {
                 selector: selector.clone(),
-                properties: resolve_urls(&parsed.properties, style_sheet),
+                properties: properties.clone(),
+                media: None,
+                layer: None,
+                comments: parsed.comments.clone(),
             });
 
-            for nested_rule in &parsed.nested_rules {
-                for nested_result in StyleRule::from_parsed_rule(nested_rule, style_sheet) {
-                    result.push(Self {
-                        selector: Selector::combine(&nested_result.selector, &selector),
-                        properties: nested_result.properties,
-                    });
-                }
+            for nested_result in &nested_rules {
+                result.push(Self {
+                    selector: Selector::combine(&nested_result.selector, selector),
+                    properties: nested_result.properties.clone(),
+                    media: None,
+                    layer: None,
+                    comments: nested_result.comments.clone(),
+                });
             }
         }
 
@@ -1,74 +1,372 @@
 // SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
 // SPDX-FileCopyrightText: 2025 Arjen Hiemstra <ahiemstra@heimr.nl>
 
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+use crate::details::comments::Comments;
 use crate::details::parse_error_from_cssparser_error;
 use crate::details::rulesparser::*;
+use crate::fontface::FontFace;
+use crate::keyframes::Keyframes;
+use crate::media::MediaQuery;
 use crate::parseerror::{ParseError, ParseErrorKind, SourceLocation};
 
-use crate::property::add_property_definition;
+use crate::details::property::function::resolve_custom_property;
+use crate::property::{cascade_property, merge_properties, register_builtin_properties, CustomProperty, Property};
+use crate::value::Value;
+use crate::selector::{ElementContext, Specificity};
 use crate::stylerule::*;
 
+/// Statistics about parsing a single file or string, see `StyleSheet::record_stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseStats {
+    pub path: PathBuf,
+    /// The number of rules that were parsed.
+    pub rules: usize,
+    /// The number of errors that were encountered.
+    pub errors: usize,
+    /// The size of the input in bytes.
+    pub bytes: usize,
+    /// The time spent parsing, including the time spent parsing imports.
+    pub duration: Duration,
+}
+
 #[derive(Debug)]
 pub struct StyleSheet {
     pub path: PathBuf,
-    pub rules: Vec<StyleRule>,
+    pub(crate) rules: Vec<StyleRule>,
+    pub(crate) parsed_rules: Vec<ParsedRule>,
     pub errors: Vec<ParseError>,
+    /// Warnings about parts of the input that were skipped.
+    pub warnings: Vec<ParseError>,
     pub imported_sheets: Vec<StyleSheet>,
+    /// Names of the cascade layers declared in this style sheet, in order of declaration.
+    ///
+    /// Nested layers use their full name, like `base.reset`.
+    pub layers: Vec<String>,
+    /// Animations defined using `@keyframes` in this style sheet.
+    pub keyframes: Vec<Keyframes>,
+    /// Fonts defined using `@font-face` in this style sheet.
+    pub font_faces: Vec<FontFace>,
+    /// The custom properties declared in this style sheet, in order of declaration.
+    pub variables: Vec<CustomProperty>,
+    /// Whether properties without a registered definition are an error.
+    ///
+    /// When false, these properties are kept and their values are parsed
+    /// using the universal syntax. Defaults to true.
+    pub strict_unknown_properties: bool,
+    /// Whether unsupported @-rules are an error.
+    ///
+    /// When false, these @-rules are skipped and a warning is recorded in
+    /// `warnings`. Defaults to true.
+    pub strict_unknown_at_rules: bool,
+    /// Whether dimensions with an unknown unit are an error.
+    ///
+    /// When false, these dimensions are kept with the unit as written in
+    /// `Unit::Other`. Defaults to true.
+    pub strict_units: bool,
+    /// Whether a trailing comma in a comma-separated value is an error.
+    ///
+    /// When false, a single trailing comma is ignored. Defaults to true.
+    pub strict_trailing_commas: bool,
+    /// Whether `!` flags other than `!important` are an error.
+    ///
+    /// When false, flags like `!default` are stored in `Property::flag` for
+    /// the consumer to interpret. Defaults to true.
+    pub strict_bang_flags: bool,
+    /// Whether comments preceding rules and properties should be retained.
+    ///
+    /// Defaults to false.
+    pub retain_comments: bool,
+    /// Whether statistics should be recorded in `stats` for each parse.
+    ///
+    /// Defaults to false.
+    pub record_stats: bool,
+    /// Statistics of each parse of this style sheet, only recorded if requested.
+    pub stats: Vec<ParseStats>,
+    /// How deep `@import` rules can be nested before they are an error.
+    ///
+    /// Defaults to 32.
+    pub max_import_depth: usize,
+    // How many imports deep this style sheet is, 0 for the style sheet that was parsed.
+    import_depth: usize,
 }
 
 impl StyleSheet {
     pub fn new(path: PathBuf) -> StyleSheet {
+        register_builtin_properties();
+
         StyleSheet {
             path,
             rules: Vec::new(),
+            parsed_rules: Vec::new(),
             errors: Vec::new(),
+            warnings: Vec::new(),
             imported_sheets: Vec::new(),
+            layers: Vec::new(),
+            keyframes: Vec::new(),
+            font_faces: Vec::new(),
+            variables: Vec::new(),
+            strict_unknown_properties: true,
+            strict_unknown_at_rules: true,
+            strict_units: true,
+            strict_trailing_commas: true,
+            strict_bang_flags: true,
+            retain_comments: false,
+            record_stats: false,
+            stats: Vec::new(),
+            max_import_depth: 32,
+            import_depth: 0,
         }
     }
 
+    /// Returns an iterator over the rules of this style sheet.
+    ///
+    /// This does not include rules from imported style sheets, use `all_rules()`
+    /// for that.
+    pub fn iter_rules(&self) -> impl Iterator<Item = &StyleRule> {
+        self.rules.iter()
+    }
+
+    /// Returns the number of rules in this style sheet, excluding imported sheets.
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
     pub fn all_rules(&self) -> Vec<StyleRule> {
         let mut rules: Vec<_> = self.imported_sheets.iter().map(|sheet| sheet.all_rules()).flatten().collect();
         rules.extend(self.rules.clone());
         rules
     }
 
+    /// Returns the rules of this style sheet and imported sheets as written,
+    /// with nested rules kept inside their parent rule.
+    pub fn parsed_tree(&self) -> Vec<ParsedRule> {
+        let mut rules: Vec<_> = self.imported_sheets.iter().flat_map(|sheet| sheet.parsed_tree()).collect();
+        rules.extend(self.parsed_rules.clone());
+        rules
+    }
+
     pub fn all_errors(&self) -> Vec<ParseError> {
         let mut errors: Vec<_> = self.imported_sheets.iter().map(|sheet| sheet.all_errors()).flatten().collect();
         errors.extend(self.errors.clone());
         errors
     }
 
+    /// Returns `all_errors()` serialized as a JSON array, see `ParseError::to_json()`.
+    pub fn errors_json(&self) -> String {
+        let errors: Vec<_> = self.all_errors().iter().map(ParseError::to_json).collect();
+        format!("[{}]", errors.join(","))
+    }
+
+    pub fn all_warnings(&self) -> Vec<ParseError> {
+        let mut warnings: Vec<_> = self.imported_sheets.iter().flat_map(|sheet| sheet.all_warnings()).collect();
+        warnings.extend(self.warnings.clone());
+        warnings
+    }
+
+    /// Returns the parse statistics of this style sheet and all imported sheets.
+    pub fn all_parse_stats(&self) -> Vec<ParseStats> {
+        let mut stats: Vec<_> = self.imported_sheets.iter().flat_map(|sheet| sheet.all_parse_stats()).collect();
+        stats.extend(self.stats.clone());
+        stats
+    }
+
+    pub fn all_keyframes(&self) -> Vec<Keyframes> {
+        let mut keyframes: Vec<_> = self.imported_sheets.iter().flat_map(|sheet| sheet.all_keyframes()).collect();
+        keyframes.extend(self.keyframes.clone());
+        keyframes
+    }
+
+    pub fn all_font_faces(&self) -> Vec<FontFace> {
+        let mut font_faces: Vec<_> = self.imported_sheets.iter().flat_map(|sheet| sheet.all_font_faces()).collect();
+        font_faces.extend(self.font_faces.clone());
+        font_faces
+    }
+
+    /// Returns the custom properties declared in this style sheet and imported
+    /// sheets, in order of their first declaration.
+    ///
+    /// A later declaration of a custom property replaces the value of an
+    /// earlier one, with imported sheets coming before this style sheet.
+    pub fn all_variables(&self) -> Vec<CustomProperty> {
+        let mut variables: Vec<CustomProperty> = Vec::new();
+        let imported = self.imported_sheets.iter().flat_map(|sheet| sheet.all_variables());
+        for variable in imported.chain(self.variables.iter().cloned()) {
+            match variables.iter_mut().find(|existing| existing.name == variable.name) {
+                Some(existing) => *existing = variable,
+                None => variables.push(variable),
+            }
+        }
+        variables
+    }
+
+    /// Returns the values of all custom properties declared in this style sheet
+    /// and imported sheets, see `all_variables()`.
+    ///
+    /// `var()` references are resolved against the custom properties of these
+    /// style sheets, following chains of references. Properties that cannot be
+    /// resolved, like those with cyclic references, are not included and an
+    /// error is returned for them instead.
+    pub fn resolved_variables(&self) -> (Vec<(String, Vec<Value>)>, Vec<ParseError>) {
+        let variables = self.all_variables();
+        let mut resolved = Vec::new();
+        let mut errors = Vec::new();
+        for variable in &variables {
+            match resolve_custom_property(&variable.name, &variables) {
+                Ok(values) => resolved.push((variable.name.clone(), values)),
+                Err(error) => errors.push(error),
+            }
+        }
+        (resolved, errors)
+    }
+
+    /// Returns the names of all declared layers, including those of imported sheets.
+    pub fn all_layers(&self) -> Vec<String> {
+        let mut layers: Vec<String> = Vec::new();
+        let imported = self.imported_sheets.iter().flat_map(|sheet| sheet.all_layers());
+        for layer in imported.chain(self.layers.iter().cloned()) {
+            if !layers.contains(&layer) {
+                layers.push(layer);
+            }
+        }
+        layers
+    }
+
+    /// Returns the names of all layers in cascade order, from lowest to highest priority.
+    ///
+    /// Layers are ordered by their first declaration, with nested layers
+    /// ordered before their parent layer.
+    pub fn layer_order(&self) -> Vec<String> {
+        fn add_children(parent: Option<&str>, layers: &[String], result: &mut Vec<String>) {
+            let children = layers.iter().filter(|layer| match parent {
+                Some(parent) => layer.strip_prefix(parent).and_then(|rest| rest.strip_prefix('.')).is_some_and(|rest| !rest.contains('.')),
+                None => !layer.contains('.'),
+            });
+            for child in children {
+                add_children(Some(child), layers, result);
+                result.push(child.clone());
+            }
+        }
+
+        let mut result = Vec::new();
+        add_children(None, &self.all_layers(), &mut result);
+        result
+    }
+
     pub fn all_paths(&self) -> Vec<PathBuf> {
         let mut paths: Vec<_> = self.imported_sheets.iter().map(|sheet| sheet.all_paths()).flatten().collect();
         paths.push(self.path.clone());
         paths
     }
 
+    /// Returns all rules whose selector matches `element`, in cascade order.
+    ///
+    /// Rules are ordered by cascade layer, then by increasing specificity.
+    /// Rules outside of any layer come after all layered rules. Rules with the
+    /// same layer and specificity are kept in source order, so later rules
+    /// take precedence when applied in order.
+    pub fn matching_rules(&self, element: &ElementContext) -> Vec<(StyleRule, Specificity)> {
+        let layers = self.layer_order();
+        let layer_rank = |rule: &StyleRule| {
+            rule.layer.as_ref().and_then(|layer| layers.iter().position(|name| name == layer)).unwrap_or(layers.len())
+        };
+
+        let mut rules: Vec<_> = self.all_rules()
+            .into_iter()
+            .filter(|rule| rule.selector.matches(element))
+            .map(|rule| {
+                let specificity = rule.selector.specificity();
+                (rule, specificity)
+            })
+            .collect();
+
+        // sort_by_key is stable, so this preserves source order for ties.
+        rules.sort_by_key(|(rule, specificity)| (layer_rank(rule), *specificity));
+        rules
+    }
+
+    /// Merge adjacent rules of this style sheet that have structurally equal selectors.
+    ///
+    /// Rules are only merged when they are also in the same media query and
+    /// cascade layer. Properties of later rules override earlier ones following
+    /// the cascade. Only adjacent rules are merged, as moving properties past a
+    /// rule with a different selector could change which of them wins for an
+    /// element matching both. Rules of imported sheets are not merged.
+    pub fn merge_identical_selectors(&mut self) {
+        let mut merged: Vec<StyleRule> = Vec::new();
+
+        for rule in self.rules.drain(..) {
+            let existing = merged.last_mut().filter(|existing| {
+                existing.selector == rule.selector && existing.media == rule.media && existing.layer == rule.layer
+            });
+
+            match existing {
+                Some(existing) => {
+                    existing.properties = Arc::new(merge_properties(&existing.properties, &rule.properties));
+                    existing.comments.extend(rule.comments);
+                }
+                None => merged.push(rule),
+            }
+        }
+
+        self.rules = merged;
+    }
+
     pub fn parse(&mut self) -> Result<(), ParseError> {
         let file = File::open(&self.path);
         if let Err(error) = file {
-            return Err(ParseError{ kind: ParseErrorKind::FileError, message: format!("{}", error), location: SourceLocation{ file: self.path.to_string_lossy().to_string(), line: 0, column: 0 } });
+            return Err(self.file_error(error));
         }
 
+        self.parse_reader(file.unwrap())
+    }
+
+    /// Read all of `reader` and parse it as the contents of this style sheet.
+    ///
+    /// This allows parsing data that does not come from a file, like network
+    /// data or embedded resources. Errors are reported against `path`.
+    pub fn parse_reader(&mut self, mut reader: impl Read) -> Result<(), ParseError> {
         let mut data = String::new();
-        let result = file.unwrap().read_to_string(&mut data);
-        if let Err(error) = result {
-            return Err(ParseError{ kind: ParseErrorKind::FileError, message: format!("{}", error), location: SourceLocation{ file: self.path.to_string_lossy().to_string(), line: 0, column: 0 } });
+        if let Err(error) = reader.read_to_string(&mut data) {
+            return Err(self.file_error(error));
         }
 
         self.parse_string(data.as_str())
     }
 
+    /// Parse UTF-8 encoded `data` as the contents of this style sheet.
+    pub fn parse_bytes(&mut self, data: &[u8]) -> Result<(), ParseError> {
+        match std::str::from_utf8(data) {
+            Ok(input) => self.parse_string(input),
+            Err(error) => Err(self.file_error(error)),
+        }
+    }
+
+    /// Parse `input` as the contents of this style sheet.
+    ///
+    /// cssparser has no API to set the source URL, so it is injected as a
+    /// `sourceURL` comment on a line of its own. As cssparser counts lines from
+    /// 0, this makes reported lines match the 1-based lines of `input`.
     pub fn parse_string(&mut self, input: &str) -> Result<(), ParseError> {
+        let start = self.record_stats.then(Instant::now);
+
         let prefix_input = format!("/*# sourceURL={} */\n{}", self.path.to_string_lossy().to_string(), input);
         let mut parser_input = cssparser::ParserInput::new(prefix_input.as_str());
         let mut parser = cssparser::Parser::new(&mut parser_input);
-        let mut rules_parser = TopLevelParser{};
+        let mut rules_parser = TopLevelParser { options: self.parser_options(), ..Default::default() };
+        if self.retain_comments {
+            let mut comments = Comments::collect(prefix_input.as_str());
+            // Skip the comment containing the source URL.
+            comments.take_before(prefix_input.len() - input.len());
+            rules_parser.comments = Some(Rc::new(RefCell::new(comments)));
+        }
         let style_sheet_parser = cssparser::StyleSheetParser::new(&mut parser, &mut rules_parser);
 
         let mut rules: Vec<StyleRule> = Vec::new();
@@ -76,22 +374,7 @@ impl StyleSheet {
         for entry in style_sheet_parser {
             match entry {
                 Ok(entry_contents) => {
-                    match entry_contents {
-                        ParseResult::Rule(rule) => {
-                            let mut parsed_rules = StyleRule::from_parsed_rule(&rule, self);
-                            rules.append(&mut parsed_rules);
-                        },
-                        ParseResult::PropertyDefinition(definition) => {
-                            let arc = Arc::new(definition);
-                            add_property_definition(&arc);
-                        },
-                        ParseResult::Import(name) => {
-                            self.import(PathBuf::from(name))?
-                        }
-                        ParseResult::Property(_) => {
-                            panic!("Received property at toplevel!");
-                        }
-                    }
+                    self.add_parse_result(entry_contents, None, None, &mut rules, &mut errors)?;
                 }
                 Err(error) => {
                     errors.push(parse_error_from_cssparser_error(&error.0, self.path.to_string_lossy().to_string()));
@@ -99,15 +382,202 @@ impl StyleSheet {
             }
         }
 
+        // Keep errors in source order so the output is stable regardless of
+        // the order in which nested blocks are processed.
+        errors.sort_by_key(|error| (error.location.line, error.location.column));
+
+        if let Some(start) = start {
+            self.stats.push(ParseStats {
+                path: self.path.clone(),
+                rules: rules.len(),
+                errors: errors.len(),
+                bytes: input.len(),
+                duration: start.elapsed(),
+            });
+        }
+
         self.rules.extend(rules);
         self.errors.extend(errors);
+        self.warnings.extend(rules_parser.warnings.take());
+        self.variables.extend(rules_parser.custom_properties.take());
 
         Ok(())
     }
 
+    /// Parse `declarations` as an inline style, like `color: red; width: 10px`.
+    ///
+    /// Returns the properties that were declared, errors are added to
+    /// `errors`. Nested rules and @-rules are not allowed.
+    pub fn parse_inline(&mut self, declarations: &str) -> Result<Vec<Property>, ParseError> {
+        let prefix_input = format!("/*# sourceURL={} */\n{}", self.path.to_string_lossy(), declarations);
+        let mut parser_input = cssparser::ParserInput::new(prefix_input.as_str());
+        let mut parser = cssparser::Parser::new(&mut parser_input);
+        let mut declarations_parser = NestedParser { options: self.parser_options(), ..Default::default() };
+
+        let file = self.path.to_string_lossy().to_string();
+        let mut properties = Vec::new();
+        let mut errors = Vec::new();
+        let mut body_parser = cssparser::RuleBodyParser::<NestedParser, ParseResult, ParseError>::new(&mut parser, &mut declarations_parser);
+        loop {
+            // Remember where the entry starts, to report nested rules there.
+            body_parser.input.skip_whitespace();
+            let location = body_parser.input.current_source_location();
+            let Some(entry) = body_parser.next() else {
+                break;
+            };
+
+            match entry {
+                Ok(ParseResult::Property(property)) => cascade_property(&mut properties, property),
+                Ok(ParseResult::PropertyDefinition(definition)) => register_parsed_definition(definition),
+                Ok(ParseResult::Ignored) => (),
+                Ok(_) => errors.push(ParseError {
+                    kind: ParseErrorKind::InvalidQualifiedRule,
+                    message: String::from("Inline styles can only contain declarations"),
+                    location: SourceLocation::from_file_location(file.clone(), location),
+                }),
+                Err(error) => errors.push(parse_error_from_cssparser_error(&error.0, file.clone())),
+            }
+        }
+
+        self.errors.extend(errors);
+        self.warnings.extend(declarations_parser.warnings.take());
+        self.variables.extend(declarations_parser.custom_properties.take());
+
+        Ok(properties)
+    }
+
+    fn parser_options(&self) -> ParserOptions {
+        ParserOptions {
+            strict_unknown_properties: self.strict_unknown_properties,
+            strict_unknown_at_rules: self.strict_unknown_at_rules,
+            strict_units: self.strict_units,
+            strict_trailing_commas: self.strict_trailing_commas,
+            strict_bang_flags: self.strict_bang_flags,
+        }
+    }
+
+    fn file_error(&self, error: impl std::fmt::Display) -> ParseError {
+        ParseError{ kind: ParseErrorKind::FileError, message: format!("{}", error), location: SourceLocation{ file: self.path.to_string_lossy().to_string(), line: 0, column: 0 } }
+    }
+
+    fn declare_layer(&mut self, name: &str) {
+        // Parent layers are declared before their children.
+        for (index, _) in name.match_indices('.').chain([(name.len(), "")]) {
+            let parent = &name[..index];
+            if !self.layers.iter().any(|layer| layer == parent) {
+                self.layers.push(parent.to_string());
+            }
+        }
+    }
+
+    fn add_parse_result(&mut self, result: ParseResult, media: Option<&MediaQuery>, layer: Option<&str>, rules: &mut Vec<StyleRule>, errors: &mut Vec<ParseError>) -> Result<(), ParseError> {
+        let full_layer_name = |name: &str| match layer {
+            Some(layer) => format!("{}.{}", layer, name),
+            None => name.to_string(),
+        };
+
+        match result {
+            ParseResult::Rule(rule) => {
+                let mut parsed_rules = StyleRule::from_parsed_rule(&rule, self);
+                for parsed_rule in &mut parsed_rules {
+                    parsed_rule.media = media.cloned();
+                    parsed_rule.layer = layer.map(String::from);
+                }
+                rules.append(&mut parsed_rules);
+                self.parsed_rules.push(rule);
+            },
+            ParseResult::PropertyDefinition(definition) => register_parsed_definition(definition),
+            ParseResult::Import { url, location } => {
+                if self.import_depth >= self.max_import_depth {
+                    errors.push(ParseError {
+                        kind: ParseErrorKind::InvalidAtRule,
+                        message: format!("Import of {} exceeds the maximum import depth of {}", url, self.max_import_depth),
+                        location: SourceLocation::from_file_location(self.path.to_string_lossy().to_string(), location),
+                    });
+                    return Ok(());
+                }
+
+                let path = self.import_path(&PathBuf::from(&url));
+                if let Err(error) = self.import(PathBuf::from(&url)) {
+                    // Report a missing file at the @import rule rather than in the missing file.
+                    if error.kind == ParseErrorKind::FileError && error.location.file == path.to_string_lossy() {
+                        return Err(ParseError {
+                            kind: ParseErrorKind::FileError,
+                            message: format!("Could not import {}: {}", url, error.message),
+                            location: SourceLocation::from_file_location(self.path.to_string_lossy().to_string(), location),
+                        });
+                    }
+                    return Err(error);
+                }
+            }
+            ParseResult::Namespace => {
+                // Namespaces are tracked by the rules parser, they only affect
+                // how selectors are parsed.
+            }
+            ParseResult::Media { query, contents, errors: media_errors } => {
+                // A nested @media only applies when the outer one does too.
+                let query = match media {
+                    Some(outer) => outer.and(&query),
+                    None => query,
+                };
+                for content in contents {
+                    self.add_parse_result(content, Some(&query), layer, rules, errors)?;
+                }
+                errors.extend(media_errors);
+            }
+            ParseResult::Layer { name, contents, errors: layer_errors } => {
+                let name = full_layer_name(&name);
+                self.declare_layer(&name);
+                for content in contents {
+                    self.add_parse_result(content, media, Some(&name), rules, errors)?;
+                }
+                errors.extend(layer_errors);
+            }
+            ParseResult::LayerStatement(names) => {
+                for name in names {
+                    self.declare_layer(&full_layer_name(&name));
+                }
+            }
+            ParseResult::Keyframes { keyframes, errors: keyframes_errors } => {
+                self.keyframes.push(keyframes);
+                errors.extend(keyframes_errors);
+            }
+            ParseResult::FontFace { mut font_face, errors: font_face_errors } => {
+                // Relative sources are relative to the style sheet, like other URLs.
+                if let Some(parent) = self.path.parent() {
+                    for source in font_face.sources.iter_mut().filter(|source| !has_url_scheme(&source.url)) {
+                        source.url = parent.join(&source.url).to_string_lossy().to_string();
+                    }
+                }
+                self.font_faces.push(font_face);
+                errors.extend(font_face_errors);
+            }
+            ParseResult::Ignored => (),
+            ParseResult::Property(_) => {
+                panic!("Received property at toplevel!");
+            }
+        }
+
+        Ok(())
+    }
+
+    // Imports are relative to the importing sheet, unless they are absolute.
+    fn import_path(&self, file: &PathBuf) -> PathBuf {
+        if file.is_absolute() { file.clone() } else { self.path.parent().unwrap().join(file) }
+    }
+
     pub fn import(&mut self, file: PathBuf) -> Result<(), ParseError> {
-        let path = if file.is_absolute() { file.clone() } else { self.path.parent().unwrap().join(file.clone()) };
+        let path = self.import_path(&file);
         let mut sheet = StyleSheet::new(path);
+        sheet.strict_unknown_properties = self.strict_unknown_properties;
+        sheet.strict_unknown_at_rules = self.strict_unknown_at_rules;
+        sheet.strict_units = self.strict_units;
+        sheet.strict_trailing_commas = self.strict_trailing_commas;
+        sheet.strict_bang_flags = self.strict_bang_flags;
+        sheet.retain_comments = self.retain_comments;
+        sheet.record_stats = self.record_stats;
+        sheet.max_import_depth = self.max_import_depth;
+        sheet.import_depth = self.import_depth + 1;
         sheet.parse()?;
 
         self.imported_sheets.push(sheet);
@@ -115,3 +585,27 @@ impl StyleSheet {
         Ok(())
     }
 }
+
+/// Parse `data` as a style sheet and return the parsed rules and errors.
+///
+/// `origin` is used as the path of the style sheet, for error locations and to
+/// resolve relative imports and URLs. Rules and errors from imported sheets are
+/// included in the result.
+pub fn parse_stylesheet(data: &str, origin: &str) -> Result<(Vec<StyleRule>, Vec<ParseError>), ParseError> {
+    let mut sheet = StyleSheet::new(PathBuf::from(origin));
+    sheet.parse_string(data)?;
+    Ok((sheet.all_rules(), sheet.all_errors()))
+}
+
+// Whether `url` starts with a scheme like `data:` or `https:`. Single letters
+// are not considered a scheme, so Windows drive letters remain paths.
+pub(crate) fn has_url_scheme(url: &str) -> bool {
+    match url.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+use crate::ffi::ffi::ValueType;
+use crate::ffi::{set_channels, UNSET_CHANNEL};
+use crate::value::{Color, ColorOperation, Dimension, Unit, Value};
+
+#[test]
+fn set_channel_encoding() {
+    let operation = ColorOperation::Set { r: Some(255), g: Some(0), b: None, a: None };
+    assert_eq!(set_channels(&operation), Some([255, 0, UNSET_CHANNEL, UNSET_CHANNEL]));
+
+    let operation = ColorOperation::set(None, None, None, Some(127));
+    assert_eq!(set_channels(&operation), Some([UNSET_CHANNEL, UNSET_CHANNEL, UNSET_CHANNEL, 127]));
+
+    let operation = ColorOperation::Add { other: Box::new(Color::rgba(0, 0, 0, 255)) };
+    assert_eq!(set_channels(&operation), None);
+}
+
+#[test]
+fn to_number() {
+    assert_eq!(Value::from(5).to_number().ok(), Some(5.0));
+    assert_eq!(Value::from(Dimension { value: 5.0, unit: Unit::Number }).to_number().ok(), Some(5.0));
+
+    let error = Value::from(Dimension::px(5.0)).to_number().err().unwrap();
+    assert_eq!(error.message, "Not a number value");
+    assert!(Value::new_keyword("five").to_number().is_err());
+}
+
+#[test]
+fn to_image() {
+    let value = Value::new_image("icons/x.svg");
+    assert!(value.value_type() == ValueType::Image);
+    assert_eq!(value.to_image().ok(), Some("icons/x.svg"));
+
+    let url = Value::new_url("icons/x.svg");
+    assert!(url.value_type() == ValueType::Url);
+    assert_eq!(url.to_image().err().unwrap().message, "Not an image");
+    assert!(Value::from("icons/x.svg").to_image().is_err());
+}
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+use crate::details::mediaparser::parse_media_query;
+use crate::media::{MediaComparison, MediaCondition, MediaContext, MediaFeature, MediaQuery};
+use crate::parseerror::ParseErrorKind;
+
+fn parse_query(input: &str) -> Result<MediaQuery, ParseErrorKind> {
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+
+    match parse_media_query(&mut parser) {
+        Ok(query) => Ok(query),
+        Err(error) => {
+            if let cssparser::ParseErrorKind::Custom(parse_error) = error.kind {
+                Err(parse_error.kind)
+            } else {
+                Err(ParseErrorKind::UnexpectedToken)
+            }
+        }
+    }
+}
+
+fn test_feature(input: &str, expected: MediaFeature) {
+    let query = parse_query(input).unwrap();
+    assert_eq!(query.condition, Some(MediaCondition::Feature(expected)));
+}
+
+test_cases! {
+    min_resolution_dppx: test_feature "(min-resolution: 2dppx)", MediaFeature::Resolution { comparison: MediaComparison::Minimum, value: 2.0 };
+    max_resolution_x: test_feature "(max-resolution: 1.5x)", MediaFeature::Resolution { comparison: MediaComparison::Maximum, value: 1.5 };
+    resolution_dpi: test_feature "(resolution: 192dpi)", MediaFeature::Resolution { comparison: MediaComparison::Exact, value: 2.0 };
+    min_width: test_feature "(min-width: 600px)", MediaFeature::Width { comparison: MediaComparison::Minimum, value: 600.0 };
+    max_height: test_feature "(max-height: 0)", MediaFeature::Height { comparison: MediaComparison::Maximum, value: 0.0 };
+    color_scheme: test_feature "(prefers-color-scheme: Dark)", MediaFeature::PrefersColorScheme(String::from("dark"));
+    unknown: test_feature "(hover: hover)", MediaFeature::Unknown(String::from("hover"));
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(parse_query("(min-resolution: 2px)").unwrap_err(), ParseErrorKind::InvalidAtRule);
+    assert!(parse_query("min-resolution: 2dppx").is_err());
+}
+
+#[test]
+fn evaluate() {
+    let context = MediaContext { width: 800.0, height: 600.0, device_pixel_ratio: 2.0, ..Default::default() };
+
+    assert!(parse_query("(min-resolution: 2dppx)").unwrap().evaluate(&context));
+    assert!(!parse_query("(min-resolution: 3dppx)").unwrap().evaluate(&context));
+    assert!(parse_query("(max-resolution: 192dpi)").unwrap().evaluate(&context));
+    assert!(parse_query("(min-width: 600px)").unwrap().evaluate(&context));
+    assert!(!parse_query("(max-height: 400px)").unwrap().evaluate(&context));
+    assert!(!parse_query("(prefers-color-scheme: dark)").unwrap().evaluate(&context));
+    assert!(!parse_query("(hover: hover)").unwrap().evaluate(&context));
+    assert!(MediaQuery::empty().evaluate(&context));
+}
+
+fn min_width_condition(value: f32) -> MediaCondition {
+    MediaCondition::Feature(MediaFeature::Width { comparison: MediaComparison::Minimum, value })
+}
+
+fn media_type(name: &str) -> MediaCondition {
+    MediaCondition::MediaType(String::from(name))
+}
+
+fn test_condition(input: &str, expected: MediaCondition) {
+    let query = parse_query(input).unwrap();
+    assert_eq!(query.condition, Some(expected));
+}
+
+test_cases! {
+    media_type_only: test_condition "print", media_type("print");
+    media_type_and: test_condition "screen and (min-width: 600px)", MediaCondition::And(vec![media_type("screen"), min_width_condition(600.0)]);
+    only_media_type: test_condition "only screen", media_type("screen");
+    not_media_type: test_condition "not print and (min-width: 600px)", MediaCondition::Not(Box::new(MediaCondition::And(vec![media_type("print"), min_width_condition(600.0)])));
+    not_condition: test_condition "not (min-width: 600px)", MediaCondition::Not(Box::new(min_width_condition(600.0)));
+    or_condition: test_condition "(min-width: 600px) or (min-width: 800px)", MediaCondition::Or(vec![min_width_condition(600.0), min_width_condition(800.0)]);
+    nested_condition: test_condition "(min-width: 600px) and ((min-width: 800px) or (min-width: 1000px))", MediaCondition::And(vec![
+        min_width_condition(600.0),
+        MediaCondition::Or(vec![min_width_condition(800.0), min_width_condition(1000.0)]),
+    ]);
+    query_list: test_condition "screen and (min-width: 600px), print", MediaCondition::Or(vec![
+        MediaCondition::And(vec![media_type("screen"), min_width_condition(600.0)]),
+        media_type("print"),
+    ]);
+}
+
+#[test]
+fn invalid_logic() {
+    for input in [
+        "(min-width: 600px) and (min-width: 800px) or (min-width: 1000px)",
+        "screen and (min-width: 600px) or (min-width: 800px)",
+        "screen print",
+        "not",
+        "only and",
+        "(min-width: 600px),",
+        "(min-width: 600px) (min-width: 800px)",
+    ] {
+        assert!(parse_query(input).is_err(), "Query {:?} should not parse", input);
+    }
+
+    assert_eq!(parse_query("screen and (min-width: 600px) or (min-width: 800px)").unwrap_err(), ParseErrorKind::InvalidAtRule);
+}
+
+#[test]
+fn evaluate_logic() {
+    let context = MediaContext { width: 800.0, height: 600.0, color_scheme: String::from("dark"), ..Default::default() };
+
+    assert!(parse_query("screen and (min-width: 600px), print").unwrap().evaluate(&context));
+    assert!(!parse_query("print and (min-width: 600px)").unwrap().evaluate(&context));
+    assert!(parse_query("print, (prefers-color-scheme: dark)").unwrap().evaluate(&context));
+    assert!(parse_query("all and (max-width: 800px)").unwrap().evaluate(&context));
+    assert!(parse_query("not print").unwrap().evaluate(&context));
+    assert!(!parse_query("not screen and (min-width: 600px)").unwrap().evaluate(&context));
+    assert!(parse_query("not (min-width: 1000px)").unwrap().evaluate(&context));
+    assert!(parse_query("(min-width: 1000px) or (min-height: 600px)").unwrap().evaluate(&context));
+    assert!(!parse_query("(min-width: 600px) and (not (prefers-color-scheme: dark))").unwrap().evaluate(&context));
+}
@@ -19,3 +19,7 @@ mod propertyvalue;
 mod selectorparser;
 mod selector;
 mod propertyfunction;
+mod propertydefinition;
+mod media;
+mod value;
+mod ffi;
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+use crate::details::property::definitionparser::parse_property_definition;
+use crate::details::property::syntax::ParsedPropertySyntax;
+use crate::parseerror::ParseErrorKind;
+use crate::property::{parse_property_value, DataType, PropertyDefinition};
+use crate::value::{Dimension, Value};
+
+fn parse_definition(name: &str, input: &str) -> Result<PropertyDefinition, ParseErrorKind> {
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+
+    match parse_property_definition(&mut parser, name.to_string()) {
+        Ok(definition) => Ok(definition),
+        Err(error) => {
+            if let cssparser::ParseErrorKind::Custom(parse_error) = error.kind {
+                Err(parse_error.kind)
+            } else {
+                panic!("Expected details::ParseError, got {:?}", error)
+            }
+        }
+    }
+}
+
+#[test]
+fn typed_with_initial() {
+    let definition = parse_definition("width", "syntax: \"<length>\"; inherits: false; initial-value: 10px;").unwrap();
+    assert_eq!(definition.initial, vec![Value::from(Dimension::px(10.0))]);
+}
+
+#[test]
+fn typed_without_initial() {
+    let result = parse_definition("width", "syntax: \"<length>\"; inherits: false;");
+    assert_eq!(result.unwrap_err(), ParseErrorKind::InvalidPropertyDefinition);
+}
+
+#[test]
+fn typed_inherited_without_initial() {
+    let definition = parse_definition("width", "syntax: \"<length>\"; inherits: true;").unwrap();
+    assert!(definition.initial.is_empty());
+}
+
+#[test]
+fn universal_without_initial() {
+    let definition = parse_definition("--anything", "syntax: \"*\"; inherits: false;").unwrap();
+    assert_eq!(definition.syntax, ParsedPropertySyntax::Universal);
+    assert!(definition.initial.is_empty());
+}
+
+fn test_accepted_types(input: &str, expected: (Vec<DataType>, Vec<&str>)) {
+    let definition = PropertyDefinition::from_name_syntax("test", input, "test", 0, 0).unwrap();
+    assert_eq!(definition.accepted_data_types(), expected.0);
+    assert_eq!(definition.accepted_keywords(), expected.1);
+}
+
+test_cases! {
+    accepted_single: test_accepted_types "<length>", (vec![DataType::Length], vec![]);
+    accepted_alternatives: test_accepted_types "<url> | none", (vec![DataType::Url], vec!["none"]);
+    accepted_lists: test_accepted_types "<length>+ <color>#", (vec![DataType::Length, DataType::Color], vec![]);
+    accepted_nested: test_accepted_types "(<length> | auto) (<length> | auto | <percentage>)", (vec![DataType::Length, DataType::Percentage], vec!["auto"]);
+    accepted_range: test_accepted_types "<integer [0, 10]>{1,2}", (vec![DataType::Integer], vec![]);
+    accepted_universal: test_accepted_types "*", (vec![], vec![]);
+}
+
+#[test]
+fn accepted_types_strings() {
+    let definition = PropertyDefinition::from_name_syntax("test", "<url> | none", "test", 0, 0).unwrap();
+    assert_eq!(definition.accepted_types(), vec![String::from("<url>"), String::from("none")]);
+}
+
+#[test]
+fn from_name_syntax_initial() {
+    let initial = vec![Value::from(Dimension::px(10.0))];
+    let definition = PropertyDefinition::from_name_syntax_initial("width", "<length>", &initial, "Test Input", 0, 0).unwrap();
+    assert_eq!(definition.name, "width");
+    assert_eq!(definition.initial, initial);
+    assert_eq!(definition.accepted_data_types(), vec![DataType::Length]);
+
+    let error = PropertyDefinition::from_name_syntax_initial("width", "<length", &initial, "Test Input", 0, 0).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidPropertySyntax);
+}
+
+#[test]
+fn keyword_set() {
+    let definition = PropertyDefinition::from_name_syntax("overflow", "visible | hidden | scroll | auto", "Test Input", 0, 0).unwrap();
+
+    let values = parse_property_value(&definition, "scroll").unwrap();
+    assert_eq!(values[0].as_keyword(), Some("scroll"));
+    assert_eq!(definition.keyword_index(&values[0]), Some(2));
+
+    assert!(parse_property_value(&definition, "clip").is_err());
+    assert!(parse_property_value(&definition, "'scroll'").is_err());
+
+    assert_eq!(Value::from("scroll").as_keyword(), None);
+    assert_eq!(definition.keyword_index(&Value::new_keyword("clip")), None);
+}
@@ -1,7 +1,8 @@
 // SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
 // SPDX-FileCopyrightText: 2025 Arjen Hiemstra <ahiemstra@heimr.nl>
 
-use crate::details::property::function::property_function;
+use crate::details::property::function::{property_function, property_function_syntax, add_property_function, add_property_function_with_syntax, PropertyFunctionResult};
+use crate::parseerror::ParseErrorKind;
 use crate::value::{Color, ColorOperation, Value};
 
 fn check_value(input: &str, expected: Vec<Value>) {
@@ -34,7 +35,22 @@ test_cases! {
 
     mix_alpha:
         check_value "mix(rgba(255, 0, 255, 0.25), rgba(255, 255, 0, 0.75), 0.25)", vec![
-            Value::from(Color::modified(&Color::rgba(255, 0, 255, 63), ColorOperation::mix(&Color::rgba(255, 255, 0, 191), 0.25)))
+            Value::from(Color::modified(&Color::rgba(255, 0, 255, 64), ColorOperation::mix(&Color::rgba(255, 255, 0, 191), 0.25)))
+        ];
+
+    mix_integer_amount:
+        check_value "mix(black, white, 1)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::mix(&Color::rgba(255, 255, 255, 255), 1.0)))
+        ];
+
+    mix_clamp_above:
+        check_value "mix(black, white, 1.5)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::Mix { other: Box::new(Color::rgba(255, 255, 255, 255)), amount: 1.0 }))
+        ];
+
+    mix_clamp_below:
+        check_value "mix(black, white, -0.5)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::Mix { other: Box::new(Color::rgba(255, 255, 255, 255)), amount: 0.0 }))
         ];
 
     custom_color:
@@ -61,4 +77,125 @@ test_cases! {
         check_value "modify-color(black set-alpha 0.5)", vec![
             Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(None, None, None, Some(127))))
         ];
+
+    modify_color_set_alpha_percentage:
+        check_value "modify-color(black set-alpha 50%)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(None, None, None, Some(127))))
+        ];
+
+    modify_color_set_multiple:
+        check_value "modify-color(black set-red 10 set-alpha 0.5)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(Some(10), None, None, Some(127))))
+        ];
+
+    modify_color_set_rgb:
+        check_value "modify-color(black set-rgb 10 20 100%)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(Some(10), Some(20), Some(255), None)))
+        ];
+
+    modify_color_set_rgba:
+        check_value "modify-color(black set-rgba 10 20 30 1 set-green 300)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(Some(10), Some(255), Some(30), Some(255))))
+        ];
+
+    relative_rgb_passthrough:
+        check_value "rgb(from #102030 r g b)", vec![Value::from(Color::rgba(16, 32, 48, 255))];
+
+    relative_rgb_channels:
+        check_value "rgb(from #102030 b r 100% / 50%)", vec![Value::from(Color::rgba(48, 16, 255, 128))];
+
+    relative_rgba_alpha:
+        check_value "rgba(from red r g b / 0.5)", vec![Value::from(Color::rgba(255, 0, 0, 128))];
+
+    relative_hsl_passthrough:
+        check_value "hsl(from #ff0000 h s l)", vec![Value::from(Color::rgba(255, 0, 0, 255))];
+
+    relative_hsl_hue:
+        check_value "hsl(from red 120 s l)", vec![Value::from(Color::rgba(0, 255, 0, 255))];
+
+    relative_rgb_modified_base:
+        check_value "rgb(from modify-color(black add white) r 10 b)", vec![
+            Value::from(Color::modified(
+                &Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::add(&Color::rgba(255, 255, 255, 255))),
+                ColorOperation::set(None, Some(10), None, None)
+            ))
+        ];
+
+    modify_color_mix:
+        check_value "modify-color(black mix white 0.25)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::mix(&Color::rgba(255, 255, 255, 255), 0.25)))
+        ];
+
+    modify_color_mix_clamp:
+        check_value "modify-color(black mix white 1.5)", vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::Mix { other: Box::new(Color::rgba(255, 255, 255, 255)), amount: 1.0 }))
+        ];
+}
+
+#[test]
+fn mix_amount_normalization() {
+    let white = Color::rgba(255, 255, 255, 255);
+    assert_eq!(ColorOperation::mix(&white, 1.5), ColorOperation::Mix { other: Box::new(white.clone()), amount: 1.0 });
+    assert_eq!(ColorOperation::mix(&white, -1.0), ColorOperation::Mix { other: Box::new(white.clone()), amount: 0.0 });
+    assert_eq!(ColorOperation::mix(&white, f32::NAN), ColorOperation::Mix { other: Box::new(white.clone()), amount: 0.0 });
+}
+
+fn check_error(input: &str, expected: ParseErrorKind) {
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+
+    let function_name = parser.expect_function().unwrap().as_ref();
+    let function = property_function(function_name).unwrap();
+
+    let result = parser.parse_nested_block(|parser| function(parser));
+    match result {
+        Ok(values) => panic!("Expected error, got {:?}", values),
+        Err(error) => {
+            if let cssparser::ParseErrorKind::Custom(error) = error.kind {
+                assert_eq!(error.kind, expected);
+            } else {
+                panic!("Expected a custom error, got {:?}", error);
+            }
+        }
+    }
+}
+
+test_cases! {
+    mix_trailing_ident: check_error "mix(black, white, 0.5 extra)", ParseErrorKind::InvalidPropertyValue;
+    mix_trailing_argument: check_error "mix(black, white, 0.5, 0.5)", ParseErrorKind::InvalidPropertyValue;
+    custom_color_trailing_dimension: check_error "custom-color('test', 'argument', 10px)", ParseErrorKind::InvalidPropertyValue;
+    modify_color_trailing_ident: check_error "modify-color(black add white extra)", ParseErrorKind::InvalidPropertyValue;
+    modify_color_set_missing_value: check_error "modify-color(black set-rgb 10 20)", ParseErrorKind::InvalidPropertyValue;
+    modify_color_set_invalid_value: check_error "modify-color(black set-red 10px)", ParseErrorKind::InvalidPropertyValue;
+    relative_rgb_missing_channel: check_error "rgb(from red r g)", ParseErrorKind::InvalidPropertyValue;
+    relative_rgb_invalid_channel: check_error "rgb(from red r g h)", ParseErrorKind::InvalidPropertyValue;
+    relative_rgb_without_from: check_error "rgb(red r g b)", ParseErrorKind::InvalidPropertyValue;
+    relative_hsl_modified_base: check_error "hsl(from modify-color(black add white) h s l)", ParseErrorKind::InvalidPropertyValue;
+    modify_color_set_no_operation: check_error "modify-color(black)", ParseErrorKind::InvalidPropertyValue;
+    var_trailing_bang: check_error "var(--undefined-variable, 10px !extra)", ParseErrorKind::InvalidPropertyValue;
+}
+
+fn test_function<'i, 't>(_parser: &mut cssparser::Parser<'i, 't>) -> PropertyFunctionResult<'i> {
+    Ok(vec![Value::from(1)])
+}
+
+#[test]
+fn function_syntax() {
+    assert_eq!(property_function_syntax("mix"), Some(String::from("<color>, <color>, <number>")));
+    assert_eq!(property_function_syntax("var"), None);
+    assert_eq!(property_function_syntax("test-undefined-function"), None);
+    // hwb() is parsed as a color and is not a registered function.
+    assert!(property_function("hwb").is_none());
+
+    assert!(add_property_function_with_syntax("test-syntax-function", "<length>, <color>?", test_function));
+    assert_eq!(property_function_syntax("test-syntax-function"), Some(String::from("<length>, <color>?")));
+    assert!(property_function("test-syntax-function").is_some());
+
+    // Bare registration still works, but has no syntax.
+    assert!(add_property_function("test-bare-function", test_function));
+    assert_eq!(property_function_syntax("test-bare-function"), None);
+
+    assert!(!add_property_function_with_syntax("test-invalid-syntax-function", "<length", test_function));
+    assert!(property_function("test-invalid-syntax-function").is_none());
+    assert!(!add_property_function_with_syntax("mix", "<color>", test_function));
 }
@@ -2,7 +2,7 @@
 // SPDX-FileCopyrightText: 2025 Arjen Hiemstra <ahiemstra@heimr.nl>
 
 use crate::details::property::syntax::*;
-use crate::parseerror::SourceLocation;
+use crate::parseerror::{ParseErrorKind, SourceLocation};
 
 fn check_syntax(input: &str, expected: ParsedPropertySyntax) {
     let result = parse_syntax(input, SourceLocation::from_file("Test Input"));
@@ -32,6 +32,32 @@ test_cases! {
             SyntaxAlternatives::Component(SyntaxComponent::CommaSeparatedList(DataType::Url))
         ]);
 
+    angle_percentage:
+        check_syntax "<angle-percentage>",
+        ParsedPropertySyntax::Expression(vec![
+            SyntaxAlternatives::Component(SyntaxComponent::DataType(DataType::AnglePercentage))
+        ]);
+
+    time_percentage_list:
+        check_syntax "<time-percentage>#",
+        ParsedPropertySyntax::Expression(vec![
+            SyntaxAlternatives::Component(SyntaxComponent::CommaSeparatedList(DataType::TimePercentage))
+        ]);
+
+    slash:
+        check_syntax "<length> / <number>",
+        ParsedPropertySyntax::Expression(vec![
+            SyntaxAlternatives::Component(SyntaxComponent::DataType(DataType::Length)),
+            SyntaxAlternatives::Component(SyntaxComponent::Slash),
+            SyntaxAlternatives::Component(SyntaxComponent::DataType(DataType::Number)),
+        ]);
+
+    range:
+        check_syntax "<length [0,∞]>",
+        ParsedPropertySyntax::Expression(vec![
+            SyntaxAlternatives::Component(SyntaxComponent::Range{data_type: DataType::Length, minimum: 0.0, maximum: f32::INFINITY})
+        ]);
+
     multiple:
         check_syntax "<percentage> <angle>",
         ParsedPropertySyntax::Expression(vec![
@@ -104,3 +130,38 @@ fn invalid_datatype() {
     let result = parse_syntax("<invalid> | <length>", SourceLocation::from_file("Test Input"));
     assert!(result.is_err());
 }
+
+fn check_round_trip(input: &str, expected: &str) {
+    let syntax = parse_syntax(input, SourceLocation::from_file("Test Input")).unwrap();
+    let output = syntax.to_syntax_string();
+    assert_eq!(output, expected);
+
+    let reparsed = parse_syntax(&output, SourceLocation::from_file("Test Input")).unwrap();
+    assert_eq!(reparsed, syntax);
+}
+
+test_cases! {
+    round_trip_universal: check_round_trip "*", "*";
+    round_trip_datatype: check_round_trip "<color>", "<color>";
+    round_trip_lists: check_round_trip "<length>+ <url>#", "<length>+ <url>#";
+    round_trip_alternatives: check_round_trip "<length>|auto", "<length> | auto";
+    round_trip_repeat: check_round_trip "<length>{1,4}", "<length>{1,4}";
+    round_trip_range: check_round_trip "<integer [0, 10]>", "<integer [0, 10]>";
+    round_trip_group: check_round_trip "(auto | <length>) | <length> <length>", "(auto | <length>) | <length> <length>";
+}
+
+#[test]
+fn deep_nesting() {
+    let nested = |depth: usize| format!("{}<length>{}", "(".repeat(depth), ")".repeat(depth));
+
+    let result = parse_syntax(&nested(32), SourceLocation::from_file("Test Input"));
+    assert!(result.is_ok(), "{}", result.unwrap_err());
+
+    let result = parse_syntax(&nested(33), SourceLocation::from_file("Test Input"));
+    let error = result.unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidPropertySyntax);
+    assert!(error.message.contains("nested more than 32 levels"), "Unexpected message: {}", error.message);
+
+    let result = parse_syntax(&nested(100_000), SourceLocation::from_file("Test Input"));
+    assert!(result.is_err());
+}
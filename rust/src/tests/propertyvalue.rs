@@ -2,9 +2,10 @@
 // SPDX-FileCopyrightText: 2025 Arjen Hiemstra <ahiemstra@heimr.nl>
 
 use crate::details::property::syntax::parse_syntax;
-use crate::details::property::value::parse_values;
+use crate::details::property::value::{parse_values, parse_values_with_options};
+use crate::details::rulesparser::ParserOptions;
 use crate::parseerror::{ParseErrorKind, SourceLocation};
-use crate::value::{Color, Dimension, Value, Unit};
+use crate::value::{Color, ColorOperation, Dimension, Value, Unit};
 
 fn check_value(input: (&str, &str), expected: Vec<Value>) {
     let mut parser_input = cssparser::ParserInput::new(input.1);
@@ -24,6 +25,18 @@ test_cases! {
         check_value ("<length>", "24px"), vec![
             Value::from(Dimension::px(24.0))
         ];
+    length_negative:
+        check_value ("<length>", "-10px"), vec![
+            Value::from(Dimension::px(-10.0))
+        ];
+    length_range:
+        check_value ("<length [0,∞]>", "5px"), vec![
+            Value::from(Dimension::px(5.0))
+        ];
+    length_uppercase_unit:
+        check_value ("<length>", "10PX"), vec![
+            Value::from(Dimension::px(10.0))
+        ];
     length_em:
         check_value ("<length>", "3em"), vec![
             Value::from(Dimension{value: 3.0, unit: Unit::Em})
@@ -34,10 +47,154 @@ test_cases! {
             Value::from(Dimension::px(2.0)),
             Value::from(Dimension::px(3.0)),
         ];
+    percentage:
+        check_value ("<percentage>", "50%"), vec![
+            Value::from(Dimension{value: 0.5, unit: Unit::Percent})
+        ];
+    percentage_over_hundred:
+        check_value ("<percentage>", "150%"), vec![
+            Value::from(Dimension{value: 1.5, unit: Unit::Percent})
+        ];
+    length_percentage_length:
+        check_value ("<length-percentage>", "10px"), vec![
+            Value::from(Dimension::px(10.0))
+        ];
+    length_percentage_percentage:
+        check_value ("<length-percentage>", "50%"), vec![
+            Value::from(Dimension{value: 0.5, unit: Unit::Percent})
+        ];
+    angle_percentage_angle:
+        check_value ("<angle-percentage>", "90deg"), vec![
+            Value::from(Dimension{value: 90.0, unit: Unit::Degrees})
+        ];
+    angle_percentage_percentage:
+        check_value ("<angle-percentage>", "25%"), vec![
+            Value::from(Dimension{value: 0.25, unit: Unit::Percent})
+        ];
+    time:
+        check_value ("<time>", "200ms"), vec![
+            Value::from(Dimension{value: 200.0, unit: Unit::Milliseconds})
+        ];
+    time_percentage_time:
+        check_value ("<time-percentage>", "2s"), vec![
+            Value::from(Dimension{value: 2.0, unit: Unit::Seconds})
+        ];
+    time_percentage_percentage:
+        check_value ("<time-percentage>", "50%"), vec![
+            Value::from(Dimension{value: 0.5, unit: Unit::Percent})
+        ];
+    flex:
+        check_value ("<flex>+", "1fr 2.5fr"), vec![
+            Value::from(Dimension{value: 1.0, unit: Unit::Fr}),
+            Value::from(Dimension{value: 2.5, unit: Unit::Fr}),
+        ];
+    slash:
+        check_value ("<length> / <number>", "16px / 1.5"), vec![
+            Value::from(Dimension::px(16.0)),
+            Value::new_slash(),
+            Value::from(1.5),
+        ];
+    slash_without_whitespace:
+        check_value ("<length> / <number>", "16px/1.5"), vec![
+            Value::from(Dimension::px(16.0)),
+            Value::new_slash(),
+            Value::from(1.5),
+        ];
     number:
         check_value ("<number>", "66.6"), vec![
             Value::from(66.6)
         ];
+    number_exponent:
+        check_value ("<number>", "1e3"), vec![
+            Value::from(1000.0)
+        ];
+    number_negative_exponent:
+        check_value ("<number>", "2.5e-1"), vec![
+            Value::from(0.25)
+        ];
+    number_uppercase_exponent:
+        check_value ("<number>", "1E2"), vec![
+            Value::from(100.0)
+        ];
+    length_exponent:
+        check_value ("<length>", "1e1px"), vec![
+            Value::from(Dimension::px(10.0))
+        ];
+    url_unquoted:
+        check_value ("<url>", "url(icons/x.svg)"), vec![
+            Value::new_url("icons/x.svg")
+        ];
+    url_quoted:
+        check_value ("<url>", "url(\"icons/x.svg\")"), vec![
+            Value::new_url("icons/x.svg")
+        ];
+    url_single_quoted:
+        check_value ("<url>", "url('icons/x.svg')"), vec![
+            Value::new_url("icons/x.svg")
+        ];
+    url_data:
+        check_value ("<url>", "url(\"data:image/png;base64,iVBORw0KGgo=\")"), vec![
+            Value::new_url("data:image/png;base64,iVBORw0KGgo=")
+        ];
+    url_data_unquoted:
+        check_value ("<url>", "url(data:image/png;base64,iVBORw0KGgo=)"), vec![
+            Value::new_url("data:image/png;base64,iVBORw0KGgo=")
+        ];
+    integer_for_number:
+        check_value ("<number>", "1"), vec![
+            Value::from(1)
+        ];
+    integer_max:
+        check_value ("<integer>", "2147483647"), vec![
+            Value::from(i32::MAX)
+        ];
+    integer_max_after_comment:
+        check_value ("<integer>", "/**/2147483647"), vec![
+            Value::from(i32::MAX)
+        ];
+    integer_min:
+        check_value ("<integer>", "-2147483648"), vec![
+            Value::from(i32::MIN)
+        ];
+    large_integer_for_number:
+        check_value ("<number>", "99999999999"), vec![
+            Value::from(99999999999.0)
+        ];
+    zero_for_length:
+        check_value ("<length>", "0"), vec![
+            Value::from(Dimension::px(0.0))
+        ];
+    zero_fraction_for_length:
+        check_value ("<length>", "0.0"), vec![
+            Value::from(Dimension::px(0.0))
+        ];
+    zero_for_length_percentage:
+        check_value ("<length-percentage>+", "0 50%"), vec![
+            Value::from(Dimension::px(0.0)),
+            Value::from(Dimension{value: 0.5, unit: Unit::Percent}),
+        ];
+    zero_for_length_range:
+        check_value ("<length [0,∞]>", "0"), vec![
+            Value::from(Dimension::px(0.0))
+        ];
+    zero_for_number_alternative:
+        check_value ("<number> | <length>", "0"), vec![
+            Value::from(0)
+        ];
+    zero_for_length_after_number:
+        check_value ("<integer> <length>", "0 0"), vec![
+            Value::from(0),
+            Value::from(Dimension::px(0.0)),
+        ];
+    number_for_number:
+        check_value ("<number>", "0.5"), vec![
+            Value::from(0.5)
+        ];
+    integer_list_for_number_list:
+        check_value ("<number>+", "1 2.5"), vec![
+            Value::from(1),
+            Value::from(2.5),
+        ];
     color_hex:
         check_value ("<color>", "#ff0000"), vec![
             Value::from(Color::rgba(255, 0, 0, 255))
@@ -50,19 +207,69 @@ test_cases! {
         check_value ("<color>", "blue"), vec![
             Value::from(Color::rgba(0, 0, 255, 255))
         ];
+    color_rgba_alpha:
+        check_value ("<color>", "rgba(255, 0, 0, 0.5)"), vec![
+            Value::from(Color::rgba(255, 0, 0, 128))
+        ];
+    color_rgba_percent_alpha:
+        check_value ("<color>", "rgba(255,0,0,50%)"), vec![
+            Value::from(Color::rgba(255, 0, 0, 128))
+        ];
+    color_hsla_percent_alpha:
+        check_value ("<color>", "hsla(120, 100%, 50%, 50%)"), vec![
+            Value::from(Color::rgba(0, 255, 0, 128))
+        ];
+    color_hsl:
+        check_value ("<color>", "hsl(0deg 100% 50%)"), vec![
+            Value::from(Color::from((1.0, 0.0, 0.0)))
+        ];
+    color_hsl_hue:
+        check_value ("<color>", "hsl(240deg 100% 50%)"), vec![
+            Value::from(Color::rgba(0, 0, 255, 255))
+        ];
+    color_hwb_percent_alpha:
+        check_value ("<color>", "hwb(120 0% 0% / 50%)"), vec![
+            Value::from(Color::rgba(0, 255, 0, 128))
+        ];
+    color_hwb_percent:
+        check_value ("<color>", "hwb(240deg 20% 20%)"), vec![
+            Value::from(Color::rgba(51, 51, 204, 255))
+        ];
+    color_hwb_none:
+        check_value ("<color>", "hwb(none none 100%)"), vec![
+            Value::from(Color::rgba(0, 0, 0, 255))
+        ];
+    color_calc_channel:
+        check_value ("<color>", "rgb(calc((100 - 10) / 2 + 10) 0 0 / calc(25% * 2))"), vec![
+            Value::from(Color::rgba(55, 0, 0, 128))
+        ];
+    color_calc_hue:
+        check_value ("<color>", "hsl(calc(60deg * 2) 100% 50%)"), vec![
+            Value::from(Color::rgba(0, 255, 0, 255))
+        ];
+    color_hwb_alpha:
+        check_value ("<color>", "hwb(0deg 0% 0% / 0.5)"), vec![
+            Value::from(Color::from((1.0, 0.0, 0.0, 0.5)))
+        ];
     color_comma_list:
         check_value ("<color>#", "red, green, blue"), vec![
             Value::from(Color::rgba(255, 0, 0, 255)),
             Value::from(Color::rgba(0, 128, 0, 255)),
             Value::from(Color::rgba(0, 0, 255, 255)),
         ];
+    unicode_range:
+        check_value ("<unicode-range>#", "U+0000-00FF, u+4??, U+131"), vec![
+            Value::new_unicode_range(0x0, 0xFF),
+            Value::new_unicode_range(0x400, 0x4FF),
+            Value::new_unicode_range(0x131, 0x131),
+        ];
     universal:
         check_value ("*", "#ff0000"), vec![
             Value::from(Color::rgba(255, 0, 0, 255))
         ];
     alternative_keyword:
         check_value ("auto | <length>", "auto"), vec![
-            Value::from("auto")
+            Value::new_keyword("auto")
         ];
     alternative_value:
         check_value ("auto | <length>", "24px"), vec![
@@ -70,7 +277,7 @@ test_cases! {
         ];
     group_keyword:
         check_value ("(auto | <number>) | (<length> <length>)", "auto"), vec![
-            Value::from("auto"),
+            Value::new_keyword("auto"),
         ];
     group_number:
         check_value ("(auto | <number>) | (<length> <length>)", "24.0"), vec![
@@ -115,5 +322,322 @@ test_cases! {
         check_error "<length> <length>", "24px";
     too_many_values:
         check_error "<percentage>", "100% 100%";
+    angle_for_length_percentage:
+        check_error "<length-percentage>", "10deg";
+    length_for_angle_percentage:
+        check_error "<angle-percentage>", "10px";
+    angle_for_time_percentage:
+        check_error "<time-percentage>", "10deg";
+    number_for_integer:
+        check_error "<integer>", "1.5";
+    zero_fraction_for_integer:
+        check_error "<integer>", "5.0";
+    missing_slash:
+        check_error "<length> / <number>", "16px 1.5";
+    unexpected_slash:
+        check_error "<length> <number>", "16px / 1.5";
+    number_for_length:
+        check_error "<length>", "1.5";
+    integer_for_length:
+        check_error "<length>", "10";
+    integer_for_length_percentage:
+        check_error "<length-percentage>", "10";
+    zero_for_angle:
+        check_error "<angle>", "0";
+    length_for_number:
+        check_error "<number>", "10px";
+    percentage_for_number:
+        check_error "<number>", "50%";
+    exponent_for_integer:
+        check_error "<integer>", "1e3";
+    negative_length_in_range:
+        check_error "<length [0,∞]>", "-5px";
+    keyword_for_unicode_range:
+        check_error "<unicode-range>", "latin";
+    unicode_range_out_of_bounds:
+        check_error "<unicode-range>", "U+110000";
+
+}
+
+fn check_invalid_value(syntax: &str, input: &str) {
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+
+    let parsed_syntax = parse_syntax(syntax, SourceLocation::from_file("Test Input")).unwrap();
+
+    let result = parse_values(&parsed_syntax, &mut parser);
+    match result {
+        Ok(values) => panic!("Expected error, got Ok({:?})", values),
+        Err(error) => {
+            if let cssparser::ParseErrorKind::Custom(parse_error) = error.kind {
+                assert_eq!(parse_error.kind, ParseErrorKind::InvalidPropertyValue);
+            } else{
+                panic!("Expected details::ParseError, got {:?}", error)
+            }
+        }
+    }
+}
+
+test_cases! {
+    hwb_missing_blackness:
+        check_invalid_value "<color>", "hwb(120 0%)";
+    hwb_missing_whiteness:
+        check_invalid_value "<color>", "hwb(120)";
+    hwb_missing_alpha:
+        check_invalid_value "<color>", "hwb(120 0% 0% /)";
+    hwb_legacy_commas:
+        check_invalid_value "<color>", "hwb(120, 0%, 0%)";
+    trailing_comma:
+        check_invalid_value "<color>#", "red, blue,";
+    calc_mismatched_types:
+        check_invalid_value "<color>", "rgb(calc(10deg + 5) 0 0)";
+    calc_multiply_angles:
+        check_invalid_value "<color>", "hsl(calc(10deg * 2deg) 100% 50%)";
+    calc_divide_by_zero:
+        check_invalid_value "<color>", "rgb(calc(10 / 0) 0 0)";
+    integer_overflow:
+        check_invalid_value "<integer>", "99999999999";
+    integer_underflow:
+        check_invalid_value "<integer>", "-2147483649";
+}
+
+#[test]
+fn color_from_float_clamps() {
+    assert_eq!(Color::from((1.01, -0.1, 0.5, 2.0)), Color::rgba(255, 0, 128, 255));
+}
+
+test_cases! {
+    var_repeated:
+        check_value ("<length>+", "var(--undefined-gap, 4px) var(--undefined-gap, 4px)"), vec![
+            Value::from(Dimension::px(4.0)),
+            Value::from(Dimension::px(4.0)),
+        ];
+    var_between_literals:
+        check_value ("<length>+", "1px var(--undefined-gap, 2px) 3px"), vec![
+            Value::from(Dimension::px(1.0)),
+            Value::from(Dimension::px(2.0)),
+            Value::from(Dimension::px(3.0)),
+        ];
+    var_multiple_values:
+        check_value ("<length>+", "1px var(--undefined-gap, 2px 3px)"), vec![
+            Value::from(Dimension::px(1.0)),
+            Value::from(Dimension::px(2.0)),
+            Value::from(Dimension::px(3.0)),
+        ];
+    var_mixed_types:
+        check_value ("<length> solid <color>", "1px solid var(--undefined-color, red)"), vec![
+            Value::from(Dimension::px(1.0)),
+            Value::new_keyword("solid"),
+            Value::from(Color::rgba(255, 0, 0, 255)),
+        ];
+    var_comma_separated_fallback:
+        check_value ("*", "var(--undefined-list, 1px, 2px)"), vec![
+            Value::from(Dimension::px(1.0)),
+            Value::from(Dimension::px(2.0)),
+        ];
+}
+
+test_cases! {
+    keyword_ident:
+        check_value ("none | <length>", "none"), vec![Value::new_keyword("none")];
+    string_quoted:
+        check_value ("<string>", "\"none\""), vec![Value::from("none")];
+    keyword_or_string:
+        check_value ("none | <string>", "\"none\""), vec![Value::from("none")];
+    keyword_auto:
+        check_value ("auto | <length>", "auto"), vec![Value::new_keyword("auto")];
+    keyword_universal:
+        check_value ("*", "none \"none\""), vec![Value::new_keyword("none"), Value::from("none")];
+}
+
+test_cases! {
+    keyword_quoted:
+        check_error "none | <length>", "\"none\"";
+    string_ident:
+        check_error "<string>", "none";
+}
+
+test_cases! {
+    whitespace_single:
+        check_value ("<color>", "  red  "), vec![Value::from(Color::rgba(255, 0, 0, 255))];
+    whitespace_space_list:
+        check_value ("<length>+", "\n 1px   2px\t3px \n"), vec![
+            Value::from(Dimension::px(1.0)),
+            Value::from(Dimension::px(2.0)),
+            Value::from(Dimension::px(3.0)),
+        ];
+    whitespace_comma_list:
+        check_value ("<color>#", " red ,green ,  blue "), vec![
+            Value::from(Color::rgba(255, 0, 0, 255)),
+            Value::from(Color::rgba(0, 128, 0, 255)),
+            Value::from(Color::rgba(0, 0, 255, 255)),
+        ];
+}
+
+#[test]
+fn unexpected_token_location() {
+    let mut parser_input = cssparser::ParserInput::new("1px  2px ) 3px");
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let parsed_syntax = parse_syntax("<length>+", SourceLocation::from_file("Test Input")).unwrap();
+    let error = parse_values(&parsed_syntax, &mut parser).unwrap_err();
+    assert_eq!(error.location.column, 10);
+    if let cssparser::ParseErrorKind::Custom(error) = error.kind {
+        assert_eq!(error.kind, ParseErrorKind::InvalidPropertyValue);
+        assert_eq!(error.message, "Unexpected token CloseParenthesis");
+    } else {
+        panic!("Expected a custom error, got {:?}", error);
+    }
+}
+
+#[test]
+fn var_fallback_error_location() {
+    let mut parser_input = cssparser::ParserInput::new("1px var(--undefined-gap, 2px unknown(3px))");
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let parsed_syntax = parse_syntax("<length>+", SourceLocation::from_file("Test Input")).unwrap();
+    let error = parse_values(&parsed_syntax, &mut parser).unwrap_err();
+    assert_eq!((error.location.line, error.location.column), (0, 38));
+    if let cssparser::ParseErrorKind::Custom(error) = error.kind {
+        assert_eq!(error.kind, ParseErrorKind::UnknownFunction);
+    } else {
+        panic!("Expected a custom error, got {:?}", error);
+    }
+}
+
+#[test]
+fn invalid_hwb_error() {
+    let mut parser_input = cssparser::ParserInput::new("red HWB(120 0%)");
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let parsed_syntax = parse_syntax("<color>+", SourceLocation::from_file("Test Input")).unwrap();
+    let error = parse_values(&parsed_syntax, &mut parser).unwrap_err();
+    assert_eq!(error.location.column, 5);
+    if let cssparser::ParseErrorKind::Custom(error) = error.kind {
+        assert_eq!(error.kind, ParseErrorKind::InvalidPropertyValue);
+        assert_eq!(error.message, "Invalid color function hwb(), expected hue, whiteness, blackness and an optional alpha");
+    } else {
+        panic!("Expected a custom error, got {:?}", error);
+    }
+}
+
+#[test]
+fn range_error_message() {
+    let mut parser_input = cssparser::ParserInput::new("-5px");
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let parsed_syntax = parse_syntax("<length [0,∞]>", SourceLocation::from_file("Test Input")).unwrap();
+    let error = parse_values(&parsed_syntax, &mut parser).unwrap_err();
+    if let cssparser::ParseErrorKind::Custom(error) = error.kind {
+        assert_eq!(error.kind, ParseErrorKind::PropertyValueDoesNotMatchSyntax);
+        assert_eq!(error.message, "Value Dimension(-5 px) is outside of the range [0, ∞]");
+    } else {
+        panic!("Expected a custom error, got {:?}", error);
+    }
+}
+
+fn check_integer_range_error(input: (&str, &str), message: &str) {
+    let mut parser_input = cssparser::ParserInput::new(input.1);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let parsed_syntax = parse_syntax(input.0, SourceLocation::from_file("Test Input")).unwrap();
+    let error = parse_values(&parsed_syntax, &mut parser).unwrap_err();
+    if let cssparser::ParseErrorKind::Custom(error) = error.kind {
+        assert_eq!(error.message, message);
+    } else {
+        panic!("Expected a custom error, got {:?}", error);
+    }
+}
+
+test_cases! {
+    integer_overflow_message:
+        check_integer_range_error ("<integer>", "99999999999"), "Integer 99999999999 is out of range";
+    integer_underflow_message:
+        check_integer_range_error ("<integer>", "-2147483649"), "Integer -2147483649 is out of range";
+    integer_overflow_after_comment_message:
+        check_integer_range_error ("<integer>", "/**/2147483648"), "Integer 2147483648 is out of range";
+    integer_overflow_in_list_message:
+        check_integer_range_error ("<integer>+", "1 /**/ 99999999999"), "Integer 99999999999 is out of range";
+}
+
+#[test]
+fn lenient_trailing_comma() {
+    let parsed_syntax = parse_syntax("<color>#", SourceLocation::from_file("Test Input")).unwrap();
+    let options = ParserOptions { strict_trailing_commas: false, ..Default::default() };
+
+    let mut parser_input = cssparser::ParserInput::new("red, blue,");
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let values = parse_values_with_options(&parsed_syntax, &mut parser, &options).unwrap();
+    assert_eq!(values, vec![
+        Value::from(Color::rgba(255, 0, 0, 255)),
+        Value::from(Color::rgba(0, 0, 255, 255)),
+    ]);
+
+    // Only a single trailing comma is ignored.
+    let mut parser_input = cssparser::ParserInput::new("red, blue,,");
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    assert!(parse_values_with_options(&parsed_syntax, &mut parser, &options).is_err());
+}
+
+#[test]
+fn color_calc_folded() {
+    let parsed_syntax = parse_syntax("<color>", SourceLocation::from_file("Test Input")).unwrap();
+    let parse = |input: &str| {
+        let mut parser_input = cssparser::ParserInput::new(input);
+        let mut parser = cssparser::Parser::new(&mut parser_input);
+        parse_values(&parsed_syntax, &mut parser).unwrap()
+    };
+
+    assert_eq!(parse("rgb(calc(50 + 5) 0 0)"), parse("rgb(55 0 0)"));
+}
+
+fn parse_universal(input: &str) -> Vec<Value> {
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let parsed_syntax = parse_syntax("*", SourceLocation::from_file("Test Input")).unwrap();
+    parser.parse_entirely(|parser| parse_values(&parsed_syntax, parser)).unwrap()
+}
+
+fn check_css_round_trip(input: &str, expected: &str) {
+    let values = parse_universal(input);
+    let css: Vec<_> = values.iter().map(Value::to_css).collect();
+    let css = css.join(" ");
+    assert_eq!(css, expected);
+    assert_eq!(parse_universal(&css), values);
+}
+
+test_cases! {
+    css_round_trip_dimensions: check_css_round_trip "10px 1.5em 50% 90deg 2 0.5", "10px 1.5em 50% 90deg 2 0.5";
+    css_round_trip_strings: check_css_round_trip "auto 'quoted' url(x.png)", "auto \"quoted\" url(\"x.png\")";
+    css_round_trip_slash: check_css_round_trip "16px/1.5", "16px / 1.5";
+    css_round_trip_color: check_css_round_trip "red rgba(0, 0, 255, 50%)", "#ff0000 #0000ff80";
+    css_round_trip_modified: check_css_round_trip "modify-color(red add blue) modify-color(#000 set-rgb 1 2 3)", "modify-color(#ff0000 add #0000ff) modify-color(#000000 set-red 1 set-green 2 set-blue 3)";
+}
+
+#[test]
+fn css_round_trip_alpha() {
+    for alpha in 0..=255u8 {
+        let values = vec![Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(None, None, None, Some(alpha))))];
+        assert_eq!(parse_universal(&values[0].to_css()), values, "Alpha {} did not round-trip", alpha);
+    }
+}
+
+test_cases! {
+    function_uppercase:
+        check_value ("<color>", "MIX(black, white, 0.5)"), vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::mix(&Color::rgba(255, 255, 255, 255), 0.5)))
+        ];
+    function_mixed_case:
+        check_value ("<color>", "Modify-Color(black add white)"), vec![
+            Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::add(&Color::rgba(255, 255, 255, 255))))
+        ];
+}
+
+#[test]
+fn unknown_function() {
+    let mut parser_input = cssparser::ParserInput::new("TranslateX(10px)");
+    let mut parser = cssparser::Parser::new(&mut parser_input);
+    let parsed_syntax = parse_syntax("*", SourceLocation::from_file("Test Input")).unwrap();
 
+    let error = parse_values(&parsed_syntax, &mut parser).unwrap_err();
+    let cssparser::ParseErrorKind::Custom(parse_error) = error.kind else {
+        panic!("Expected details::ParseError, got {:?}", error);
+    };
+    assert_eq!(parse_error.kind, ParseErrorKind::UnknownFunction);
+    assert_eq!(parse_error.message, "Unknown function \"TranslateX\"");
 }
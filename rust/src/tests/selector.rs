@@ -3,6 +3,7 @@
 
 use crate::selector::*;
 use crate::value::Value;
+use crate::details::selectorparser::{SelectorParser, ParseRelative};
 
 #[test]
 fn combine_basic() {
@@ -65,3 +66,242 @@ fn combine_multiple() {
         SelectorPart::new_with_value(SelectorKind::Class, Value::from("class")),
     ]));
 }
+
+#[test]
+fn specificity() {
+    let selector = Selector::from_parts(&[
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("type")),
+        SelectorPart::new_with_value(SelectorKind::Class, Value::from("class")),
+        SelectorPart::new_with_value(SelectorKind::PseudoClass, Value::from("hovered")),
+        SelectorPart::new_with_empty(SelectorKind::DescendantCombinator),
+        SelectorPart::new_with_value(SelectorKind::Id, Value::from("id")),
+    ]);
+    assert_eq!(selector.specificity(), Specificity(1, 2, 1));
+
+    let selector = Selector::from_parts(&[
+        SelectorPart::new_with_empty(SelectorKind::AnyElement),
+    ]);
+    assert_eq!(selector.specificity(), Specificity(0, 0, 0));
+    assert_eq!(selector.complexity(), 1);
+    assert_eq!(Selector::new().complexity(), 0);
+
+    assert!(Specificity(1, 0, 0) > Specificity(0, 10, 10));
+    assert!(Specificity(0, 1, 0) > Specificity(0, 0, 10));
+}
+
+#[test]
+fn matches() {
+    let mut parent = ElementContext::new("window");
+    parent.classes.push(String::from("main"));
+
+    let mut element = ElementContext::new("button");
+    element.classes.push(String::from("primary"));
+    element.pseudo_classes.push(String::from("hovered"));
+    element.attributes.push((String::from("display"), String::from("icon-only")));
+    element.parent = Some(Box::new(ElementContext {
+        type_name: String::from("toolbar"),
+        parent: Some(Box::new(parent)),
+        ..Default::default()
+    }));
+
+    let matching = [
+        Selector::from_parts(&[
+            SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+        ]),
+        Selector::from_parts(&[
+            SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+            SelectorPart::new_with_value(SelectorKind::Class, Value::from("primary")),
+            SelectorPart::new_with_value(SelectorKind::PseudoClass, Value::from("hovered")),
+        ]),
+        Selector::from_parts(&[
+            SelectorPart::new_with_value(SelectorKind::Class, Value::from("main")),
+            SelectorPart::new_with_empty(SelectorKind::DescendantCombinator),
+            SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+        ]),
+        Selector::from_parts(&[
+            SelectorPart::new_with_value(SelectorKind::Type, Value::from("toolbar")),
+            SelectorPart::new_with_empty(SelectorKind::ChildCombinator),
+            SelectorPart {
+                kind: SelectorKind::Attribute,
+                value: SelectorValue::Attribute {
+                    name: String::from("display"),
+                    operator: AttributeOperator::DashMatch,
+                    value: Value::from("icon"),
+                }
+            },
+        ]),
+    ];
+
+    for selector in matching {
+        assert!(selector.matches(&element), "{:?} should match", selector);
+    }
+
+    let not_matching = [
+        Selector::from_parts(&[
+            SelectorPart::new_with_value(SelectorKind::Type, Value::from("toolbutton")),
+        ]),
+        Selector::from_parts(&[
+            SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+            SelectorPart::new_with_value(SelectorKind::Id, Value::from("id")),
+        ]),
+        Selector::from_parts(&[
+            SelectorPart::new_with_value(SelectorKind::Class, Value::from("main")),
+            SelectorPart::new_with_empty(SelectorKind::ChildCombinator),
+            SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+        ]),
+        Selector::from_parts(&[
+            SelectorPart::new_with_empty(SelectorKind::DocumentRoot),
+        ]),
+    ];
+
+    for selector in not_matching {
+        assert!(!selector.matches(&element), "{:?} should not match", selector);
+    }
+}
+
+#[test]
+fn matches_dir_lang() {
+    let mut parent = ElementContext::new("window");
+    parent.attributes.push((String::from("lang"), String::from("en-US")));
+    parent.attributes.push((String::from("dir"), String::from("rtl")));
+
+    let mut element = ElementContext::new("button");
+    element.parent = Some(Box::new(parent));
+
+    let pseudo_class = |name: &str, argument: &str| Selector::from_parts(&[
+        SelectorPart::new_with_argument(SelectorKind::PseudoClass, Value::from(name), argument),
+    ]);
+
+    assert!(pseudo_class("lang", "en").matches(&element));
+    assert!(pseudo_class("lang", "en-us").matches(&element));
+    assert!(!pseudo_class("lang", "nl").matches(&element));
+    assert!(pseudo_class("dir", "rtl").matches(&element));
+    assert!(!pseudo_class("dir", "ltr").matches(&element));
+    assert!(pseudo_class("dir", "ltr").matches(&ElementContext::new("button")));
+}
+
+#[test]
+fn iter_parts() {
+    let parts = [
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+        SelectorPart::new_with_empty(SelectorKind::ChildCombinator),
+        SelectorPart::new_with_value(SelectorKind::Class, Value::from("flat")),
+    ];
+    let selector = Selector::from_parts(&parts);
+
+    let borrowed: Vec<&SelectorPart> = selector.iter_parts().collect();
+    assert_eq!(borrowed, parts.iter().collect::<Vec<_>>());
+    assert_eq!(Selector::new().iter_parts().count(), 0);
+}
+
+#[test]
+fn build_and_serialize() {
+    let mut selector = Selector::new();
+    selector.push_with_value(SelectorKind::Type, Value::from("window"));
+    selector.push_with_value(SelectorKind::Class, Value::from("main"));
+    selector.push_with_empty(SelectorKind::ChildCombinator);
+    selector.push_with_value(SelectorKind::Type, Value::from("button"));
+    selector.push_with_value(SelectorKind::PseudoClass, Value::from("hovered"));
+    selector.push(SelectorPart::new_with_argument(SelectorKind::PseudoClass, Value::from("lang"), "en"));
+    selector.push(SelectorPart {
+        kind: SelectorKind::Attribute,
+        value: SelectorValue::Attribute {
+            name: String::from("flat"),
+            operator: AttributeOperator::Equals,
+            value: Value::from("true"),
+        }
+    });
+    selector.push_with_empty(SelectorKind::DescendantCombinator);
+    selector.push_with_value(SelectorKind::Id, Value::from("label"));
+
+    assert_eq!(selector.validate(), Ok(()));
+
+    let css = selector.to_css();
+    assert_eq!(css, "window.main > button:hovered:lang(en)[flat=\"true\"] #label");
+
+    let parser = SelectorParser::default();
+    let mut parser_input = cssparser::ParserInput::new(&css);
+    let mut css_parser = cssparser::Parser::new(&mut parser_input);
+    let parsed = parser.parse(&mut css_parser, ParseRelative::No).unwrap();
+    assert_eq!(parsed, vec![selector]);
+}
+
+#[test]
+fn validate_invalid() {
+    assert!(Selector::new().validate().is_err());
+
+    let adjacent = Selector::from_parts(&[
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("window")),
+        SelectorPart::new_with_empty(SelectorKind::ChildCombinator),
+        SelectorPart::new_with_empty(SelectorKind::DescendantCombinator),
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+    ]);
+    assert_eq!(adjacent.validate(), Err(String::from("Adjacent combinators at position 2")));
+
+    let leading = Selector::from_parts(&[
+        SelectorPart::new_with_empty(SelectorKind::ChildCombinator),
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+    ]);
+    assert!(leading.validate().is_err());
+
+    let trailing = Selector::from_parts(&[
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+        SelectorPart::new_with_empty(SelectorKind::DescendantCombinator),
+    ]);
+    assert!(trailing.validate().is_err());
+}
+
+#[test]
+fn kind_names() {
+    assert_eq!(SelectorKind::Type.as_str(), "type");
+    assert_eq!(SelectorKind::PseudoClass.as_str(), "pseudo-class");
+    assert_eq!(SelectorKind::ChildCombinator.to_string(), "child-combinator");
+    assert_eq!(SelectorKind::PseudoElement.as_str(), "pseudo-element");
+    assert_eq!(format!("{}", SelectorKind::AnyElement), "any-element");
+}
+
+#[test]
+fn negation() {
+    let parse = |input: &str| {
+        let parser = SelectorParser::default();
+        let mut parser_input = cssparser::ParserInput::new(input);
+        let mut css_parser = cssparser::Parser::new(&mut parser_input);
+        parser.parse(&mut css_parser, ParseRelative::No).unwrap().remove(0)
+    };
+
+    let mut element = ElementContext::new("button");
+    element.classes.push(String::from("primary"));
+    element.parent = Some(Box::new(ElementContext::new("toolbar")));
+
+    assert!(parse("button:not(.flat)").matches(&element));
+    assert!(!parse("button:not(.primary)").matches(&element));
+    assert!(parse(":not(.flat, .default)").matches(&element));
+    assert!(!parse(":not(.flat, .primary)").matches(&element));
+    assert!(!parse(":not(toolbar > button)").matches(&element));
+    assert!(parse(":not(window > button)").matches(&element));
+
+    let selector = parse("button:not(.flat, #main)");
+    assert_eq!(selector.specificity(), Specificity(1, 0, 1));
+    assert_eq!(selector.to_css(), "button:not(.flat, #main)");
+    assert_eq!(parse(&selector.to_css()), selector);
+}
+
+#[test]
+fn pseudo_elements() {
+    let parser = SelectorParser::default();
+    for input in ["button::before", "slider::part(track thumb)", "::slotted(button.flat)"] {
+        let mut parser_input = cssparser::ParserInput::new(input);
+        let mut css_parser = cssparser::Parser::new(&mut parser_input);
+        let parsed = parser.parse(&mut css_parser, ParseRelative::No).unwrap();
+        assert_eq!(parsed[0].to_css(), input);
+        assert_eq!(parsed[0].complexity(), 1);
+    }
+
+    let selector = Selector::from_parts(&[
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("slider")),
+        SelectorPart::new_with_argument(SelectorKind::PseudoElement, Value::from("part"), "track"),
+    ]);
+    assert_eq!(selector.specificity(), Specificity(0, 0, 2));
+    // Pseudo-elements are not elements, so they never match one.
+    assert!(!selector.matches(&ElementContext::new("slider")));
+}
@@ -6,7 +6,7 @@ use crate::value::Value;
 use crate::details::selectorparser::{SelectorParser, ParseRelative};
 
 fn check_selector(input: &str, expected: Vec<Selector>, relative: ParseRelative) {
-    let parser = SelectorParser{};
+    let parser = SelectorParser::default();
 
     let mut parser_input = cssparser::ParserInput::new(input);
     let mut css_parser = cssparser::Parser::new(&mut parser_input);
@@ -83,6 +83,22 @@ test_cases! {
             ])
         ];
 
+    pseudoclass_dir:
+        check_selector_toplevel "type:dir(rtl)", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("type")),
+                SelectorPart::new_with_argument(SelectorKind::PseudoClass, Value::from("dir"), "rtl"),
+            ])
+        ];
+
+    pseudoclass_lang:
+        check_selector_toplevel ".class:lang(\"en-US\")", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Class, Value::from("class")),
+                SelectorPart::new_with_argument(SelectorKind::PseudoClass, Value::from("lang"), "en-US"),
+            ])
+        ];
+
     nested:
         check_selector_nested "type", vec![
             Selector::from_parts(&[
@@ -117,6 +133,46 @@ test_cases! {
             ])
         ];
 
+    all_with_class:
+        check_selector_toplevel "*.foo", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_empty(SelectorKind::AnyElement),
+                SelectorPart::new_with_value(SelectorKind::Class, Value::from("foo")),
+            ])
+        ];
+
+    all_with_attribute:
+        check_selector_toplevel "*[test]", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_empty(SelectorKind::AnyElement),
+                SelectorPart {
+                    kind: SelectorKind::Attribute,
+                    value: SelectorValue::Attribute {
+                        name: String::from("test"),
+                        operator: AttributeOperator::Exists,
+                        value: Value::empty(),
+                    }
+                },
+            ])
+        ];
+
+    all_descendant_class:
+        check_selector_toplevel "type *.foo", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("type")),
+                SelectorPart::new_with_empty(SelectorKind::DescendantCombinator),
+                SelectorPart::new_with_empty(SelectorKind::AnyElement),
+                SelectorPart::new_with_value(SelectorKind::Class, Value::from("foo")),
+            ])
+        ];
+
+    any_namespace_without_default:
+        check_selector_toplevel "*|rect", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("rect")),
+            ])
+        ];
+
     attribute_exists:
         check_selector_toplevel "type[test]", vec![
             Selector::from_parts(&[
@@ -175,3 +231,173 @@ test_cases! {
             ])
         ]
 }
+
+fn check_relative_parent((input, relative): (&str, ParseRelative), expected: bool) {
+    let parser = SelectorParser::default();
+
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut css_parser = cssparser::Parser::new(&mut parser_input);
+
+    let result = parser.parse(&mut css_parser, relative).unwrap();
+    assert!(result.iter().all(|selector| selector.has_relative_parent() == expected));
+}
+
+fn check_complexity(input: &str, expected: Vec<usize>) {
+    let parser = SelectorParser::default();
+
+    let mut parser_input = cssparser::ParserInput::new(input);
+    let mut css_parser = cssparser::Parser::new(&mut parser_input);
+
+    let result = parser.parse(&mut css_parser, ParseRelative::No).unwrap();
+    assert_eq!(result.iter().map(Selector::complexity).collect::<Vec<_>>(), expected);
+}
+
+test_cases! {
+    complexity_single: check_complexity "type.class:hovered", vec![1];
+    complexity_descendant: check_complexity "window type .class", vec![3];
+    complexity_child: check_complexity "window > type #id", vec![3];
+    complexity_negation: check_complexity "type:not(window > .class)", vec![1];
+    complexity_list: check_complexity "type, window > type, a b > c d", vec![1, 2, 4];
+}
+
+// Returns whether the pseudo-class is an active-or-hover state and whether it
+// is a user action state.
+fn check_pseudo_class_state(input: &str, expected: (bool, bool)) {
+    use selectors::parser::{NonTSPseudoClass, Parser};
+
+    let parser = SelectorParser::default();
+    let location = cssparser::SourceLocation { line: 0, column: 1 };
+    let pseudo_class = parser.parse_non_ts_pseudo_class(location, input.into()).unwrap();
+    assert_eq!((pseudo_class.is_active_or_hover(), pseudo_class.is_user_action_state()), expected);
+}
+
+test_cases! {
+    pseudo_class_state_hover: check_pseudo_class_state "hover", (true, true);
+    pseudo_class_state_active: check_pseudo_class_state "active", (true, true);
+    pseudo_class_state_uppercase: check_pseudo_class_state "HOVER", (true, true);
+    pseudo_class_state_focus: check_pseudo_class_state "focus", (false, true);
+    pseudo_class_state_checked: check_pseudo_class_state "checked", (false, false);
+    pseudo_class_state_disabled: check_pseudo_class_state "disabled", (false, false);
+    pseudo_class_state_hovered: check_pseudo_class_state "hovered", (false, false);
+}
+
+test_cases! {
+    relative_parent_toplevel: check_relative_parent ("type .class", ParseRelative::No), false;
+    relative_parent_nested_implicit: check_relative_parent ("type", ParseRelative::Nested), true;
+    relative_parent_nested_explicit: check_relative_parent ("&.class:hovered", ParseRelative::Nested), true;
+    relative_parent_nested_multiple: check_relative_parent (".class & & &", ParseRelative::Nested), true;
+}
+
+#[test]
+fn pseudo_element_not_last() {
+    // A pseudo-element can only appear at the end of a selector.
+    for input in ["type::before.class", "type::before type", "type::after > type", "type::selection::before"] {
+        let parser = SelectorParser::default();
+
+        let mut parser_input = cssparser::ParserInput::new(input);
+        let mut css_parser = cssparser::Parser::new(&mut parser_input);
+
+        let result = parser.parse(&mut css_parser, ParseRelative::No);
+        assert!(result.is_err(), "{} should not parse, got {:?}", input, result.ok().unwrap());
+    }
+}
+
+fn disabled_attribute() -> SelectorPart {
+    SelectorPart {
+        kind: SelectorKind::Attribute,
+        value: SelectorValue::Attribute {
+            name: String::from("disabled"),
+            operator: AttributeOperator::Exists,
+            value: Value::empty(),
+        }
+    }
+}
+
+// An attribute selector without type matches any element, which is implied
+// rather than added as an explicit AnyElement part.
+test_cases! {
+    attribute_only:
+        check_selector_toplevel "[disabled]", vec![
+            Selector::from_parts(&[disabled_attribute()])
+        ];
+
+    attribute_only_descendant:
+        check_selector_toplevel "window [disabled] > button", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("window")),
+                SelectorPart::new_with_empty(SelectorKind::DescendantCombinator),
+                disabled_attribute(),
+                SelectorPart::new_with_empty(SelectorKind::ChildCombinator),
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+            ])
+        ];
+
+    attribute_only_explicit_universal:
+        check_selector_toplevel "*[disabled]", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_empty(SelectorKind::AnyElement),
+                disabled_attribute(),
+            ])
+        ];
+}
+
+fn class_selector(name: &str) -> Selector {
+    Selector::from_parts(&[SelectorPart::new_with_value(SelectorKind::Class, Value::from(name))])
+}
+
+test_cases! {
+    negation:
+        check_selector_toplevel "button:not(.flat)", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+                SelectorPart::new_with_selectors(SelectorKind::Negation, &[class_selector("flat")]),
+            ])
+        ];
+
+    negation_multiple:
+        check_selector_toplevel ":not(.a, window > .b)", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_selectors(SelectorKind::Negation, &[
+                    class_selector("a"),
+                    Selector::from_parts(&[
+                        SelectorPart::new_with_value(SelectorKind::Type, Value::from("window")),
+                        SelectorPart::new_with_empty(SelectorKind::ChildCombinator),
+                        SelectorPart::new_with_value(SelectorKind::Class, Value::from("b")),
+                    ]),
+                ]),
+            ])
+        ];
+
+    pseudo_element:
+        check_selector_toplevel "button::before", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("button")),
+                SelectorPart::new_with_value(SelectorKind::PseudoElement, Value::from("before")),
+            ])
+        ];
+
+    pseudo_element_part:
+        check_selector_toplevel "slider::part(track)", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("slider")),
+                SelectorPart::new_with_argument(SelectorKind::PseudoElement, Value::from("part"), "track"),
+            ])
+        ];
+
+    pseudo_element_part_multiple:
+        check_selector_toplevel "window > slider::part(track thumb)", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("window")),
+                SelectorPart::new_with_empty(SelectorKind::ChildCombinator),
+                SelectorPart::new_with_value(SelectorKind::Type, Value::from("slider")),
+                SelectorPart::new_with_argument(SelectorKind::PseudoElement, Value::from("part"), "track thumb"),
+            ])
+        ];
+
+    pseudo_element_slotted:
+        check_selector_toplevel "::slotted(button.flat)", vec![
+            Selector::from_parts(&[
+                SelectorPart::new_with_argument(SelectorKind::PseudoElement, Value::from("slotted"), "button.flat"),
+            ])
+        ];
+}
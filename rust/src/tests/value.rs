@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: LGPL-2.1-only OR LGPL-3.0-only OR LicenseRef-KDE-Accepted-LGPL
+// SPDX-FileCopyrightText: 2026 Arjen Hiemstra <ahiemstra@heimr.nl>
+
+use crate::value::{Color, ColorOperation, Dimension, DimensionClass, Unit, Value, ValueData};
+
+fn test_scaled_dimension(input: (Dimension, f32), expected: Dimension) {
+    assert_eq!(input.0.scaled(input.1), expected);
+}
+
+fn test_scaled_value(input: (Value, f32), expected: Value) {
+    assert_eq!(input.0.scaled(input.1), expected);
+}
+
+test_cases! {
+    scaled_px: test_scaled_dimension (Dimension::px(10.0), 1.5), Dimension { value: 15.0, unit: Unit::Px };
+    scaled_em: test_scaled_dimension (Dimension { value: 2.0, unit: Unit::Em }, 0.5), Dimension { value: 1.0, unit: Unit::Em };
+    scaled_value_dimension: test_scaled_value (Value::from(Dimension::px(10.0)), 2.0), Value::from(Dimension::px(20.0));
+    scaled_value_color: test_scaled_value (Value::from(Color::rgba(255, 0, 0, 255)), 2.0), Value::from(Color::rgba(255, 0, 0, 255));
+}
+
+fn test_resolve(color: Color, expected: Option<(u8, u8, u8, u8)>) {
+    assert_eq!(color.resolve(), expected);
+}
+
+test_cases! {
+    resolve_rgba: test_resolve Color::rgba(1, 2, 3, 4), Some((1, 2, 3, 4));
+    resolve_empty: test_resolve Color::empty(), None;
+    resolve_custom: test_resolve Color::custom(String::from("palette"), vec![]), None;
+    resolve_set: test_resolve Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(Some(10), None, None, Some(127))), Some((10, 0, 0, 127));
+    resolve_add: test_resolve Color::modified(&Color::rgba(200, 10, 0, 255), ColorOperation::add(&Color::rgba(100, 10, 0, 0))), Some((255, 20, 0, 255));
+    resolve_subtract: test_resolve Color::modified(&Color::rgba(200, 10, 0, 255), ColorOperation::subtract(&Color::rgba(100, 20, 0, 255))), Some((100, 0, 0, 255));
+    resolve_multiply: test_resolve Color::modified(&Color::rgba(255, 128, 0, 255), ColorOperation::multiply(&Color::rgba(128, 255, 255, 255))), Some((128, 128, 0, 255));
+    resolve_mix: test_resolve Color::modified(&Color::rgba(0, 0, 0, 0), ColorOperation::mix(&Color::rgba(255, 255, 255, 255), 0.5)), Some((128, 128, 128, 128));
+    resolve_nested_custom: test_resolve Color::modified(&Color::custom(String::from("palette"), vec![]), ColorOperation::set(Some(10), None, None, None)), None;
+}
+
+#[test]
+fn eq_resolved() {
+    let red = Color::rgba(255, 0, 0, 255);
+    let mixed = Color::modified(&Color::rgba(255, 0, 0, 255), ColorOperation::mix(&Color::rgba(255, 0, 0, 255), 0.5));
+    let set = Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(Some(255), None, None, None));
+
+    assert_ne!(mixed, red);
+    assert!(mixed.eq_resolved(&red));
+    assert_ne!(set, red);
+    assert!(set.eq_resolved(&red));
+    assert!(red.eq_resolved(&Color::from((1.0, 0.0, 0.0))));
+    assert!(!red.eq_resolved(&Color::rgba(255, 0, 0, 128)));
+
+    let custom = Color::custom(String::from("palette"), vec![String::from("highlight")]);
+    assert!(custom.eq_resolved(&custom.clone()));
+    assert!(!custom.eq_resolved(&Color::custom(String::from("palette"), vec![String::from("text")])));
+    assert!(!custom.eq_resolved(&red));
+}
+
+#[test]
+fn relative_luminance() {
+    assert_eq!(Color::rgba(0, 0, 0, 255).relative_luminance(), Some(0.0));
+    assert_eq!(Color::rgba(255, 255, 255, 255).relative_luminance(), Some(1.0));
+    assert!((Color::rgba(255, 0, 0, 255).relative_luminance().unwrap() - 0.2126).abs() < 0.0001);
+    assert_eq!(Color::custom(String::from("palette"), vec![]).relative_luminance(), None);
+}
+
+fn check_unit_class(input: Unit, expected: DimensionClass) {
+    assert_eq!(Dimension { value: 1.0, unit: input }.unit_class(), expected);
+}
+
+test_cases! {
+    unit_class_number: check_unit_class Unit::Number, DimensionClass::Number;
+    unit_class_length: check_unit_class Unit::Rem, DimensionClass::Length;
+    unit_class_percentage: check_unit_class Unit::Percent, DimensionClass::Percentage;
+    unit_class_angle: check_unit_class Unit::Radians, DimensionClass::Angle;
+    unit_class_time: check_unit_class Unit::Milliseconds, DimensionClass::Time;
+    unit_class_resolution: check_unit_class Unit::Dpi, DimensionClass::Resolution;
+    unit_class_flex: check_unit_class Unit::Fr, DimensionClass::Flex;
+    unit_class_unsupported: check_unit_class Unit::Unsupported, DimensionClass::Unknown;
+    unit_class_other: check_unit_class Unit::Other(String::from("foo")), DimensionClass::Unknown;
+}
+
+#[test]
+fn percent_display() {
+    let dimension = Dimension { value: 0.5, unit: Unit::Percent };
+    assert_eq!(dimension.to_string(), "Dimension(50 %)");
+}
+
+fn check_to_css(input: Value, expected: &str) {
+    assert_eq!(input.to_css(), expected);
+}
+
+test_cases! {
+    to_css_px: check_to_css Value::from(Dimension::px(10.0)), "10px";
+    to_css_percent: check_to_css Value::from(Dimension { value: 0.5, unit: Unit::Percent }), "50%";
+    to_css_degrees: check_to_css Value::from(Dimension { value: 90.0, unit: Unit::Degrees }), "90deg";
+    to_css_fr: check_to_css Value::from(Dimension { value: 2.0, unit: Unit::Fr }), "2fr";
+    to_css_number: check_to_css Value::from(1.5), "1.5";
+    to_css_integer: check_to_css Value::from(-3), "-3";
+    to_css_string: check_to_css Value::from("a \"b\""), "\"a \\\"b\\\"\"";
+    to_css_keyword: check_to_css Value::new_keyword("auto"), "auto";
+    to_css_url: check_to_css Value::new_url("icons/x.svg"), "url(\"icons/x.svg\")";
+    to_css_image: check_to_css Value::new_image("icons/x.svg"), "url(\"icons/x.svg\")";
+    to_css_unicode_range: check_to_css Value::new_unicode_range(0x400, 0x4FF), "U+400-4FF";
+    to_css_color: check_to_css Value::from(Color::rgba(255, 0, 128, 255)), "#ff0080";
+    to_css_color_alpha: check_to_css Value::from(Color::rgba(255, 0, 128, 16)), "#ff008010";
+    to_css_color_custom: check_to_css Value::from(Color::custom(String::from("palette"), vec![String::from("highlight")])), "custom-color(\"palette\", \"highlight\")";
+    to_css_color_mix: check_to_css Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::mix(&Color::rgba(255, 255, 255, 255), 0.25))), "mix(#000000, #ffffff, 0.25)";
+    to_css_color_set: check_to_css Value::from(Color::modified(&Color::rgba(0, 0, 0, 255), ColorOperation::set(Some(10), None, None, None))), "modify-color(#000000 set-red 10)";
+}
+
+#[test]
+fn new_image() {
+    let value = Value::new_image("icons/x.svg");
+    assert_eq!(value.data, ValueData::Image(String::from("icons/x.svg")));
+    assert_ne!(value, Value::new_url("icons/x.svg"));
+    assert!(!value.is_empty());
+}
+
+#[test]
+fn empty() {
+    assert!(Value::empty().is_empty());
+    assert!(Value::empty_ref().is_empty());
+    assert!(std::ptr::eq(Value::empty_ref(), Value::empty_ref()));
+    assert!(!Value::from(0).is_empty());
+    assert!(!Value::from("").is_empty());
+}
+
+#[test]
+fn empty_ref_outlives_other_values() {
+    let empty = Value::empty_ref();
+    for index in 0..16 {
+        let value = Value::from(format!("value {}", index).as_str());
+        assert!(!value.is_empty());
+    }
+    assert!(empty.is_empty());
+    assert_eq!(empty, &Value::empty());
+}
@@ -3,6 +3,8 @@
 
 use crate::details::identifier::Identifier;
 
+use cssparser::color::clamp_unit_f32;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ColorOperation {
     Set { r: Option<u8>, g: Option<u8>, b: Option<u8>, a: Option<u8> },
@@ -29,7 +31,9 @@ impl ColorOperation {
         ColorOperation::Set { r, g, b, a }
     }
 
+    /// Mix with `color` by `amount`, which is clamped to [0, 1]. NaN is treated as 0.
     pub fn mix(color: &Color, amount: f32) -> ColorOperation {
+        let amount = if amount.is_nan() { 0.0 } else { amount.clamp(0.0, 1.0) };
         ColorOperation::Mix { other: Box::new(color.clone()), amount }
     }
 
@@ -89,6 +93,111 @@ impl Color {
             }
         }
     }
+
+    /// Resolve this color to its red, green, blue and alpha channels, applying
+    /// any modifications.
+    ///
+    /// Returns None if this color is empty or depends on a custom color, as
+    /// those can only be resolved by the application.
+    pub fn resolve(&self) -> Option<(u8, u8, u8, u8)> {
+        match &self.data {
+            ColorData::Empty | ColorData::Custom { .. } => None,
+            ColorData::Rgba { r, g, b, a } => Some((*r, *g, *b, *a)),
+            ColorData::Modified { color, operation } => {
+                let (r, g, b, a) = color.resolve()?;
+                match operation {
+                    ColorOperation::Set { r: set_r, g: set_g, b: set_b, a: set_a } => {
+                        Some((set_r.unwrap_or(r), set_g.unwrap_or(g), set_b.unwrap_or(b), set_a.unwrap_or(a)))
+                    }
+                    ColorOperation::Add { other } => {
+                        let (other_r, other_g, other_b, _) = other.resolve()?;
+                        Some((r.saturating_add(other_r), g.saturating_add(other_g), b.saturating_add(other_b), a))
+                    }
+                    ColorOperation::Subtract { other } => {
+                        let (other_r, other_g, other_b, _) = other.resolve()?;
+                        Some((r.saturating_sub(other_r), g.saturating_sub(other_g), b.saturating_sub(other_b), a))
+                    }
+                    ColorOperation::Multiply { other } => {
+                        let (other_r, other_g, other_b, _) = other.resolve()?;
+                        let multiply = |first: u8, second: u8| ((first as u16 * second as u16) / 255) as u8;
+                        Some((multiply(r, other_r), multiply(g, other_g), multiply(b, other_b), a))
+                    }
+                    ColorOperation::Mix { other, amount } => {
+                        let (other_r, other_g, other_b, other_a) = other.resolve()?;
+                        let mix = |first: u8, second: u8| (first as f32 + (second as f32 - first as f32) * amount).round() as u8;
+                        Some((mix(r, other_r), mix(g, other_g), mix(b, other_b), mix(a, other_a)))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether this color and `other` resolve to the same channels.
+    ///
+    /// Unlike `==`, which compares how colors are defined, this considers a
+    /// modified color equal to the color it resolves to. Colors that cannot be
+    /// resolved are compared structurally.
+    pub fn eq_resolved(&self, other: &Color) -> bool {
+        match (self.resolve(), other.resolve()) {
+            (Some(first), Some(second)) => first == second,
+            (None, None) => self == other,
+            _ => false,
+        }
+    }
+
+    /// Serialize this color to CSS syntax that can be parsed again.
+    ///
+    /// Colors are written as hex colors, modified and custom colors use the
+    /// functions that create them. An empty color is written as an empty string.
+    pub fn to_css(&self) -> String {
+        match &self.data {
+            ColorData::Empty => String::new(),
+            ColorData::Rgba { r, g, b, a: 255 } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+            ColorData::Rgba { r, g, b, a } => format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a),
+            ColorData::Custom { source, arguments } => {
+                let mut result = String::from("custom-color(");
+                for (index, argument) in std::iter::once(source).chain(arguments).enumerate() {
+                    if index > 0 {
+                        result.push_str(", ");
+                    }
+                    let _ = cssparser::serialize_string(argument, &mut result);
+                }
+                result.push(')');
+                result
+            }
+            ColorData::Modified { color, operation } => match operation {
+                ColorOperation::Set { r, g, b, a } => {
+                    let mut result = format!("modify-color({}", color.to_css());
+                    for (name, channel) in [("set-red", r), ("set-green", g), ("set-blue", b)] {
+                        if let Some(channel) = channel {
+                            result += &format!(" {} {}", name, channel);
+                        }
+                    }
+                    if let Some(alpha) = a {
+                        result += &format!(" set-alpha {}", *alpha as f32 / 255.0);
+                    }
+                    result.push(')');
+                    result
+                }
+                ColorOperation::Add { other } => format!("modify-color({} add {})", color.to_css(), other.to_css()),
+                ColorOperation::Subtract { other } => format!("modify-color({} subtract {})", color.to_css(), other.to_css()),
+                ColorOperation::Multiply { other } => format!("modify-color({} multiply {})", color.to_css(), other.to_css()),
+                ColorOperation::Mix { other, amount } => format!("mix({}, {}, {})", color.to_css(), other.to_css(), amount),
+            },
+        }
+    }
+
+    /// The relative luminance of this color, between 0.0 and 1.0.
+    ///
+    /// Returns None if this color cannot be resolved.
+    pub fn relative_luminance(&self) -> Option<f32> {
+        let (r, g, b, _) = self.resolve()?;
+        let linear = |channel: u8| {
+            let channel = channel as f32 / 255.0;
+            if channel <= 0.04045 { channel / 12.92 } else { ((channel + 0.055) / 1.055).powf(2.4) }
+        };
+        Some(0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b))
+    }
 }
 
 impl From<(u8, u8, u8)> for Color {
@@ -111,20 +220,26 @@ impl From<(u8, u8, u8, f32)> for Color {
                 r: value.0,
                 g: value.1,
                 b: value.2,
-                a: (value.3 * 255.0) as u8
+                a: clamp_unit_f32(value.3)
             }
         }
     }
 }
 
+impl From<(f32, f32, f32)> for Color {
+    fn from(value: (f32, f32, f32)) -> Self {
+        Self::from((value.0, value.1, value.2, 1.0))
+    }
+}
+
 impl From<(f32, f32, f32, f32)> for Color {
     fn from(value: (f32, f32, f32, f32)) -> Self {
         Self {
             data: ColorData::Rgba {
-                r: (value.0 * 255.0) as u8,
-                g: (value.1 * 255.0) as u8,
-                b: (value.2 * 255.0) as u8,
-                a: (value.3 * 255.0) as u8,
+                r: clamp_unit_f32(value.0),
+                g: clamp_unit_f32(value.1),
+                b: clamp_unit_f32(value.2),
+                a: clamp_unit_f32(value.3),
             }
         }
     }
@@ -160,16 +275,26 @@ pub enum Unit {
     Em,
     Rem,
     Pt,
+    /// A percentage, stored as a fraction so that `50%` has a value of `0.5`.
     Percent,
     Degrees,
     Radians,
     Seconds,
     Milliseconds,
+    Dppx,
+    Dpi,
+    Dpcm,
+    /// A fraction of the free space in a grid container.
+    Fr,
+    /// A unit that is not known, with the unit as it was written.
+    ///
+    /// This is only produced when parsing with strict units disabled.
+    Other(String),
 }
 
 impl Unit {
     pub fn parse(input: &str) -> Unit {
-        match input {
+        match input.to_ascii_lowercase().as_str() {
             "px" => Unit::Px,
             "em" => Unit::Em,
             "rem" => Unit::Rem,
@@ -179,9 +304,13 @@ impl Unit {
             "rad" => Unit::Radians,
             "s" => Unit::Seconds,
             "ms" => Unit::Milliseconds,
+            "dppx" | "x" => Unit::Dppx,
+            "dpi" => Unit::Dpi,
+            "dpcm" => Unit::Dpcm,
+            "fr" => Unit::Fr,
             "mm"
             | "cm"
-            | "Q"
+            | "q"
             | "in"
             | "pc"
             | "vh"
@@ -210,10 +339,29 @@ impl ToString for Unit {
             Unit::Radians => String::from("rad"),
             Unit::Seconds => String::from("s"),
             Unit::Milliseconds => String::from("ms"),
+            Unit::Dppx => String::from("dppx"),
+            Unit::Dpi => String::from("dpi"),
+            Unit::Dpcm => String::from("dpcm"),
+            Unit::Fr => String::from("fr"),
+            Unit::Other(unit) => unit.clone(),
         }
     }
 }
 
+/// The kind of quantity that a dimension measures, based on its unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimensionClass {
+    Unknown,
+    Number,
+    Length,
+    Percentage,
+    Angle,
+    Time,
+    Resolution,
+    /// A flexible length in a grid, in `fr`.
+    Flex,
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Dimension {
     pub value: f32,
@@ -225,26 +373,73 @@ impl Dimension {
         Dimension { value, unit: Unit::Px }
     }
 
+    pub fn unit_class(&self) -> DimensionClass {
+        match self.unit {
+            Unit::Number => DimensionClass::Number,
+            Unit::Px | Unit::Em | Unit::Rem | Unit::Pt => DimensionClass::Length,
+            Unit::Percent => DimensionClass::Percentage,
+            Unit::Degrees | Unit::Radians => DimensionClass::Angle,
+            Unit::Seconds | Unit::Milliseconds => DimensionClass::Time,
+            Unit::Dppx | Unit::Dpi | Unit::Dpcm => DimensionClass::Resolution,
+            Unit::Fr => DimensionClass::Flex,
+            Unit::Unknown | Unit::Unsupported | Unit::Other(_) => DimensionClass::Unknown,
+        }
+    }
+
     pub fn is_number(&self) -> bool {
-        self.unit == Unit::Number
+        self.unit_class() == DimensionClass::Number
     }
 
     pub fn is_length(&self) -> bool {
-        match self.unit {
-            Unit::Px | Unit::Em | Unit::Rem | Unit::Pt => true,
-            _ => false,
-        }
+        self.unit_class() == DimensionClass::Length
     }
 
     pub fn is_percent(&self) -> bool {
-        self.unit == Unit::Percent
+        self.unit_class() == DimensionClass::Percentage
     }
 
     pub fn is_angle(&self) -> bool {
-        match self.unit {
-            Unit::Degrees | Unit::Radians => true,
-            _ => false
-        }
+        self.unit_class() == DimensionClass::Angle
+    }
+
+    pub fn is_time(&self) -> bool {
+        self.unit_class() == DimensionClass::Time
+    }
+
+    pub fn is_resolution(&self) -> bool {
+        self.unit_class() == DimensionClass::Resolution
+    }
+
+    pub fn is_flex(&self) -> bool {
+        self.unit_class() == DimensionClass::Flex
+    }
+
+    /// Serialize this dimension to CSS syntax, like `10px` or `50%`.
+    pub fn to_css(&self) -> String {
+        let unit = match &self.unit {
+            Unit::Unknown | Unit::Unsupported | Unit::Number => "",
+            // Percentages are stored as a fraction.
+            Unit::Percent => return format!("{}%", self.value * 100.0),
+            Unit::Px => "px",
+            Unit::Em => "em",
+            Unit::Rem => "rem",
+            Unit::Pt => "pt",
+            Unit::Degrees => "deg",
+            Unit::Radians => "rad",
+            Unit::Seconds => "s",
+            Unit::Milliseconds => "ms",
+            Unit::Dppx => "dppx",
+            Unit::Dpi => "dpi",
+            Unit::Dpcm => "dpcm",
+            Unit::Fr => "fr",
+            Unit::Other(unit) => unit,
+        };
+        format!("{}{}", self.value, unit)
+    }
+
+    /// Returns a new dimension with the value multiplied by `factor` and the same unit.
+    pub fn scaled(&self, factor: f32) -> Dimension {
+        Dimension { value: self.value * factor, unit: self.unit.clone() }
     }
 }
 
@@ -260,7 +455,47 @@ impl From<Value> for Dimension {
 
 impl std::fmt::Display for Dimension {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Dimension({} {})", self.value, self.unit.to_string())
+        match self.unit {
+            // Percentages are stored as a fraction but written as they are in CSS.
+            Unit::Percent => write!(f, "Dimension({} %)", self.value * 100.0),
+            _ => write!(f, "Dimension({} {})", self.value, self.unit.to_string()),
+        }
+    }
+}
+
+/// A keyword that is valid for every property.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CssWideKeyword {
+    Initial,
+    Inherit,
+    Unset,
+    Revert,
+    /// Rolls back to the value the property would have from the previous cascade layer.
+    RevertLayer,
+}
+
+impl CssWideKeyword {
+    pub fn parse(input: &str) -> Option<CssWideKeyword> {
+        match input.to_ascii_lowercase().as_str() {
+            "initial" => Some(CssWideKeyword::Initial),
+            "inherit" => Some(CssWideKeyword::Inherit),
+            "unset" => Some(CssWideKeyword::Unset),
+            "revert" => Some(CssWideKeyword::Revert),
+            "revert-layer" => Some(CssWideKeyword::RevertLayer),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CssWideKeyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Initial => write!(f, "initial"),
+            Self::Inherit => write!(f, "inherit"),
+            Self::Unset => write!(f, "unset"),
+            Self::Revert => write!(f, "revert"),
+            Self::RevertLayer => write!(f, "revert-layer"),
+        }
     }
 }
 
@@ -268,11 +503,19 @@ impl std::fmt::Display for Dimension {
 pub enum ValueData {
     #[default] Empty,
     Dimension(Dimension),
+    /// A quoted string.
     String(String),
+    /// An identifier, like `none` or `auto`.
+    Keyword(String),
     Color(Color),
     Image(String),
     Url(String),
     Integer(i32),
+    /// A range of code points, like `U+0000-00FF`, with inclusive bounds.
+    UnicodeRange { start: u32, end: u32 },
+    /// A `/` separating components of a value.
+    Slash,
+    CssWideKeyword(CssWideKeyword),
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -289,15 +532,78 @@ impl Value {
         Value{data: ValueData::Url(url.to_string())}
     }
 
+    pub fn new_image(image: &str) -> Value {
+        Value{data: ValueData::Image(image.to_string())}
+    }
+
+    pub fn new_keyword(keyword: &str) -> Value {
+        Value{data: ValueData::Keyword(keyword.to_string())}
+    }
+
+    pub fn new_unicode_range(start: u32, end: u32) -> Value {
+        Value{data: ValueData::UnicodeRange { start, end }}
+    }
+
+    pub fn new_slash() -> Value {
+        Value{data: ValueData::Slash}
+    }
+
+    /// A shared empty value, for accessors that return a reference.
     pub fn empty_ref() -> &'static Value {
-        &Value{data: ValueData::Empty}
+        static EMPTY: Value = Value{data: ValueData::Empty};
+        &EMPTY
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self.data, ValueData::Empty)
     }
 
     pub fn to_string(&self) -> String {
-        if let ValueData::String(string) = &self.data {
-            string.clone()
+        match &self.data {
+            ValueData::String(string) | ValueData::Keyword(string) => string.clone(),
+            _ => String::new(),
+        }
+    }
+
+    /// Serialize this value to CSS syntax that can be parsed again.
+    pub fn to_css(&self) -> String {
+        let mut result = String::new();
+        match &self.data {
+            ValueData::Empty => (),
+            ValueData::Dimension(dimension) => result = dimension.to_css(),
+            ValueData::String(string) => { let _ = cssparser::serialize_string(string, &mut result); }
+            ValueData::Keyword(keyword) => { let _ = cssparser::serialize_identifier(keyword, &mut result); }
+            ValueData::Color(color) => result = color.to_css(),
+            ValueData::Image(url) | ValueData::Url(url) => {
+                result.push_str("url(");
+                let _ = cssparser::serialize_string(url, &mut result);
+                result.push(')');
+            }
+            ValueData::Integer(integer) => result = integer.to_string(),
+            ValueData::UnicodeRange { start, end } if start == end => result = format!("U+{:X}", start),
+            ValueData::UnicodeRange { start, end } => result = format!("U+{:X}-{:X}", start, end),
+            ValueData::Slash => result.push('/'),
+            ValueData::CssWideKeyword(keyword) => result = keyword.to_string(),
+        }
+        result
+    }
+
+    /// Returns the keyword if this value is an identifier keyword, like `auto`.
+    pub fn as_keyword(&self) -> Option<&str> {
+        match &self.data {
+            ValueData::Keyword(keyword) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of this value with dimensions scaled by `factor`.
+    ///
+    /// Values that are not a dimension are returned unchanged.
+    pub fn scaled(&self, factor: f32) -> Value {
+        if let ValueData::Dimension(dimension) = &self.data {
+            Value::from(dimension.scaled(factor))
         } else {
-            String::new()
+            self.clone()
         }
     }
 }
@@ -333,6 +639,12 @@ impl From<Color> for Value {
     }
 }
 
+impl From<CssWideKeyword> for Value {
+    fn from(value: CssWideKeyword) -> Self {
+        Value{data: ValueData::CssWideKeyword(value)}
+    }
+}
+
 impl From<Dimension> for Value {
     fn from(value: Dimension) -> Self {
         Value{data: ValueData::Dimension(value)}
@@ -345,10 +657,14 @@ impl std::fmt::Display for Value {
             ValueData::Empty => write!(f, "Empty Value"),
             ValueData::Dimension(dimension) => write!(f, "{}", dimension),
             ValueData::String(string) => write!(f, "String({})", string),
+            ValueData::Keyword(keyword) => write!(f, "Keyword({})", keyword),
             ValueData::Image(string) => write!(f, "Image({})", string),
             ValueData::Url(string) => write!(f, "Url({})", string),
             ValueData::Color(color) => write!(f, "{}", color),
             ValueData::Integer(value) => write!(f, "Integer({})", value),
+            ValueData::UnicodeRange { start, end } => write!(f, "UnicodeRange(U+{:X}-{:X})", start, end),
+            ValueData::Slash => write!(f, "Slash"),
+            ValueData::CssWideKeyword(keyword) => write!(f, "CssWideKeyword({})", keyword),
         }
     }
 }
@@ -6,11 +6,15 @@ use std::{path::PathBuf, sync::Arc};
 use cxx_rust_cssparser_impl::parseerror::{ParseError, ParseErrorKind, SourceLocation};
 use cxx_rust_cssparser_impl::stylesheet;
 use cxx_rust_cssparser_impl::{
-    property::{add_property_definition, property_definition, Property, PropertyDefinition},
+    background::{parse_background, BackgroundLayer},
+    fontface::{FontFace, FontFaceSource},
+    grid::{parse_track_list, GridTrack},
+    media::MediaContext,
+    property::{add_property_definition, merge_properties, parse_property_value, property_definition, registered_property_names, Property, PropertyDefinition},
     selector::*,
     stylerule::StyleRule,
     stylesheet::StyleSheet,
-    value::{Color, Dimension, Value, Unit},
+    value::{Color, CssWideKeyword, Dimension, Value, Unit},
 };
 
 fn setup() {
@@ -28,12 +32,15 @@ fn minimal() {
     let result = stylesheet.parse_string("test { }");
     assert!(result.is_ok());
 
-    assert_eq!(stylesheet.rules, Vec::from([
+    assert_eq!(stylesheet.iter_rules().cloned().collect::<Vec<_>>(), Vec::from([
         StyleRule {
             selector: Selector::from_parts(&[
                 SelectorPart::new_with_value(SelectorKind::Type, Value::from("test")),
             ]),
-            properties: Vec::new(),
+            properties: Arc::new(Vec::new()),
+            media: None,
+            layer: None,
+            comments: Vec::new(),
         }
     ]));
 }
@@ -49,24 +56,36 @@ fn property_registration() {
     assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
 
     assert_eq!(
-        stylesheet.rules,
+        stylesheet.iter_rules().cloned().collect::<Vec<_>>(),
         vec![
             StyleRule {
                 selector: Selector::from_parts(&[
                     SelectorPart::new_with_value(SelectorKind::Type, Value::from("example"))
                 ]),
-                properties: vec![
+                properties: Arc::new(vec![
                     Property {
                         name: String::from("test"),
                         definition: property_definition.clone(),
                         values: Vec::from([
                             Value::from(Color::rgba(255, 0, 0, 255))
-                        ])
+                        ]),
+                        important: false,
+                        flag: None,
+                        comments: Vec::new(),
                     }
-                ],
+                ]),
+                media: None,
+                layer: None,
+                comments: Vec::new(),
             }
         ]
     );
+
+    stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("example { TEST: red; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty(), "Unexpected errors: {:?}", stylesheet.errors);
+    assert_eq!(stylesheet.iter_rules().next().unwrap().properties[0].name, "test");
 }
 
 #[test]
@@ -93,27 +112,36 @@ fn custom_properties() {
     assert_eq!(*length_definition, PropertyDefinition::from_name_syntax_initial("--test-length", "*", &[Value::from(Dimension{value: 24.0, unit: Unit::Px})], "Test Input", 0, 0).unwrap());
 
     assert_eq!(
-        stylesheet.rules,
+        stylesheet.iter_rules().cloned().collect::<Vec<_>>(),
         vec![
             StyleRule {
                 selector: Selector::from_parts(&[
                     SelectorPart::new_with_empty(SelectorKind::DocumentRoot),
                 ]),
-                properties: Vec::new(),
+                properties: Arc::new(Vec::new()),
+                media: None,
+                layer: None,
+                comments: Vec::new(),
             },
             StyleRule {
                 selector: Selector::from_parts(&[
                     SelectorPart::new_with_value(SelectorKind::Type, Value::from("example")),
                 ]),
-                properties: vec![
+                properties: Arc::new(vec![
                     Property {
                         name: String::from("test"),
                         definition: property_definition("test").unwrap().clone(),
                         values: vec![
                             Value::from(Color::rgba(255, 0, 0, 255))
-                        ]
+                        ],
+                        important: false,
+                        flag: None,
+                        comments: Vec::new(),
                     }
-                ]
+                ]),
+                media: None,
+                layer: None,
+                comments: Vec::new(),
             }
         ]
     );
@@ -141,15 +169,21 @@ fn nested_block() {
             selector: Selector::from_parts(&[
                 SelectorPart::new_with_value(SelectorKind::Type, Value::from("example"))
             ]),
-            properties: Vec::from([
+            properties: Arc::new(Vec::from([
                 Property {
                     name: String::from("test"),
                     definition: property_definition.clone(),
                     values: Vec::from([
                         Value::from(Color::rgba(255, 0, 0, 255))
                     ]),
+                    important: false,
+                    flag: None,
+                    comments: Vec::new(),
                 }
-            ]),
+            ])),
+            media: None,
+            layer: None,
+            comments: Vec::new(),
         },
         StyleRule {
             selector: Selector::from_parts(&[
@@ -157,21 +191,27 @@ fn nested_block() {
                 SelectorPart::new_with_empty(SelectorKind::DescendantCombinator),
                 SelectorPart::new_with_value(SelectorKind::Type, Value::from("nested")),
             ]),
-            properties: Vec::from([
+            properties: Arc::new(Vec::from([
                 Property {
                     name: String::from("test"),
                     definition: property_definition.clone(),
                     values: Vec::from([
                         Value::from(Color::rgba(0, 0, 255, 255))
                     ]),
+                    important: false,
+                    flag: None,
+                    comments: Vec::new(),
                 }
-            ]),
+            ])),
+            media: None,
+            layer: None,
+            comments: Vec::new(),
         },
     ]);
 
-    let rules = &stylesheet.rules;
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
     assert_eq!(rules.len(), expected.len());
-    assert_eq!(rules, &expected);
+    assert_eq!(&rules, &expected);
 
     stylesheet = StyleSheet::new(PathBuf::new());
     let result = stylesheet.parse_string(
@@ -184,9 +224,9 @@ fn nested_block() {
     }");
     assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
 
-    let rules = &stylesheet.rules;
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
     assert_eq!(rules.len(), expected.len());
-    assert_eq!(rules, &expected);
+    assert_eq!(&rules, &expected);
 }
 
 #[test]
@@ -197,8 +237,8 @@ fn complex() {
     let result = stylesheet.parse();
     assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
 
-    let rules = &stylesheet.rules;
-    assert_eq!(rules.len(), 9);
+    assert_eq!(stylesheet.rule_count(), 9);
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
 
     let expected_selectors = [
         Selector::from_parts(&[
@@ -264,16 +304,25 @@ fn complex() {
             name: String::from("width"),
             definition: property_definition("width").unwrap(),
             values: vec![Value::from(Dimension{value: 32.0, unit: Unit::Px})],
+            important: false,
+            flag: None,
+            comments: Vec::new(),
         },
         Property {
             name: String::from("height"),
             definition: property_definition("height").unwrap(),
             values: vec![Value::from(Dimension{value: 32.0, unit: Unit::Px})],
+            important: false,
+            flag: None,
+            comments: Vec::new(),
         },
         Property {
             name: String::from("color"),
             definition: property_definition("color").unwrap(),
-            values: vec![Value::from(Color::rgba(255, 0, 0, 255))]
+            values: vec![Value::from(Color::rgba(255, 0, 0, 255))],
+            important: false,
+            flag: None,
+            comments: Vec::new(),
         },
         Property {
             name: String::from("padding"),
@@ -283,24 +332,33 @@ fn complex() {
                 Value::from(Dimension{value: 4.0, unit: Unit::Px}),
                 Value::from(Dimension{value: 4.0, unit: Unit::Px}),
                 Value::from(Dimension{value: 4.0, unit: Unit::Px}),
-            ]
+            ],
+            important: false,
+            flag: None,
+            comments: Vec::new(),
         },
         Property {
             name: String::from("padding-top"),
             definition: property_definition("padding-top").unwrap(),
             values: vec![
                 Value::from(Dimension{value: 2.0, unit: Unit::Rem}),
-            ]
+            ],
+            important: false,
+            flag: None,
+            comments: Vec::new(),
         },
         Property {
             name: String::from("background-image"),
             definition: property_definition("background-image").unwrap(),
             values: vec![
                 Value::new_url(path.parent().unwrap().join("background.svg").to_string_lossy().as_ref()),
-            ]
+            ],
+            important: false,
+            flag: None,
+            comments: Vec::new(),
         }
     ];
-    let properties: Vec<Property> = rules.first().unwrap().properties.clone();
+    let properties: Vec<Property> = rules.first().unwrap().properties.to_vec();
     assert_eq!(properties, expected_properties);
 }
 
@@ -317,6 +375,66 @@ fn import() {
     assert_eq!(rules.len(), 4);
 }
 
+#[test]
+fn import_absolute() {
+    // An absolute path is used as is, regardless of the path of the importing sheet.
+    let mut stylesheet = StyleSheet::new(PathBuf::from("/nonexistent/sheet.css"));
+    let result = stylesheet.import(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/import_values.css")));
+    assert!(result.is_ok(), "Importing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.imported_sheets.len(), 1);
+
+    let mut stylesheet = StyleSheet::new(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/import.css")));
+    let result = stylesheet.import(PathBuf::from("import_values.css"));
+    assert!(result.is_ok(), "Importing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.imported_sheets[0].path, PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/import_values.css")));
+}
+
+#[test]
+fn error_line_numbers() {
+    setup();
+
+    // The injected sourceURL line must not shift lines of the input.
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { unknown-property: 1; }");
+    assert!(result.is_ok());
+    let locations: Vec<_> = stylesheet.errors.iter().map(|error| (error.location.line, error.location.column)).collect();
+    assert_eq!(locations, vec![(1, 26)]);
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("\n\nthird { unknown-property: 1; }");
+    assert!(result.is_ok());
+    let locations: Vec<_> = stylesheet.errors.iter().map(|error| (error.location.line, error.location.column)).collect();
+    assert_eq!(locations, vec![(3, 26)]);
+}
+
+#[test]
+fn errors_json() {
+    setup();
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { unknown-property: 1; }");
+    assert!(result.is_ok());
+    assert_eq!(
+        stylesheet.errors_json(),
+        r#"[{"kind":"UnknownProperty","message":"No definition for property unknown-property","file":"","line":1,"column":26}]"#
+    );
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { color: red; }");
+    assert!(result.is_ok());
+    assert_eq!(stylesheet.errors_json(), "[]");
+
+    let error = ParseError {
+        kind: ParseErrorKind::Unknown,
+        message: "a \"quoted\"\tmessage\n".to_string(),
+        location: SourceLocation::from_file("C:\\style.css"),
+    };
+    assert_eq!(
+        error.to_json(),
+        r#"{"kind":"Unknown","message":"a \"quoted\"\tmessage\n","file":"C:\\style.css","line":0,"column":0}"#
+    );
+}
+
 #[test]
 fn errors() {
     setup();
@@ -366,6 +484,12 @@ fn errors() {
         }
     ]);
 
+    stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("@media (min-width: 10px) {\n  first { unknown-property: 1; }\n}\nsecond { other-property: 1; }");
+    assert!(result.is_ok());
+    let locations: Vec<_> = stylesheet.errors.iter().map(|error| (error.location.line, error.location.column)).collect();
+    assert_eq!(locations, vec![(2, 28), (4, 25)]);
+
     stylesheet = StyleSheet::new(PathBuf::new());
     let result = stylesheet.parse_string("invalid - selector { }");
     assert!(result.is_ok());
@@ -388,7 +512,7 @@ fn errors() {
             message: String::from("No definition for property unknown-property"),
             location: SourceLocation {
                 file: path.to_string_lossy().to_string(),
-                line: 38,
+                line: 43,
                 column: 22,
             }
         },
@@ -397,16 +521,16 @@ fn errors() {
             message: String::from("Invalid Selectors: Dangling Combinator"),
             location: SourceLocation {
                 file: path.to_string_lossy().to_string(),
-                line: 41,
+                line: 46,
                 column: 19,
             }
         },
         ParseError {
             kind: ParseErrorKind::InvalidPropertyValue,
-            message: String::from("Parsing values for property padding-top failed: Expected Length, got String(value)"),
+            message: String::from("Parsing values for property padding-top failed: Expected Length, got Keyword(value)"),
             location: SourceLocation {
                 file: path.to_string_lossy().to_string(),
-                line: 46,
+                line: 51,
                 column: 23,
             }
         }
@@ -426,3 +550,1135 @@ fn errors() {
     assert_eq!(stylesheet.errors, vec![]);
     assert_eq!(stylesheet.all_errors(), file_errors);
 }
+
+#[test]
+fn missing_initial_value() {
+    let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/missing_initial_value.css"));
+    let mut stylesheet = StyleSheet::new(path.clone());
+    let result = stylesheet.parse();
+    assert!(result.is_ok());
+
+    let errors: Vec<_> = stylesheet.errors.iter().map(|error| (error.kind.clone(), error.location.line)).collect();
+    assert_eq!(errors, vec![(ParseErrorKind::InvalidPropertyDefinition, 9)]);
+    assert!(stylesheet.errors[0].message.contains("'initial-value' is required for property --missing-initial-typed"));
+
+    assert!(property_definition("--missing-initial-typed").is_none());
+    assert!(property_definition("--missing-initial-inherited").unwrap().initial.is_empty());
+    assert!(property_definition("--missing-initial-universal").unwrap().initial.is_empty());
+}
+
+#[test]
+fn matching_rules() {
+    setup();
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string(
+        "button.primary { test: green; }
+        button { test: red; }
+        .primary { test: blue; }
+        toolbutton { test: yellow; }
+        button { test: black; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let mut element = ElementContext::new("button");
+    element.classes.push(String::from("primary"));
+
+    let rules = stylesheet.matching_rules(&element);
+    let colors: Vec<(Value, Specificity)> = rules.iter().map(|(rule, specificity)| (rule.properties[0].values[0].clone(), *specificity)).collect();
+    assert_eq!(colors, vec![
+        (Value::from(Color::rgba(255, 0, 0, 255)), Specificity(0, 0, 1)),
+        (Value::from(Color::rgba(0, 0, 0, 255)), Specificity(0, 0, 1)),
+        (Value::from(Color::rgba(0, 0, 255, 255)), Specificity(0, 1, 0)),
+        (Value::from(Color::rgba(0, 128, 0, 255)), Specificity(0, 1, 1)),
+    ]);
+}
+
+#[test]
+fn media() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+
+    let result = stylesheet.parse_string(
+    "first { }
+
+    @media (min-resolution: 2dppx) {
+        second { }
+    }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty(), "Unexpected errors: {:?}", stylesheet.errors);
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0].media, None);
+
+    let media = rules[1].media.as_ref().unwrap();
+    assert!(media.evaluate(&MediaContext { device_pixel_ratio: 2.0, ..Default::default() }));
+    assert!(!media.evaluate(&MediaContext::default()));
+}
+
+#[test]
+fn media_query_logic() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("@media screen and (min-width: 600px), print { first { } }
+@media screen and (min-width: 600px) or print { second { } }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 1);
+    let media = rules[0].media.as_ref().unwrap();
+    assert!(media.evaluate(&MediaContext { width: 800.0, ..Default::default() }));
+    assert!(media.evaluate(&MediaContext { media_type: String::from("print"), ..Default::default() }));
+    assert!(!media.evaluate(&MediaContext::default()));
+
+    assert_eq!(stylesheet.errors.len(), 1);
+    assert_eq!(stylesheet.errors[0].kind, ParseErrorKind::InvalidAtRule);
+    assert_eq!(stylesheet.errors[0].location.line, 2);
+}
+
+#[test]
+fn media_nested() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("
+        @media (min-width: 100px) { @media (min-resolution: 2dppx) { first { } } }
+        @media print, (min-width: 600px) { @media (min-resolution: 2dppx) { second { } } }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty());
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 2);
+
+    // Both the outer and the inner query need to match.
+    let first = rules[0].media.as_ref().unwrap();
+    assert!(first.evaluate(&MediaContext { width: 200.0, device_pixel_ratio: 2.0, ..Default::default() }));
+    assert!(!first.evaluate(&MediaContext { width: 50.0, device_pixel_ratio: 2.0, ..Default::default() }));
+    assert!(!first.evaluate(&MediaContext { width: 200.0, device_pixel_ratio: 1.0, ..Default::default() }));
+
+    let second = rules[1].media.as_ref().unwrap();
+    assert!(second.evaluate(&MediaContext { media_type: String::from("print"), device_pixel_ratio: 2.0, ..Default::default() }));
+    assert!(second.evaluate(&MediaContext { width: 800.0, device_pixel_ratio: 2.0, ..Default::default() }));
+    assert!(!second.evaluate(&MediaContext { width: 200.0, device_pixel_ratio: 2.0, ..Default::default() }));
+    assert!(!second.evaluate(&MediaContext { media_type: String::from("print"), device_pixel_ratio: 1.0, ..Default::default() }));
+}
+
+#[test]
+fn parse_stylesheet() {
+    let (rules, errors) = stylesheet::parse_stylesheet("first { } second { unknown-property: 1; }", "test.css").unwrap();
+
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].selector, Selector::from_parts(&[
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("first")),
+    ]));
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, ParseErrorKind::UnknownProperty);
+    assert_eq!(errors[0].location.file, "test.css");
+}
+
+#[test]
+fn namespaces() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+
+    let result = stylesheet.parse_string(
+    "@namespace svg url(http://www.w3.org/2000/svg);
+    svg|rect { }
+    rect { }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty(), "Unexpected errors: {:?}", stylesheet.errors);
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0].selector, Selector::from_parts(&[
+        SelectorPart::new_with_namespace("svg", "http://www.w3.org/2000/svg"),
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("rect")),
+    ]));
+    assert_eq!(rules[1].selector, Selector::from_parts(&[
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("rect")),
+    ]));
+
+    let mut element = ElementContext::new("rect");
+    assert!(!rules[0].selector.matches(&element));
+    element.namespace = String::from("http://www.w3.org/2000/svg");
+    assert!(rules[0].selector.matches(&element));
+
+    stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string(
+    "@namespace url(http://www.w3.org/2000/svg);
+    rect { }
+    *|rect { }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.iter_rules().next().unwrap().selector, Selector::from_parts(&[
+        SelectorPart::new_with_namespace("", "http://www.w3.org/2000/svg"),
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("rect")),
+    ]));
+    assert_eq!(stylesheet.iter_rules().nth(1).unwrap().selector, Selector::from_parts(&[
+        SelectorPart::new_with_namespace("*", ""),
+        SelectorPart::new_with_value(SelectorKind::Type, Value::from("rect")),
+    ]));
+
+    stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("unknown|rect { }");
+    assert!(result.is_ok());
+    assert_eq!(stylesheet.errors.len(), 1);
+}
+
+#[test]
+fn lenient_unknown_properties() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    stylesheet.strict_unknown_properties = false;
+
+    let result = stylesheet.parse_string("example { unknown-property: 10px solid; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty(), "Unexpected errors: {:?}", stylesheet.errors);
+
+    let properties = &stylesheet.iter_rules().next().unwrap().properties;
+    assert_eq!(properties.len(), 1);
+    assert_eq!(properties[0].name, "unknown-property");
+    assert_eq!(properties[0].values, vec![Value::from(Dimension::px(10.0)), Value::new_keyword("solid")]);
+    assert!(property_definition("unknown-property").is_none());
+}
+
+#[test]
+fn builtin_properties() {
+    let _ = StyleSheet::new(PathBuf::new());
+
+    for definition in cxx_rust_cssparser_impl::property::builtin_property_definitions() {
+        let registered = property_definition(&definition.name);
+        assert!(registered.is_some(), "Property {} was not registered", definition.name);
+        assert_eq!(*registered.unwrap(), definition);
+    }
+
+    let width = property_definition("width").unwrap();
+    assert_eq!(width.initial, vec![Value::from(Dimension::px(0.0))]);
+}
+
+#[test]
+fn comments() {
+    setup();
+
+    let source = "/* First rule */
+    first {
+        /* The test property */
+        test: red;
+    }
+
+    second { test: blue; }";
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string(source);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.iter_rules().all(|rule| rule.comments.is_empty()));
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    stylesheet.retain_comments = true;
+    let result = stylesheet.parse_string(source);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0].comments, vec![String::from("First rule")]);
+    assert_eq!(rules[0].properties[0].comments, vec![String::from("The test property")]);
+    assert!(rules[1].comments.is_empty());
+    assert!(rules[1].properties[0].comments.is_empty());
+}
+
+#[test]
+fn important() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { color: red; } second { color: red !important; } third { color: blue; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty());
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert!(!rules[0].properties[0].important);
+    assert!(rules[1].properties[0].important);
+    assert!(!rules[2].properties[0].important);
+
+    let blue = vec![Value::from(Color::rgba(0, 0, 255, 255))];
+    let red = vec![Value::from(Color::rgba(255, 0, 0, 255))];
+
+    let merged = merge_properties(&rules[0].properties, &rules[2].properties);
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].values, blue);
+
+    let merged = merge_properties(&rules[1].properties, &rules[2].properties);
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].values, red);
+    assert!(merged[0].important);
+
+    let merged = merge_properties(&rules[2].properties, &rules[1].properties);
+    assert_eq!(merged[0].values, red);
+}
+
+#[test]
+fn bang_flags() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { color: red !important; } second { color: red !default; } third { color: red; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.errors.len(), 1);
+    assert_eq!(stylesheet.errors[0].kind, ParseErrorKind::UnexpectedToken);
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0].properties[0].flag, Some(String::from("important")));
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    stylesheet.strict_bang_flags = false;
+    let result = stylesheet.parse_string("first { color: red ! IMPORTANT; } second { color: red !default; } third { color: red; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty());
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert!(rules[0].properties[0].important);
+    assert_eq!(rules[0].properties[0].flag, Some(String::from("IMPORTANT")));
+    assert!(!rules[1].properties[0].important);
+    assert_eq!(rules[1].properties[0].flag, Some(String::from("default")));
+    assert_eq!(rules[1].properties[0].values, vec![Value::from(Color::rgba(255, 0, 0, 255))]);
+    assert_eq!(rules[2].properties[0].flag, None);
+}
+
+#[test]
+fn shared_properties() {
+    setup();
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first, second, third { test: red; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 3);
+    assert!(Arc::ptr_eq(&rules[0].properties, &rules[1].properties));
+    assert!(Arc::ptr_eq(&rules[0].properties, &rules[2].properties));
+    assert_eq!(rules[0].properties, Arc::new(vec![Property {
+        name: String::from("test"),
+        definition: property_definition("test").unwrap(),
+        values: vec![Value::from(Color::rgba(255, 0, 0, 255))],
+        important: false,
+        flag: None,
+        comments: Vec::new(),
+    }]));
+}
+
+#[test]
+fn duplicate_properties() {
+    setup();
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { color: red; test: red; color: blue; } second { color: red !important; color: blue; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules[0].properties.len(), 2);
+    assert_eq!(rules[0].properties[0].name, "color");
+    assert_eq!(rules[0].properties[0].values, vec![Value::from(Color::rgba(0, 0, 255, 255))]);
+    assert_eq!(rules[0].properties[1].name, "test");
+
+    assert_eq!(rules[1].properties.len(), 1);
+    assert_eq!(rules[1].properties[0].values, vec![Value::from(Color::rgba(255, 0, 0, 255))]);
+}
+
+#[test]
+fn property_value() {
+    let _ = StyleSheet::new(PathBuf::new());
+    let width = property_definition("width").unwrap();
+
+    assert_eq!(parse_property_value(&width, "10px"), Ok(vec![Value::from(Dimension::px(10.0))]));
+
+    let error = parse_property_value(&width, "red").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::PropertyValueDoesNotMatchSyntax);
+
+    let error = parse_property_value(&width, "10px !important").unwrap_err();
+    assert_eq!((error.location.line, error.location.column), (1, 6));
+
+    let error = parse_property_value(&width, "foo").unwrap_err();
+    assert_eq!((error.location.line, error.location.column), (1, 4));
+
+    let error = parse_property_value(&width, "10px\n  !important").unwrap_err();
+    assert_eq!((error.location.line, error.location.column), (2, 3));
+
+    let color = property_definition("color").unwrap();
+    assert_eq!(parse_property_value(&color, "rgb(from red r 128 b / 50%)"), Ok(vec![Value::from(Color::rgba(255, 128, 0, 128))]));
+}
+
+#[test]
+fn unknown_at_rules() {
+    setup();
+
+    let source = "@unknown-block { nested { test: red; } @inner { } }\n@unknown-statement something;\nfirst { test: red; }";
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string(source);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.rule_count(), 1);
+    assert_eq!(stylesheet.errors.len(), 2);
+    assert!(stylesheet.warnings.is_empty());
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    stylesheet.strict_unknown_at_rules = false;
+    let result = stylesheet.parse_string(source);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.rule_count(), 1);
+    assert!(stylesheet.errors.is_empty(), "Unexpected errors: {:?}", stylesheet.errors);
+    assert_eq!(stylesheet.warnings.len(), 2);
+    assert!(stylesheet.warnings.iter().all(|warning| warning.kind == ParseErrorKind::UnsupportedAtRule));
+    assert_eq!(stylesheet.warnings[1].location.line, 2);
+}
+
+#[test]
+fn lenient_units() {
+    let source = "example { unknown-units: 10foo 2mm; } other { width: 10foo; }";
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    stylesheet.strict_unknown_properties = false;
+    let result = stylesheet.parse_string(source);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.errors.len(), 2);
+    assert!(stylesheet.errors.iter().all(|error| error.kind == ParseErrorKind::InvalidPropertyValue));
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    stylesheet.strict_unknown_properties = false;
+    stylesheet.strict_units = false;
+    let result = stylesheet.parse_string(source);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    // Unknown units still do not match a typed syntax.
+    assert_eq!(stylesheet.errors.len(), 1);
+    assert_eq!(stylesheet.errors[0].kind, ParseErrorKind::InvalidPropertyValue);
+
+    let properties = &stylesheet.iter_rules().next().unwrap().properties;
+    assert_eq!(properties.len(), 1);
+    assert_eq!(properties[0].values, vec![
+        Value::from(Dimension { value: 10.0, unit: Unit::Other(String::from("foo")) }),
+        Value::from(Dimension { value: 2.0, unit: Unit::Other(String::from("mm")) }),
+    ]);
+}
+
+#[test]
+fn css_wide_keywords() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { color: inherit; width: 10px; } second { all: initial; } third { all: unset; } fourth { all: red; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    assert_eq!(stylesheet.errors.len(), 1);
+    assert_eq!(stylesheet.errors[0].kind, ParseErrorKind::InvalidPropertyValue);
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[0].properties[0].values, vec![Value::from(CssWideKeyword::Inherit)]);
+    assert_eq!(rules[0].properties[0].css_wide_keyword(), Some(CssWideKeyword::Inherit));
+    assert_eq!(rules[0].properties[1].css_wide_keyword(), None);
+    assert_eq!(rules[1].properties[0].css_wide_keyword(), Some(CssWideKeyword::Initial));
+    assert_eq!(rules[2].properties[0].css_wide_keyword(), Some(CssWideKeyword::Unset));
+
+    let merged = merge_properties(&rules[0].properties, &rules[2].properties);
+    assert_eq!(merged.len(), 3);
+    assert!(merged.iter().all(|property| property.css_wide_keyword() == Some(CssWideKeyword::Unset)));
+}
+
+#[test]
+fn all_skips_custom_properties() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { all: initial; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let property = |name: &str, definition: Arc<PropertyDefinition>, value: Value| Property {
+        name: String::from(name),
+        definition,
+        values: vec![value],
+        important: false,
+        flag: None,
+        comments: Vec::new(),
+    };
+    let base = vec![
+        property("--x", Arc::new(PropertyDefinition::from_name_syntax("--x", "*", "Test Input", 0, 0).unwrap()), Value::from(Dimension::px(1.0))),
+        property("direction", Arc::new(PropertyDefinition::from_name_syntax("direction", "rtl | ltr", "Test Input", 0, 0).unwrap()), Value::from("rtl")),
+        property("color", property_definition("color").unwrap(), Value::from(Color::rgba(255, 0, 0, 255))),
+    ];
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    let merged = merge_properties(&base, &rules[0].properties);
+    assert_eq!(merged.len(), 4);
+    assert_eq!(merged[0].values, vec![Value::from(Dimension::px(1.0))]);
+    assert_eq!(merged[1].values, vec![Value::from("rtl")]);
+    assert_eq!(merged[2].css_wide_keyword(), Some(CssWideKeyword::Initial));
+}
+
+#[test]
+fn revert_layer() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("@layer base { first { color: red; } } @layer theme { first { color: revert-layer; } second { all: REVERT-LAYER; } }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty());
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[1].layer.as_deref(), Some("theme"));
+    assert_eq!(rules[1].properties[0].css_wide_keyword(), Some(CssWideKeyword::RevertLayer));
+    assert_eq!(rules[2].properties[0].css_wide_keyword(), Some(CssWideKeyword::RevertLayer));
+    assert_eq!(CssWideKeyword::RevertLayer.to_string(), "revert-layer");
+}
+
+#[test]
+fn layers() {
+    setup();
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string(
+        "@layer base, components;
+        button.primary { test: green; }
+        @layer components { button.primary { test: red; } }
+        @layer base { #id { test: blue; } @layer reset { button { test: yellow; } } }
+        @layer { button { test: black; } }
+        @layer one, two { button { test: black; } }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    assert_eq!(stylesheet.errors.len(), 2);
+    assert!(stylesheet.errors.iter().all(|error| error.kind == ParseErrorKind::InvalidAtRule));
+
+    assert_eq!(stylesheet.layers, vec!["base", "components", "base.reset"]);
+    assert_eq!(stylesheet.layer_order(), vec!["base.reset", "base", "components"]);
+
+    let rules: Vec<_> = stylesheet.iter_rules().collect();
+    assert_eq!(rules[0].layer, None);
+    assert_eq!(rules[1].layer.as_deref(), Some("components"));
+    assert_eq!(rules[2].layer.as_deref(), Some("base"));
+    assert_eq!(rules[3].layer.as_deref(), Some("base.reset"));
+
+    let mut element = ElementContext::new("button");
+    element.classes.push(String::from("primary"));
+    element.id = String::from("id");
+
+    let colors: Vec<Value> = stylesheet.matching_rules(&element).iter().map(|(rule, _)| rule.properties[0].values[0].clone()).collect();
+    assert_eq!(colors, vec![
+        Value::from(Color::rgba(255, 255, 0, 255)),
+        Value::from(Color::rgba(0, 0, 255, 255)),
+        Value::from(Color::rgba(255, 0, 0, 255)),
+        Value::from(Color::rgba(0, 128, 0, 255)),
+    ]);
+}
+
+#[test]
+fn keyframes() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string(
+        "@keyframes fade {
+            from { color: red; }
+            50%, 75% { color: blue; width: 10px; }
+            to { color: blue; }
+            150% { color: red; }
+            button { color: red; }
+        }
+        @keyframes \"quoted\" { 0% { width: 0px; } }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    assert_eq!(stylesheet.errors.len(), 2);
+    assert!(stylesheet.errors.iter().all(|error| error.kind == ParseErrorKind::InvalidSelectors));
+
+    let keyframes = stylesheet.all_keyframes();
+    assert_eq!(keyframes.len(), 2);
+    assert_eq!(keyframes[0].name, "fade");
+    assert_eq!(keyframes[1].name, "quoted");
+
+    let offsets: Vec<_> = keyframes[0].stops.iter().map(|stop| stop.offsets.clone()).collect();
+    assert_eq!(offsets, vec![vec![0.0], vec![0.5, 0.75], vec![1.0]]);
+
+    let properties = keyframes[0].properties_at(0.75);
+    assert_eq!(properties.len(), 2);
+    assert_eq!(properties[0].values, vec![Value::from(Color::rgba(0, 0, 255, 255))]);
+    assert_eq!(properties[1].values, vec![Value::from(Dimension::px(10.0))]);
+
+    // Keyframes are not rules.
+    assert_eq!(stylesheet.rule_count(), 0);
+}
+
+#[test]
+fn parsed_tree() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("toplevel { width: 10px; nested { height: 5px; & inner { color: red; } } } other { color: blue; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    assert_eq!(stylesheet.rule_count(), 4);
+
+    let tree = stylesheet.parsed_tree();
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree[0].properties.len(), 1);
+    assert_eq!(tree[0].nested_rules.len(), 1);
+    assert!(tree[0].nested_rules[0].selectors[0].has_relative_parent());
+    assert_eq!(tree[0].nested_rules[0].nested_rules.len(), 1);
+    assert_eq!(tree[0].nested_rules[0].nested_rules[0].properties[0].name, "color");
+    assert!(tree[1].nested_rules.is_empty());
+}
+
+#[test]
+fn property_names() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("@property --registered-names-test { syntax: \"<length>\"; inherits: false; initial-value: 0px; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let names = registered_property_names();
+    assert!(names.contains(&String::from("width")));
+    assert!(names.contains(&String::from("--registered-names-test")));
+
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+}
+
+#[test]
+fn property_redefinition() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("
+        @property --redefinition-test { syntax: \"<length>\"; inherits: false; initial-value: 0px; }
+        first { --redefinition-test: 5px; }
+        @property --redefinition-test { syntax: \"<color>\"; inherits: true; initial-value: red; }
+        second { --redefinition-test: blue; }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty(), "{:?}", stylesheet.errors);
+
+    let definition = property_definition("--redefinition-test").unwrap();
+    assert_eq!(definition.syntax.to_syntax_string(), "<color>");
+    assert!(definition.inherit);
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules[0].properties[0].values, vec![Value::from(Dimension { value: 5.0, unit: Unit::Px })]);
+    assert_eq!(rules[1].properties[0].values, vec![Value::from(Color::rgba(0, 0, 255, 255))]);
+}
+
+#[test]
+fn builtin_property_redefinition() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("
+        @property width { syntax: \"<length>\"; inherits: false; initial-value: 0px; }
+        @property height { syntax: \"<color>\"; inherits: true; initial-value: red; }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty(), "{:?}", stylesheet.errors);
+
+    // Repeating the built-in definition is fine, changing it is ignored with a warning.
+    assert_eq!(stylesheet.warnings.len(), 1, "{:?}", stylesheet.warnings);
+    assert_eq!(stylesheet.warnings[0].kind, ParseErrorKind::InvalidPropertyDefinition);
+    assert_eq!(stylesheet.warnings[0].message, "Ignoring redefinition of property height");
+    assert_eq!(stylesheet.warnings[0].location.line, 3);
+
+    let definition = property_definition("height").unwrap();
+    assert_eq!(definition.syntax.to_syntax_string(), "<length>");
+    assert!(!definition.inherit);
+}
+
+#[test]
+fn property_definition_to_css() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("
+        @property --to-css-list { syntax: '<length>#'; inherits: true; initial-value: 1px, 2EM; }
+        @property --to-css-color { syntax: \"<color> | none\"; inherits: false; initial-value: rgb(255 0 0); }
+        @property --to-css-any { syntax: '*'; inherits: false; }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty(), "{:?}", stylesheet.errors);
+
+    let expected = [
+        ("--to-css-list", "@property --to-css-list { syntax: \"<length>#\"; inherits: true; initial-value: 1px, 2em; }"),
+        ("--to-css-color", "@property --to-css-color { syntax: \"<color> | none\"; inherits: false; initial-value: #ff0000; }"),
+        ("--to-css-any", "@property --to-css-any { syntax: \"*\"; inherits: false; }"),
+    ];
+
+    for (name, css) in expected {
+        let definition = property_definition(name).unwrap();
+        assert_eq!(definition.to_css(), css);
+
+        // Parsing the output again replaces the definition with an identical one.
+        let result = StyleSheet::new(PathBuf::new()).parse_string(css);
+        assert!(result.is_ok(), "Parsing {} failed with error: {}", css, result.err().unwrap().to_string());
+        assert_eq!(property_definition(name).unwrap(), definition);
+    }
+}
+
+#[test]
+fn inline_style() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let properties = stylesheet.parse_inline("color: red; width: 10px").unwrap();
+    assert!(stylesheet.errors.is_empty(), "{:?}", stylesheet.errors);
+    assert_eq!(stylesheet.iter_rules().count(), 0);
+
+    let names: Vec<_> = properties.iter().map(|property| property.name.as_str()).collect();
+    assert_eq!(names, vec!["color", "width"]);
+    assert_eq!(properties[0].values, vec![Value::from(Color::rgba(255, 0, 0, 255))]);
+    assert_eq!(properties[1].values, vec![Value::from(Dimension::px(10.0))]);
+
+    let properties = stylesheet.parse_inline("color: red; unknown-property: 1; nested { width: 1px; } width: 2px").unwrap();
+    assert_eq!(properties.len(), 2);
+    assert_eq!(stylesheet.errors.len(), 2);
+
+    let nested_error = stylesheet.errors.iter().find(|error| error.kind == ParseErrorKind::InvalidQualifiedRule).unwrap();
+    assert_eq!((nested_error.location.line, nested_error.location.column), (1, 34));
+}
+
+#[test]
+fn empty_stylesheet() {
+    for input in ["", "   \n\t  ", "/* only a comment */", "/* one */ \n /* two */"] {
+        let (rules, errors) = stylesheet::parse_stylesheet(input, "x").unwrap();
+        assert!(rules.is_empty(), "Input {:?} produced rules", input);
+        assert!(errors.is_empty(), "Input {:?} produced errors: {:?}", input, errors);
+    }
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    stylesheet.retain_comments = true;
+    let result = stylesheet.parse_string("/* only a comment */");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.rule_count(), 0);
+    assert!(stylesheet.errors.is_empty());
+}
+
+#[test]
+fn merge_identical_selectors() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("
+        button { color: red; }
+        button { width: 1px; }
+        label { color: green; }
+        @media (min-width: 100px) { button { color: yellow; } }
+        button { color: white; width: 2px !important; }
+        button { width: 3px; }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.rule_count(), 6);
+
+    stylesheet.merge_identical_selectors();
+    assert_eq!(stylesheet.rule_count(), 4);
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    let names: Vec<_> = rules[0].properties.iter().map(|property| property.name.as_str()).collect();
+    assert_eq!(names, vec!["color", "width"]);
+
+    assert_eq!(rules[1].properties.len(), 1);
+    assert!(rules[2].media.is_some());
+
+    assert!(rules[3].media.is_none());
+    let names: Vec<_> = rules[3].properties.iter().map(|property| property.name.as_str()).collect();
+    assert_eq!(names, vec!["color", "width"]);
+    assert_eq!(rules[3].properties[0].values, vec![Value::from(Color::rgba(255, 255, 255, 255))]);
+    assert_eq!(rules[3].properties[1].values, vec![Value::from(Dimension::px(2.0))]);
+    assert!(rules[3].properties[1].important);
+}
+
+#[test]
+fn merge_identical_selectors_interleaved() {
+    // An element with both classes is green, as .y comes after the first .x
+    // but before the second one. Merging the .x rules must keep that order.
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string(".x { color: red; } .y { color: green; } .x { color: blue; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    stylesheet.merge_identical_selectors();
+    assert_eq!(stylesheet.rule_count(), 3);
+
+    let colors: Vec<_> = stylesheet.iter_rules().map(|rule| rule.properties[0].values.clone()).collect();
+    assert_eq!(colors, vec![
+        vec![Value::from(Color::rgba(255, 0, 0, 255))],
+        vec![Value::from(Color::rgba(0, 128, 0, 255))],
+        vec![Value::from(Color::rgba(0, 0, 255, 255))],
+    ]);
+}
+
+#[test]
+fn url_schemes() {
+    let mut stylesheet = StyleSheet::new(PathBuf::from("/a/b/style.css"));
+    let result = stylesheet.parse_string("
+        data { background-image: url(data:image/png;base64,AAA); }
+        remote { background-image: url(\"https://example.org/x.png\"); }
+        relative { background-image: url(images/x.png); }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty(), "{:?}", stylesheet.errors);
+
+    let urls: Vec<_> = stylesheet.iter_rules().map(|rule| rule.properties[0].values[0].clone()).collect();
+    assert_eq!(urls, vec![
+        Value::new_url("data:image/png;base64,AAA"),
+        Value::new_url("https://example.org/x.png"),
+        Value::new_url("/a/b/images/x.png"),
+    ]);
+}
+
+#[test]
+fn font_faces() {
+    let mut stylesheet = StyleSheet::new(PathBuf::from("/themes/default/style.css"));
+    let result = stylesheet.parse_string(r#"
+        @font-face {
+            font-family: "Noto Sans";
+            src: url(fonts/NotoSans.woff2) format("woff2"), url("fonts/NotoSans.ttf") format(truetype), url(fallback.otf);
+            font-weight: 400;
+            unicode-range: U+0000-00FF, U+0131, U+4??;
+        }
+        @font-face { font-family: Hack Mono; src: url(hack.ttf); }
+        @font-face { font-family: Remote; src: url("data:font/woff2;base64,AAAA") format("woff2"), url(https://example.org/remote.ttf); }
+        @font-face { src: url(nameless.ttf); }
+        button { @font-face { font-family: Nested; } }
+    "#);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.errors.len(), 2);
+
+    let font_faces = stylesheet.all_font_faces();
+    assert_eq!(font_faces, vec![
+        FontFace {
+            family: String::from("Noto Sans"),
+            sources: vec![
+                FontFaceSource { url: String::from("/themes/default/fonts/NotoSans.woff2"), format: Some(String::from("woff2")) },
+                FontFaceSource { url: String::from("/themes/default/fonts/NotoSans.ttf"), format: Some(String::from("truetype")) },
+                FontFaceSource { url: String::from("/themes/default/fallback.otf"), format: None },
+            ],
+            unicode_ranges: vec![(0x0, 0xFF), (0x131, 0x131), (0x400, 0x4FF)],
+        },
+        FontFace {
+            family: String::from("Hack Mono"),
+            sources: vec![FontFaceSource { url: String::from("/themes/default/hack.ttf"), format: None }],
+            unicode_ranges: Vec::new(),
+        },
+        FontFace {
+            family: String::from("Remote"),
+            sources: vec![
+                FontFaceSource { url: String::from("data:font/woff2;base64,AAAA"), format: Some(String::from("woff2")) },
+                FontFaceSource { url: String::from("https://example.org/remote.ttf"), format: None },
+            ],
+            unicode_ranges: Vec::new(),
+        },
+    ]);
+}
+
+#[test]
+fn font_face_formats() {
+    let mut stylesheet = StyleSheet::new(PathBuf::from("/fonts/style.css"));
+    let result = stylesheet.parse_string(r#"
+        @font-face { font-family: Test; src: url(x.woff2) FORMAT("woff2"), url(x.ttf) format("truetype"), url(x.future) format("x-Future-Format"); }
+    "#);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty());
+
+    let font_faces = stylesheet.all_font_faces();
+    assert_eq!(font_faces.len(), 1);
+    let formats: Vec<_> = font_faces[0].sources.iter().map(|source| (source.url.as_str(), source.format.as_deref())).collect();
+    assert_eq!(formats, vec![
+        ("/fonts/x.woff2", Some("woff2")),
+        ("/fonts/x.ttf", Some("truetype")),
+        ("/fonts/x.future", Some("x-Future-Format")),
+    ]);
+}
+
+#[test]
+fn resolved_variables() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("
+        :root {
+            --resolved-base: #ff0000;
+            --resolved-alias: var(--resolved-base);
+            --resolved-gap: 4px var(--resolved-undefined, 8px);
+        }
+        first { --resolved-cycle-a: var(--resolved-cycle-b); }
+        second { --resolved-cycle-b: var(--resolved-cycle-a); }
+        button { --resolved-base: blue; --resolved-nested: var(--resolved-alias); }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.errors.len(), 2);
+
+    // The later declaration of --resolved-base replaces the earlier one.
+    let blue = vec![Value::from(Color::rgba(0, 0, 255, 255))];
+    let (variables, errors) = stylesheet.resolved_variables();
+    assert_eq!(variables, vec![
+        (String::from("--resolved-base"), blue.clone()),
+        (String::from("--resolved-alias"), blue.clone()),
+        (String::from("--resolved-gap"), vec![Value::from(Dimension::px(4.0)), Value::from(Dimension::px(8.0))]),
+        (String::from("--resolved-nested"), blue.clone()),
+    ]);
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].kind, ParseErrorKind::InvalidPropertyValue);
+    assert_eq!(errors[0].message, "Cyclic reference between custom properties --resolved-cycle-a -> --resolved-cycle-b -> --resolved-cycle-a");
+    assert_eq!(errors[0].location.line, 7);
+    assert_eq!(errors[1].message, "Cyclic reference between custom properties --resolved-cycle-b -> --resolved-cycle-a -> --resolved-cycle-b");
+}
+
+#[test]
+fn resolved_variables_per_sheet() {
+    // Both sheets declare the same custom property, each resolves to its own value.
+    let mut first = StyleSheet::new(PathBuf::new());
+    let result = first.parse_string(":root { --per-sheet-color: red; --per-sheet-alias: var(--per-sheet-color); }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let mut second = StyleSheet::new(PathBuf::new());
+    let result = second.parse_string(":root { --per-sheet-color: blue; --per-sheet-alias: var(--per-sheet-color, green); }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    let red = vec![Value::from(Color::rgba(255, 0, 0, 255))];
+    let blue = vec![Value::from(Color::rgba(0, 0, 255, 255))];
+    assert_eq!(first.resolved_variables().0, vec![
+        (String::from("--per-sheet-color"), red.clone()),
+        (String::from("--per-sheet-alias"), red.clone()),
+    ]);
+    assert_eq!(second.resolved_variables().0, vec![
+        (String::from("--per-sheet-color"), blue.clone()),
+        (String::from("--per-sheet-alias"), blue.clone()),
+    ]);
+
+    // A self reference is a cycle too, and a missing property without fallback is an error.
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("first { --per-sheet-self: 1px var(--per-sheet-self); } second { --per-sheet-missing: var(--per-sheet-undefined); }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    let (variables, errors) = stylesheet.resolved_variables();
+    assert!(variables.is_empty());
+    assert_eq!(errors.iter().map(|error| error.kind.clone()).collect::<Vec<_>>(), vec![ParseErrorKind::InvalidPropertyValue, ParseErrorKind::UnknownProperty]);
+    assert_eq!(errors[1].message, "No custom property --per-sheet-undefined was defined");
+}
+
+#[test]
+fn shorthands() {
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string("
+        one { inset: 1px; margin: 1px; padding: 1px; }
+        two { inset: 1px 2px; margin: 1px 2px; padding: 1px 2px; }
+        three { inset: 1px 2px 3px; margin: 1px 2px 3px; padding: 1px 2px 3px; }
+        four { inset: 1px 2px 3px 4px; margin: 1px 2px 3px 4px; padding: 1px 2px 3px 4px !important; }
+        five { padding: 1px 2px 3px 4px 5px; width: 1px; }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.errors.is_empty());
+
+    let expected: [&[f32; 4]; 4] = [&[1.0, 1.0, 1.0, 1.0], &[1.0, 2.0, 1.0, 2.0], &[1.0, 2.0, 3.0, 2.0], &[1.0, 2.0, 3.0, 4.0]];
+    let longhands = [
+        ["top", "right", "bottom", "left"],
+        ["margin-top", "margin-right", "margin-bottom", "margin-left"],
+        ["padding-top", "padding-right", "padding-bottom", "padding-left"],
+    ];
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    for (rule, values) in rules.iter().zip(expected) {
+        for (property, names) in rule.properties.iter().zip(longhands) {
+            let expanded = property.longhands().unwrap();
+            let expanded_names: Vec<_> = expanded.iter().map(|longhand| longhand.name.as_str()).collect();
+            assert_eq!(expanded_names, names);
+
+            for (longhand, value) in expanded.iter().zip(values) {
+                assert_eq!(longhand.values, vec![Value::from(Dimension::px(*value))]);
+                assert_eq!(longhand.important, property.important);
+                assert_eq!(longhand.definition, property_definition(&longhand.name).unwrap());
+                assert_eq!(property.longhand_value(&longhand.name), Some(longhand.values.clone()));
+            }
+        }
+    }
+
+    assert_eq!(rules[1].properties[2].longhand_value("padding-left"), Some(vec![Value::from(Dimension::px(2.0))]));
+    assert!(rules[1].properties[2].longhand_value("margin-left").is_none());
+    assert!(rules[4].properties[0].longhands().is_none());
+    assert!(rules[4].properties[0].longhand_value("padding-left").is_none());
+    assert!(rules[4].properties[1].longhands().is_none());
+    assert!(rules[4].properties[1].longhand_value("width").is_none());
+}
+
+#[test]
+fn import_missing_file() {
+    let mut stylesheet = StyleSheet::new(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/main.css")));
+    let result = stylesheet.parse_string("button { color: red; }\n\n  @import \"missing.css\";");
+    let error = result.unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::FileError);
+    assert!(error.message.starts_with("Could not import missing.css: "), "Unexpected message: {}", error.message);
+    assert_eq!(error.location, SourceLocation {
+        file: String::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/main.css")),
+        line: 3,
+        column: 3,
+    });
+}
+
+#[test]
+fn parse_reader_and_bytes() {
+    let mut stylesheet = StyleSheet::new(PathBuf::from("resource.css"));
+    let result = stylesheet.parse_reader("first { color: red; }".as_bytes());
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    let result = stylesheet.parse_bytes(b"second { color: blue; }\nthird { unknown-property: 1; }");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    assert_eq!(stylesheet.rule_count(), 2);
+    assert_eq!(stylesheet.errors.len(), 1);
+    assert_eq!((stylesheet.errors[0].location.file.as_str(), stylesheet.errors[0].location.line), ("resource.css", 2));
+
+    let error = stylesheet.parse_bytes(b"first { color: \xff; }").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::FileError);
+    assert_eq!(error.location.file, "resource.css");
+    assert_eq!(stylesheet.rule_count(), 2);
+}
+
+#[test]
+fn parse_stats() {
+    let source = "first { width: 10px; } second { unknown-property: 1; } third { color: red; }";
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    let result = stylesheet.parse_string(source);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.all_parse_stats().is_empty());
+
+    let mut stylesheet = StyleSheet::new(PathBuf::new());
+    stylesheet.record_stats = true;
+    let result = stylesheet.parse_string(source);
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    let stats = stylesheet.all_parse_stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].rules, 2);
+    assert_eq!(stats[0].errors, 1);
+    assert_eq!(stats[0].bytes, source.len());
+
+    // Imported sheets record their own statistics.
+    let mut stylesheet = StyleSheet::new(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/import.css")));
+    stylesheet.record_stats = true;
+    let result = stylesheet.parse();
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    let paths: Vec<_> = stylesheet.all_parse_stats().into_iter().map(|stats| stats.path).collect();
+    assert_eq!(paths, stylesheet.all_paths());
+}
+
+#[test]
+fn background_layers() {
+    let layers = parse_background("url(image.svg) no-repeat center / cover").unwrap();
+    assert_eq!(layers, vec![BackgroundLayer {
+        image: Some(String::from("image.svg")),
+        repeat: vec![String::from("no-repeat")],
+        position: vec![Value::new_keyword("center")],
+        size: vec![Value::new_keyword("cover")],
+        color: None,
+        ..Default::default()
+    }]);
+
+    let layers = parse_background("url(top.png) repeat-x left 10px, none 50% 0 / 20px auto, #ff0000").unwrap();
+    assert_eq!(layers.len(), 3);
+    assert_eq!(layers[0].image.as_deref(), Some("top.png"));
+    assert_eq!(layers[0].position, vec![Value::new_keyword("left"), Dimension::px(10.0).into()]);
+    assert_eq!(layers[1].image, None);
+    assert_eq!(layers[1].position, vec![Dimension { value: 0.5, unit: Unit::Percent }.into(), Value::from(0)]);
+    assert_eq!(layers[1].size, vec![Dimension::px(20.0).into(), Value::new_keyword("auto")]);
+    assert_eq!(layers[2].image, None);
+    assert_eq!(layers[2].color, Some(Color::rgba(255, 0, 0, 255)));
+
+    let error = parse_background("#ff0000, url(image.svg)").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidPropertyValue);
+    assert_eq!(error.message, "Only the final background layer can have a color, but layer 1 has one");
+
+    let layers = parse_background("url(image.svg) fixed").unwrap();
+    assert_eq!(layers[0].attachment.as_deref(), Some("fixed"));
+    assert_eq!(layers[0].origin, None);
+    assert_eq!(layers[0].clip, None);
+
+    let layers = parse_background("padding-box url(a.png), content-box border-box local #00ff00").unwrap();
+    assert_eq!(layers[0].origin.as_deref(), Some("padding-box"));
+    assert_eq!(layers[0].clip.as_deref(), Some("padding-box"));
+    assert_eq!(layers[1].attachment.as_deref(), Some("local"));
+    assert_eq!(layers[1].origin.as_deref(), Some("content-box"));
+    assert_eq!(layers[1].clip.as_deref(), Some("border-box"));
+
+    let error = parse_background("url(image.svg) fixed scroll").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidPropertyValue);
+
+    assert!(parse_background("center /").is_err());
+
+    let layers = parse_background("URL(x.png) NO-REPEAT Center / COVER Fixed").unwrap();
+    assert_eq!(layers[0].image.as_deref(), Some("x.png"));
+    assert_eq!(layers[0].repeat, vec![String::from("no-repeat")]);
+    assert_eq!(layers[0].position, vec![Value::new_keyword("center")]);
+    assert_eq!(layers[0].size, vec![Value::new_keyword("cover")]);
+    assert_eq!(layers[0].attachment.as_deref(), Some("fixed"));
+
+    let layers = parse_background("url(x.png) right 10px bottom 20px / 50% auto").unwrap();
+    assert_eq!(layers[0].position.len(), 4);
+    assert_eq!(layers[0].size.len(), 2);
+
+    // Positions have at most four values, which need to be adjacent.
+    assert!(parse_background("url(x) 10px no-repeat 20px 30px 40px 50px 60px").is_err());
+    assert!(parse_background("url(x) 10px no-repeat 20px").is_err());
+    assert!(parse_background("left 10px top 20px 30px").is_err());
+    assert!(parse_background("center / 10px 20px 30px").is_err());
+    assert!(parse_background("center / cover 10px").is_err());
+    assert!(parse_background("center / 10px contain").is_err());
+}
+
+#[test]
+fn background_declaration() {
+    let mut stylesheet = StyleSheet::new(PathBuf::from("/style.css"));
+    let result = stylesheet.parse_string("
+        first { background: url(x.svg) no-repeat center / cover; }
+        second { background: url(a.png), #ff0000 !important; }
+        third { background: #ff0000, url(a.png); }
+    ");
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+
+    assert_eq!(stylesheet.errors.len(), 1);
+    assert_eq!(stylesheet.errors[0].kind, ParseErrorKind::InvalidPropertyValue);
+    assert!(stylesheet.errors[0].message.contains("Only the final background layer can have a color"));
+
+    let rules: Vec<_> = stylesheet.iter_rules().cloned().collect();
+    assert_eq!(rules[0].properties[0].name, "background");
+    assert_eq!(rules[0].properties[0].values, vec![
+        Value::new_url("/x.svg"),
+        Value::new_keyword("no-repeat"),
+        Value::new_keyword("center"),
+        Value::new_slash(),
+        Value::new_keyword("cover"),
+    ]);
+    assert!(rules[1].properties[0].important);
+    assert_eq!(rules[1].properties[0].values.len(), 2);
+}
+
+#[test]
+fn max_import_depth() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/import_chain_1.css");
+
+    let mut stylesheet = StyleSheet::new(PathBuf::from(path));
+    let result = stylesheet.parse();
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert!(stylesheet.all_errors().is_empty());
+    assert_eq!(stylesheet.all_rules().len(), 3);
+
+    let mut stylesheet = StyleSheet::new(PathBuf::from(path));
+    stylesheet.max_import_depth = 1;
+    let result = stylesheet.parse();
+    assert!(result.is_ok(), "Parsing stylesheet failed with error: {}", result.err().unwrap().to_string());
+    assert_eq!(stylesheet.all_rules().len(), 2);
+
+    let errors = stylesheet.all_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, ParseErrorKind::InvalidAtRule);
+    assert_eq!(errors[0].message, "Import of import_chain_3.css exceeds the maximum import depth of 1");
+    assert_eq!(errors[0].location, SourceLocation {
+        file: String::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/import_chain_2.css")),
+        line: 6,
+        column: 1,
+    });
+}
+
+#[test]
+fn grid_track_list() {
+    let fr = |value| GridTrack::Breadth(Dimension { value, unit: Unit::Fr });
+
+    let tracks = parse_track_list("1fr 2fr minmax(100px, 1fr)").unwrap();
+    assert_eq!(tracks, vec![
+        fr(1.0),
+        fr(2.0),
+        GridTrack::MinMax { min: Box::new(GridTrack::Breadth(Dimension::px(100.0))), max: Box::new(fr(1.0)) },
+    ]);
+
+    let tracks = parse_track_list("200px 25% auto MIN-CONTENT fit-content(50%) minmax(0, max-content)").unwrap();
+    assert_eq!(tracks, vec![
+        GridTrack::Breadth(Dimension::px(200.0)),
+        GridTrack::Breadth(Dimension { value: 0.25, unit: Unit::Percent }),
+        GridTrack::Keyword(String::from("auto")),
+        GridTrack::Keyword(String::from("min-content")),
+        GridTrack::FitContent(Dimension { value: 0.5, unit: Unit::Percent }),
+        GridTrack::MinMax { min: Box::new(GridTrack::Breadth(Dimension::px(0.0))), max: Box::new(GridTrack::Keyword(String::from("max-content"))) },
+    ]);
+
+    let error = parse_track_list("minmax(1fr, 100px)").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidPropertyValue);
+    assert_eq!(error.message, "The minimum of minmax() cannot be a flexible length");
+
+    let error = parse_track_list("repeat(3, 1fr)").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::Unimplemented);
+
+    assert!(parse_track_list("").is_err());
+    assert!(parse_track_list("1fr 10deg").is_err());
+    assert!(parse_track_list("-1fr").is_err());
+    assert!(parse_track_list("fit-content(1fr)").is_err());
+}